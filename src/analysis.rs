@@ -0,0 +1,327 @@
+//! Analyses, which combine a finished ```PathMatrix``` with the graph it was computed for.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use PathMatrix;
+
+/// Describes how much the distances in a graph degrade, if a single node or edge is removed from it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vitality {
+    /// The increase of the sum of all distances between pairs, which stay connected after the removal.
+    pub sum_increase: usize,
+    /// The largest increase of a single distance between a pair, which stays connected after the removal.
+    pub max_increase: usize,
+    /// The number of pairs, which were connected before the removal and are not anymore.
+    pub disconnected_pairs: usize,
+}
+
+/// An adjacency list of a graph, which is undirected unless built by ```Adjacency::directed```.
+/// Every entry is a triple of (neighbour, weight, edge position), where the edge position is the position of the edge in ```edge_references()```.
+pub(crate) struct Adjacency {
+    adj: Vec<Vec<(usize, usize, usize)>>,
+    edges: Vec<(usize, usize, usize)>,
+}
+
+impl Adjacency {
+    /// Builds the adjacency list for the given graph, where every edge can be used in both directions.
+    pub(crate) fn new<G>(g: G) -> Adjacency
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        Adjacency::directed(g, false)
+    }
+
+    /// Builds the adjacency list for the given graph, where every edge can only be used from its source to its target, if ```directed``` is set.
+    pub(crate) fn directed<G>(g: G, directed: bool) -> Adjacency
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        let mut adj = vec![Vec::new(); g.node_count()];
+        let mut edges = Vec::new();
+
+        for (pos, e) in g.edge_references().enumerate() {
+            let u = e.source().index();
+            let v = e.target().index();
+            let w: usize = e.weight().clone().into();
+
+            adj[u].push((v, w, pos));
+            if !directed {
+                adj[v].push((u, w, pos));
+            }
+            edges.push((u, v, w));
+        }
+
+        Adjacency { adj, edges }
+    }
+
+    /// Returns the neighbours of ```u``` as (neighbour, weight, edge position) triples.
+    pub(crate) fn neighbours(&self, u: usize) -> &[(usize, usize, usize)] {
+        &self.adj[u]
+    }
+
+    /// Returns the edges as (source, target, weight) triples, in the order of ```edge_references()```.
+    pub(crate) fn edges(&self) -> &[(usize, usize, usize)] {
+        &self.edges
+    }
+
+    /// Maps every node pair, which is stored as a single edge in the matrix, to the position of the edge carrying it.
+    /// If there are parallel edges, only the one with the stored length is used.
+    pub(crate) fn stored_edges<T>(&self, m: &PathMatrix<T>) -> HashMap<(usize, usize), usize> {
+        let mut stored = HashMap::new();
+        for (pos, &(u, v, w)) in self.edges.iter().enumerate() {
+            let p = m.entry(u, v);
+            if u != v && p.via().is_none() && p.exists() && p.len() == w {
+                stored.entry(edge_key(m, u, v)).or_insert(pos);
+            }
+        }
+        stored
+    }
+
+    /// Computes the distances from ```i``` with the node ```skip_node``` or the edge at position ```skip_edge``` removed, and stores them in ```dist```.
+    /// Only the nodes, to which some shortest path runs over the removed element, can change: The stored paths to all other nodes
+    /// don't use it, so their lengths are used as exact starting labels. This holds for the nodes before and after ```i``` alike.
+    pub(crate) fn detours<T>(
+        &self,
+        m: &PathMatrix<T>,
+        i: usize,
+        skip_node: Option<usize>,
+        skip_edge: Option<usize>,
+        dist: &mut [Option<usize>],
+    ) {
+        // The length of the shortest path from i to j over the removed element, if there is one.
+        let over = |j: usize| -> Option<usize> {
+            let via = |a: usize, w: usize, b: usize| {
+                m.distance(i, a)?.checked_add(w)?.checked_add(m.distance(b, j)?)
+            };
+            match (skip_node, skip_edge) {
+                (Some(k), _) => via(k, 0, k),
+                (None, Some(pos)) => {
+                    let (u, v, w) = self.edges[pos];
+                    via(u, w, v).into_iter().chain(via(v, w, u)).min()
+                }
+                (None, None) => None,
+            }
+        };
+
+        for (j, d) in dist.iter_mut().enumerate() {
+            *d = if i == j {
+                Some(0)
+            } else if Some(j) == skip_node || !m.does_path_exist(i, j) {
+                None
+            } else {
+                let len = m.get_path_len(i, j);
+                if over(j) == Some(len) {
+                    None
+                } else {
+                    Some(len)
+                }
+            };
+        }
+
+        self.seeded_dijkstra(dist, skip_node, skip_edge);
+    }
+
+    /// Computes the distances from ```i``` to all nodes by Dijkstra's algorithm and stores them in ```dist```.
+    pub(crate) fn distances_from(&self, i: usize, dist: &mut [Option<usize>]) {
+        for d in dist.iter_mut() {
+            *d = None;
+        }
+        dist[i] = Some(0);
+
+        self.seeded_dijkstra(dist, None, None);
+    }
+
+    /// Runs Dijkstra's algorithm, where ```dist``` already contains some exact distances from the source.
+    /// These are used as starting labels, so only the remaining entries have to be computed.
+    /// The node ```skip_node``` and the edge at position ```skip_edge``` are treated as if they were removed from the graph.
+    fn seeded_dijkstra(
+        &self,
+        dist: &mut [Option<usize>],
+        skip_node: Option<usize>,
+        skip_edge: Option<usize>,
+    ) {
+        let mut heap = BinaryHeap::new();
+        for (u, d) in dist.iter().enumerate() {
+            if let Some(d) = *d {
+                heap.push(Reverse((d, u)));
+            }
+        }
+
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if dist[u].is_some_and(|du| du < d) {
+                continue;
+            }
+
+            for &(v, w, pos) in &self.adj[u] {
+                if Some(v) == skip_node || Some(pos) == skip_edge {
+                    continue;
+                }
+
+                let nd = d.saturating_add(w);
+                if dist[v].is_none_or(|dv| nd < dv) {
+                    dist[v] = Some(nd);
+                    heap.push(Reverse((nd, v)));
+                }
+            }
+        }
+    }
+}
+
+/// Appends the intermediate nodes of the stored path between ```i``` and ```j``` to ```out```.
+pub(crate) fn intermediate_nodes<T>(m: &PathMatrix<T>, i: usize, j: usize, out: &mut Vec<usize>) {
+    if let Some(k) = m.entry(i, j).via() {
+        intermediate_nodes(m, i, k, out);
+        out.push(k);
+        intermediate_nodes(m, k, j, out);
+    }
+}
+
+/// Returns the key of the edge from ```u``` to ```v``` in the matrix, which is the ordered pair in a directed matrix, and the smaller node first otherwise.
+#[inline]
+pub(crate) fn edge_key<T>(m: &PathMatrix<T>, u: usize, v: usize) -> (usize, usize) {
+    if m.is_directed() {
+        (u, v)
+    } else {
+        (u.min(v), u.max(v))
+    }
+}
+
+/// Appends the edges (as node pairs keyed by ```edge_key```) of the stored path between ```i``` and ```j``` to ```out```.
+pub(crate) fn path_edges<T>(m: &PathMatrix<T>, i: usize, j: usize, out: &mut Vec<(usize, usize)>) {
+    match m.entry(i, j).via() {
+        None => out.push(edge_key(m, i, j)),
+        Some(k) => {
+            path_edges(m, i, k, out);
+            path_edges(m, k, j, out);
+        }
+    }
+}
+
+/// Recomputes the given affected pairs with the node ```skip_node``` or the edge ```skip_edge``` removed, and sums up the degradation.
+fn degradation<T>(
+    m: &PathMatrix<T>,
+    adj: &Adjacency,
+    affected: &[(usize, usize)],
+    skip_node: Option<usize>,
+    skip_edge: Option<usize>,
+) -> Vitality {
+    let n = m.node_count();
+    let mut vitality = Vitality::default();
+
+    // Group the affected pairs by their first node, so that a single search per source suffices.
+    let mut by_source: HashMap<usize, Vec<usize>> = HashMap::new();
+    for &(i, j) in affected {
+        by_source.entry(i).or_default().push(j);
+    }
+
+    let mut dist = vec![None; n];
+    for (i, targets) in by_source {
+        adj.detours(m, i, skip_node, skip_edge, &mut dist);
+
+        for j in targets {
+            match dist[j] {
+                Some(d) => {
+                    let increase = d - m.get_path_len(i, j);
+                    vitality.sum_increase += increase;
+                    vitality.max_increase = vitality.max_increase.max(increase);
+                }
+                None => vitality.disconnected_pairs += 1,
+            }
+        }
+    }
+
+    vitality
+}
+
+/// Computes for every node, how much the distances between the other nodes degrade, if that node is removed from the graph.
+/// The result is indexed by node index. Pairs, which contain the removed node itself, are not taken into account.
+///
+/// Only the pairs, whose stored shortest path runs over the removed node, are recomputed by a single-source search,
+/// instead of running the whole algorithm once per node.
+///
+/// # Panics
+///
+/// The searches treat every edge as undirected, so this panics for the matrix of a directed graph.
+pub fn node_vitality<G, T>(g: G, m: &PathMatrix<T>) -> Vec<Vitality>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    assert!(!m.is_directed(), "the vitality of a directed graph isn't supported");
+    let n = m.node_count();
+    let adj = Adjacency::new(g);
+
+    let mut affected = vec![Vec::new(); n];
+    let mut nodes = Vec::new();
+    for (i, j) in m.connected_pairs() {
+        nodes.clear();
+        intermediate_nodes(m, i, j, &mut nodes);
+        for &k in &nodes {
+            affected[k].push((i, j));
+        }
+    }
+
+    affected
+        .iter()
+        .enumerate()
+        .map(|(k, pairs)| degradation(m, &adj, pairs, Some(k), None))
+        .collect()
+}
+
+/// Computes for every edge, how much the distances in the graph degrade, if that edge is removed from the graph.
+/// The result contains one entry per edge in the order of ```edge_references()```.
+///
+/// Only the pairs, whose stored shortest path uses the removed edge, are recomputed by a single-source search,
+/// instead of running the whole algorithm once per edge.
+///
+/// # Panics
+///
+/// The searches treat every edge as undirected, so this panics for the matrix of a directed graph.
+pub fn edge_vitality<G, T>(g: G, m: &PathMatrix<T>) -> Vec<(G::EdgeId, Vitality)>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    assert!(!m.is_directed(), "the vitality of a directed graph isn't supported");
+    let adj = Adjacency::new(g);
+
+    let mut affected: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    let mut edges = Vec::new();
+    for (i, j) in m.connected_pairs() {
+        edges.clear();
+        path_edges(m, i, j, &mut edges);
+        for &e in &edges {
+            affected.entry(e).or_default().push((i, j));
+        }
+    }
+
+    let stored = adj.stored_edges(m);
+    let none = Vec::new();
+    g.edge_references()
+        .enumerate()
+        .map(|(pos, e)| {
+            let (u, v, _) = adj.edges()[pos];
+            let key = edge_key(m, u, v);
+
+            // If there are parallel edges, only the one, which is stored in the matrix, carries any paths.
+            let pairs = if stored.get(&key) == Some(&pos) {
+                affected.get(&key).unwrap_or(&none)
+            } else {
+                &none
+            };
+
+            (e.id(), degradation(m, &adj, pairs, None, Some(pos)))
+        })
+        .collect()
+}
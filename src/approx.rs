@@ -0,0 +1,231 @@
+//! Approximation algorithms for hard problems on the metric of a graph.
+
+use std::collections::HashSet;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use analysis::path_edges;
+use analysis::Adjacency;
+use Distances;
+use PathMatrix;
+
+/// A tree in a graph, which connects a set of terminal nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SteinerTree<E> {
+    /// The edges of the tree.
+    pub edges: Vec<E>,
+    /// The total weight of the edges.
+    pub weight: usize,
+}
+
+/// A closed tour through a set of nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tour {
+    /// The nodes in the order they are visited. The tour returns from the last node to the first one.
+    pub order: Vec<usize>,
+    /// The total length of the tour including the way back to the first node.
+    pub length: usize,
+}
+
+/// Finds a minimum spanning tree of the complete graph on ```nodes``` with the weights given by ```weight``` using Prim's algorithm.
+/// Returns the tree edges as pairs of positions in ```nodes```, or ```None```, if the nodes can't be connected.
+pub(crate) fn prim<F>(nodes: usize, weight: F) -> Option<Vec<(usize, usize)>>
+where
+    F: Fn(usize, usize) -> Option<usize>,
+{
+    let mut in_tree = vec![false; nodes];
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; nodes];
+    let mut edges = Vec::new();
+
+    let mut u = 0;
+    for _ in 0..nodes {
+        in_tree[u] = true;
+        if let Some((_, from)) = best[u] {
+            edges.push((from, u));
+        }
+
+        for v in (0..nodes).filter(|&v| !in_tree[v]) {
+            if let Some(w) = weight(u, v) {
+                if best[v].is_none_or(|(b, _)| w < b) {
+                    best[v] = Some((w, u));
+                }
+            }
+        }
+
+        match (0..nodes)
+            .filter(|&v| !in_tree[v])
+            .filter_map(|v| best[v].map(|(w, _)| (w, v)))
+            .min()
+        {
+            Some((_, v)) => u = v,
+            None => break,
+        }
+    }
+
+    if edges.len() + 1 < nodes {
+        None
+    } else {
+        Some(edges)
+    }
+}
+
+/// Computes a Steiner tree, which connects all ```terminals```, and whose weight is at most twice the optimum.
+/// If the terminals are not connected in the graph, ```None``` is returned.
+///
+/// This is the algorithm by Kou, Markowsky and Berman: A minimum spanning tree of the metric closure on the terminals is built
+/// and each of its edges is replaced by the stored shortest path. Then a minimum spanning tree of the resulting subgraph is taken,
+/// and non-terminal leaves are removed.
+pub fn steiner_tree<G, T>(
+    g: G,
+    m: &PathMatrix<T>,
+    terminals: &[usize],
+) -> Option<SteinerTree<G::EdgeId>>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let adj = Adjacency::new(g);
+    let stored = adj.stored_edges(m);
+    let ids: Vec<G::EdgeId> = g.edge_references().map(|e| e.id()).collect();
+
+    // A repeated terminal would give a closure edge from a node to itself.
+    let mut terminals = terminals.to_vec();
+    terminals.sort_unstable();
+    terminals.dedup();
+
+    // Minimum spanning tree of the metric closure on the terminals.
+    let closure_tree = prim(terminals.len(), |a, b| {
+        m.distance(terminals[a], terminals[b])
+    })?;
+
+    // Replace every closure edge by the stored path in the graph.
+    let mut used = HashSet::new();
+    let mut path = Vec::new();
+    for (a, b) in closure_tree {
+        path.clear();
+        path_edges(m, terminals[a], terminals[b], &mut path);
+        used.extend(path.iter().map(|e| stored[e]));
+    }
+
+    // The union of the paths may contain cycles, so take a minimum spanning tree of it by Kruskal's algorithm.
+    let mut candidates: Vec<usize> = used.into_iter().collect();
+    candidates.sort_by_key(|&pos| (adj.edges()[pos].2, pos));
+
+    let mut parent: Vec<usize> = (0..m.node_count()).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    let mut tree: Vec<usize> = Vec::new();
+    for pos in candidates {
+        let (u, v, _) = adj.edges()[pos];
+        let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+        if ru != rv {
+            parent[ru] = rv;
+            tree.push(pos);
+        }
+    }
+
+    // Repeatedly remove leaves, which are not terminals.
+    let is_terminal: HashSet<usize> = terminals.iter().cloned().collect();
+    loop {
+        let mut degree = vec![0; m.node_count()];
+        for &pos in &tree {
+            let (u, v, _) = adj.edges()[pos];
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+
+        let before = tree.len();
+        tree.retain(|&pos| {
+            let (u, v, _) = adj.edges()[pos];
+            let leaf = |x: usize| degree[x] == 1 && !is_terminal.contains(&x);
+            !leaf(u) && !leaf(v)
+        });
+
+        if tree.len() == before {
+            break;
+        }
+    }
+
+    let weight = tree.iter().map(|&pos| adj.edges()[pos].2).sum();
+    let edges = tree.into_iter().map(|pos| ids[pos]).collect();
+
+    Some(SteinerTree { edges, weight })
+}
+
+/// Builds the ```Tour``` for the given order, or returns ```None```, if two consecutive nodes are not connected.
+fn tour<D: Distances>(d: &D, order: Vec<usize>) -> Option<Tour> {
+    let mut length = 0usize;
+    for (p, &u) in order.iter().enumerate() {
+        let v = order[(p + 1) % order.len()];
+        length = length.saturating_add(d.distance(u, v)?);
+    }
+
+    Some(Tour { order, length })
+}
+
+/// Computes a tour through all ```nodes``` by shortcutting a preorder walk of their minimum spanning tree.
+/// Because shortest path distances fulfill the triangle inequality, the tour is at most twice as long as the optimal one.
+/// The tour starts at ```nodes[0]```. If the nodes are not connected, ```None``` is returned.
+pub fn tsp_apx<D: Distances>(d: &D, nodes: &[usize]) -> Option<Tour> {
+    if nodes.is_empty() {
+        return Some(Tour {
+            order: Vec::new(),
+            length: 0,
+        });
+    }
+
+    let edges = prim(nodes.len(), |a, b| d.distance(nodes[a], nodes[b]))?;
+
+    let mut children = vec![Vec::new(); nodes.len()];
+    for (from, to) in edges {
+        children[from].push(to);
+    }
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut stack = vec![0];
+    while let Some(u) = stack.pop() {
+        order.push(nodes[u]);
+        stack.extend(children[u].iter().rev());
+    }
+
+    tour(d, order)
+}
+
+/// Computes a tour through all ```nodes``` by always going to the closest node, which wasn't visited yet.
+/// This has no constant approximation guarantee, but is often shorter than ```tsp_apx``` in practice.
+/// The tour starts at ```nodes[0]```. If the nodes are not connected, ```None``` is returned.
+pub fn tsp_nearest_neighbor<D: Distances>(d: &D, nodes: &[usize]) -> Option<Tour> {
+    let mut left: Vec<usize> = nodes.iter().skip(1).cloned().collect();
+    let mut order: Vec<usize> = nodes.iter().take(1).cloned().collect();
+
+    while let Some(&u) = order.last() {
+        let next = left
+            .iter()
+            .enumerate()
+            .filter_map(|(p, &v)| d.distance(u, v).map(|dist| (dist, p)))
+            .min();
+
+        match next {
+            Some((_, p)) => order.push(left.swap_remove(p)),
+            None if left.is_empty() => break,
+            None => return None,
+        }
+    }
+
+    if order.is_empty() {
+        return Some(Tour { order, length: 0 });
+    }
+
+    tour(d, order)
+}
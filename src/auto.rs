@@ -0,0 +1,144 @@
+//! A front-end, which chooses the fastest of the algorithms for a graph.
+
+use std::fmt;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use apsp_dijkstra;
+use apsp_unweighted;
+use floyd_warshall;
+use PathMatrix;
+
+/// Up to this number of nodes, the dynamic program is always used, because it has the least overhead.
+const SMALL: usize = 64;
+
+/// The algorithm, which computed the matrix returned by ```apsp```.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// The dynamic program of ```floyd_warshall```, which is used for small and dense graphs.
+    FloydWarshall,
+    /// Dijkstra's algorithm from every node like ```apsp_dijkstra```, which is used for sparse graphs.
+    /// This is Johnson's algorithm, which doesn't need to reweight the edges, because they can't be negative.
+    Dijkstra,
+    /// A breadth-first search from every node like ```apsp_unweighted```, which is used, if all edges have the same weight.
+    Bfs,
+}
+
+/// Formats the strategy as the name of its algorithm, like ```Floyd-Warshall```.
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            Strategy::FloydWarshall => "Floyd-Warshall",
+            Strategy::Dijkstra => "Dijkstra",
+            Strategy::Bfs => "breadth-first search",
+        })
+    }
+}
+
+/// The result of ```apsp```: The computed matrix together with the algorithm, which computed it.
+#[derive(Debug)]
+pub struct Apsp<T> {
+    /// The shortest paths between every two nodes.
+    pub matrix: PathMatrix<T>,
+    /// The algorithm, which was chosen for the graph.
+    pub strategy: Strategy,
+}
+
+/// Returns the weight of all edges, which aren't loops, if they all have the same one, or ```None``` otherwise.
+fn uniform_weight<G>(g: G) -> Option<usize>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut weights = g
+        .edge_references()
+        .filter(|e| e.source() != e.target())
+        .map(|e| e.weight().clone().into());
+    let first = weights.next()?;
+    if weights.all(|w: usize| w == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+/// Returns the algorithm, which ```apsp``` would use for the graph.
+///
+/// If all edges have the same weight, a breadth-first search from every node is used, which takes **O(V * (V + E))** runtime.
+/// Otherwise, small graphs and graphs, on which ```E * log(V)``` isn't much smaller than ```V * V```, are solved by the dynamic program,
+/// and all other graphs by Dijkstra's algorithm from every node.
+pub fn choose_strategy<G>(g: G) -> Strategy
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    if n > 1 && uniform_weight(g).is_some() {
+        return Strategy::Bfs;
+    }
+    if n <= SMALL {
+        return Strategy::FloydWarshall;
+    }
+
+    // Every undirected edge is relaxed from both of its nodes.
+    let edges = g.edge_references().count();
+    let arcs = if g.is_directed() { edges } else { 2 * edges };
+    let log = (usize::BITS - n.leading_zeros()) as usize;
+    if arcs.saturating_mul(log) < n * n / 4 {
+        Strategy::Dijkstra
+    } else {
+        Strategy::FloydWarshall
+    }
+}
+
+/// This function computes the shortest paths between every two nodes like ```floyd_warshall```,
+/// but runs the algorithm, which ```choose_strategy``` considers to be the fastest one for the graph.
+/// The chosen algorithm is returned together with the matrix, so it can be logged.
+///
+/// On graphs without parallel edges, the distances are the same as the ones of ```floyd_warshall```. Of several shortest paths, a different one may be chosen.
+pub fn apsp<G>(g: G) -> Apsp<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let strategy = choose_strategy(g);
+    let matrix = match strategy {
+        Strategy::FloydWarshall => floyd_warshall(g),
+        Strategy::Dijkstra => apsp_dijkstra(g),
+        Strategy::Bfs => {
+            // Every path with the fewest edges is a shortest path, whose length is its number of edges times the weight.
+            let w = uniform_weight(g).unwrap_or(1);
+            let mut m = apsp_unweighted(g);
+            if w != 1 {
+                let n = m.node_count();
+                for i in 0..n {
+                    let js = if m.is_directed() { 0..n } else { i..n };
+                    for j in js {
+                        if m.does_path_exist(i, j) {
+                            let len = m.get_path_len(i, j);
+                            m.set_path_len(i, j, len.saturating_mul(w));
+                        }
+                    }
+                }
+            }
+            m
+        }
+    };
+
+    Apsp { matrix, strategy }
+}
@@ -0,0 +1,93 @@
+//! Running the algorithm on many independent graphs at once.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use kernel::Kernel;
+use Options;
+use PathMatrix;
+
+/// Computes the shortest paths between every two nodes for each of the given graphs, like calling ```floyd_warshall``` on each of them.
+/// The buffers of the dynamic program are shared between the runs, so this is suited for many small graphs.
+pub fn floyd_warshall_batch<I, G>(graphs: I) -> Vec<PathMatrix<G::NodeWeight>>
+where
+    I: IntoIterator<Item = G>,
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let options = Options::default();
+    let mut kernel = Kernel::default();
+
+    graphs
+        .into_iter()
+        .map(|g| {
+            let mut m = PathMatrix::new(g.node_count());
+            ::compute(g, &options, &mut m, &mut kernel);
+            m
+        })
+        .collect()
+}
+
+/// This function computes the same matrices as ```floyd_warshall_batch```, but processes the graphs on all available cores.
+/// Every graph is still computed by a single thread, which reuses its buffers for the next graph.
+#[cfg(feature = "rayon")]
+pub fn floyd_warshall_batch_par<G>(graphs: &[G]) -> Vec<PathMatrix<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp
+        + Sync,
+    G::NodeWeight: Clone + Send,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    floyd_warshall_batch_par_with_options(graphs, &Options::default())
+}
+
+/// This function computes the same matrices as ```floyd_warshall_batch_par```, but allows tuning the algorithm and selecting the threads by the given ```Options```.
+#[cfg(feature = "rayon")]
+pub fn floyd_warshall_batch_par_with_options<G>(
+    graphs: &[G],
+    options: &Options,
+) -> Vec<PathMatrix<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp
+        + Sync,
+    G::NodeWeight: Clone + Send,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    use rayon::prelude::*;
+
+    options.install(|| {
+        graphs
+            .par_iter()
+            .map_init(Kernel::default, |kernel, &g| {
+                let mut m = PathMatrix::new(g.node_count());
+                ::compute(g, options, &mut m, kernel);
+                m
+            })
+            .collect()
+    })
+}
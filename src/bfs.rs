@@ -0,0 +1,92 @@
+//! A faster way to solve the problem on graphs without edge weights, by a breadth-first search from every node.
+
+use std::collections::VecDeque;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeRef;
+
+use PathMatrix;
+
+/// This function computes the same kind of matrix as ```floyd_warshall```, but every edge has the length 1, whatever its weight is.
+/// Then a breadth-first search from every node finds all shortest paths in **O(V * (V + E))** runtime,
+/// which is much faster than the dynamic program on sparse graphs.
+///
+/// If all edge weights are 1, the distances are the same as the ones of ```floyd_warshall```. Of several shortest paths, a different one may be chosen.
+pub fn apsp_unweighted<G>(g: G) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+{
+    let n = g.node_count();
+    let directed = g.is_directed();
+
+    let mut adj = vec![Vec::new(); n];
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        if u != v {
+            adj[u].push(v);
+            if !directed {
+                adj[v].push(u);
+            }
+        }
+    }
+
+    let mut m = if directed {
+        PathMatrix::new_directed(n)
+    } else {
+        PathMatrix::new(n)
+    };
+    m.set_node_weights(g.node_references().map(|n| n.weight().clone()));
+
+    let mut dist = vec![usize::MAX; n];
+    let mut parent = vec![0; n];
+    let mut queue = VecDeque::new();
+
+    for s in 0..n {
+        for d in dist.iter_mut() {
+            *d = usize::MAX;
+        }
+        dist[s] = 0;
+        queue.push_back(s);
+
+        while let Some(u) = queue.pop_front() {
+            for &v in &adj[u] {
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    parent[v] = u;
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        // In an undirected graph, the pairs with a smaller node were already stored by the search from that node.
+        let targets = if directed { 0..n } else { s..n };
+        for t in targets {
+            if dist[t] == usize::MAX {
+                continue;
+            }
+
+            m.set_path_len(s, t, dist[t]);
+
+            // The path is split at the node before t, whose path from s is one edge shorter, so the splits always end.
+            if dist[t] > 1 {
+                m.get_path_mut(s, t).set_via(parent[t]);
+            }
+        }
+    }
+
+    m
+}
@@ -0,0 +1,118 @@
+//! A ```PathMatrix```, which is bound to the graph it was computed for.
+
+use std::collections::HashMap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeRef;
+
+use analysis::edge_key;
+use analysis::path_edges;
+use PathMatrix;
+
+/// A ```PathMatrix``` together with the graph it was computed for.
+/// Its path queries return the node and edge references of the graph itself, so no node weight has to be cloned to process a path.
+pub struct PathMatrixRef<'g, G: 'g>
+where
+    &'g G: IntoNodeReferences + IntoEdgeReferences,
+{
+    g: &'g G,
+    m: PathMatrix<<&'g G as Data>::NodeWeight>,
+    nodes: Vec<<&'g G as IntoNodeReferences>::NodeRef>,
+    edges: HashMap<(usize, usize), <&'g G as IntoEdgeReferences>::EdgeRef>,
+}
+
+impl<'g, G: 'g> PathMatrixRef<'g, G>
+where
+    &'g G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    <&'g G as Data>::NodeWeight: Clone,
+    <&'g G as Data>::EdgeWeight: Clone + Into<usize>,
+{
+    /// Computes the shortest paths in the given graph like ```floyd_warshall``` and binds the result to the graph.
+    pub fn new(g: &'g G) -> PathMatrixRef<'g, G> {
+        PathMatrixRef::from_matrix(g, ::floyd_warshall(g))
+    }
+
+    /// Binds an already computed matrix to the graph it was computed for.
+    pub fn from_matrix(
+        g: &'g G,
+        m: PathMatrix<<&'g G as Data>::NodeWeight>,
+    ) -> PathMatrixRef<'g, G> {
+        let mut nodes: Vec<_> = g.node_references().collect();
+        nodes.sort_unstable_by_key(|n| n.id().index());
+
+        // Every pair, which is stored as a single edge, is mapped to an edge of the graph with the stored length.
+        let mut edges = HashMap::new();
+        for e in g.edge_references() {
+            let u = e.source().index();
+            let v = e.target().index();
+            let w: usize = e.weight().clone().into();
+
+            let p = m.entry(u, v);
+            if u != v && p.via().is_none() && p.exists() && p.len() == w {
+                edges.entry(edge_key(&m, u, v)).or_insert(e);
+            }
+        }
+
+        PathMatrixRef { g, m, nodes, edges }
+    }
+
+    /// Returns the graph, which this matrix was computed for.
+    #[inline]
+    pub fn graph(&self) -> &'g G {
+        self.g
+    }
+
+    /// Returns the computed matrix.
+    #[inline]
+    pub fn matrix(&self) -> &PathMatrix<<&'g G as Data>::NodeWeight> {
+        &self.m
+    }
+
+    /// Returns the reference to node ```i``` in the graph.
+    #[inline]
+    pub fn node(&self, i: usize) -> <&'g G as IntoNodeReferences>::NodeRef {
+        self.nodes[i]
+    }
+
+    /// Returns the references to all nodes on the shortest path from ```i``` to ```j``` in that order, including both endpoints.
+    /// This is ```None```, if there is no path between them.
+    pub fn path_nodes(
+        &self,
+        i: usize,
+        j: usize,
+    ) -> Option<Vec<<&'g G as IntoNodeReferences>::NodeRef>> {
+        self.m.distance(i, j)?;
+        Some(self.m.path_iter(i, j).map(|n| self.nodes[n]).collect())
+    }
+
+    /// Returns the references to all edges on the shortest path from ```i``` to ```j``` in that order.
+    /// This is ```None```, if there is no path between them.
+    pub fn path_edges(
+        &self,
+        i: usize,
+        j: usize,
+    ) -> Option<Vec<<&'g G as IntoEdgeReferences>::EdgeRef>> {
+        self.m.distance(i, j)?;
+
+        let mut pairs = Vec::new();
+        if i != j {
+            path_edges(&self.m, i, j, &mut pairs);
+        }
+
+        Some(pairs.iter().map(|pair| self.edges[pair]).collect())
+    }
+}
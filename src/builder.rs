@@ -0,0 +1,169 @@
+//! A builder, which collects the settings of a run in one place.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use kernel::Kernel;
+use CompactDistances;
+use DistanceOverflow;
+use EventLog;
+use Observer;
+use Options;
+use PathMatrix;
+use Progress;
+use Snapshots;
+use Threads;
+
+/// A run of the algorithm on a graph, which is configured step by step and started by ```run```.
+/// This combines the ```Options``` with the settings, which would otherwise need a function of their own.
+///
+/// ```FloydWarshall::new(g).run()``` computes the same matrix as ```floyd_warshall(g)```.
+#[derive(Clone, Debug)]
+pub struct FloydWarshall<G> {
+    g: G,
+    options: Options,
+    max_distance: Option<usize>,
+    parallel: bool,
+}
+
+impl<G> FloydWarshall<G>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone + Send + Sync,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    /// Starts the configuration of a run on the given graph with the default settings.
+    pub fn new(g: G) -> FloydWarshall<G> {
+        FloydWarshall {
+            g,
+            options: Options::default(),
+            max_distance: None,
+            parallel: false,
+        }
+    }
+
+    /// Replaces all tuning parameters by the given ```Options```.
+    pub fn options(mut self, options: Options) -> FloydWarshall<G> {
+        self.options = options;
+        self
+    }
+
+    /// Sets ```Options::tile_size```.
+    pub fn tile_size(mut self, tile_size: usize) -> FloydWarshall<G> {
+        self.options.tile_size = Some(tile_size);
+        self
+    }
+
+    /// Sets ```Options::threads```.
+    pub fn threads(mut self, threads: Threads) -> FloydWarshall<G> {
+        self.options.threads = threads;
+        self
+    }
+
+    /// Sets ```Options::first_touch```.
+    pub fn first_touch(mut self, first_touch: bool) -> FloydWarshall<G> {
+        self.options.first_touch = first_touch;
+        self
+    }
+
+    /// Sets ```Options::auto_tune```.
+    pub fn auto_tune(mut self, auto_tune: bool) -> FloydWarshall<G> {
+        self.options.auto_tune = auto_tune;
+        self
+    }
+
+    /// Sets ```Options::observer```.
+    pub fn observer(mut self, observer: Observer) -> FloydWarshall<G> {
+        self.options.observer = Some(observer);
+        self
+    }
+
+    /// Sets ```Options::snapshots```.
+    pub fn snapshots(mut self, snapshots: Snapshots) -> FloydWarshall<G> {
+        self.options.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Sets ```Options::event_log```.
+    pub fn event_log(mut self, event_log: EventLog) -> FloydWarshall<G> {
+        self.options.event_log = Some(event_log);
+        self
+    }
+
+    /// Sets ```Options::random_ties``` to the given seed.
+    pub fn random_ties(mut self, seed: u64) -> FloydWarshall<G> {
+        self.options.random_ties = Some(seed);
+        self
+    }
+
+    /// Sets ```Options::progress```.
+    pub fn progress(mut self, progress: Progress) -> FloydWarshall<G> {
+        self.options.progress = Some(progress);
+        self
+    }
+
+    /// Drops every path, which is longer than ```d```, from the result, so the matrix only contains the pairs within this distance.
+    pub fn max_distance(mut self, d: usize) -> FloydWarshall<G> {
+        self.max_distance = Some(d);
+        self
+    }
+
+    /// Runs the algorithm on all available cores like ```floyd_warshall_par```, if this is set.
+    #[cfg(feature = "rayon")]
+    pub fn parallel(mut self, parallel: bool) -> FloydWarshall<G> {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Runs the algorithm with the configured settings.
+    pub fn run(self) -> PathMatrix<G::NodeWeight> {
+        let mut m = if self.parallel {
+            self.run_par()
+        } else {
+            let mut m = PathMatrix::new(self.g.node_count());
+            ::compute(self.g, &self.options, &mut m, &mut Kernel::default());
+            m
+        };
+
+        if let Some(d) = self.max_distance {
+            // Every part of a path is at most as long as the path itself, so the remaining paths don't run over a dropped one.
+            for i in 0..m.node_count() {
+                for j in i + 1..m.node_count() {
+                    if m.distance(i, j).is_some_and(|len| len > d) {
+                        m.get_path_mut(i, j).clear();
+                    }
+                }
+            }
+        }
+
+        m
+    }
+
+    /// Computes only the distances like ```floyd_warshall_compact```, with the blocked algorithm over tiles of the configured tile size.
+    /// With ```auto_tune```, the tile size and the kernel are benchmarked instead. All other settings are ignored.
+    pub fn run_compact(self) -> Result<CompactDistances, DistanceOverflow> {
+        ::compact::compact(self.g, &self.options)
+    }
+
+    #[cfg(feature = "rayon")]
+    fn run_par(&self) -> PathMatrix<G::NodeWeight> {
+        ::floyd_warshall_par_with_options(self.g, &self.options)
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn run_par(&self) -> PathMatrix<G::NodeWeight> {
+        unreachable!("parallel runs need the feature rayon")
+    }
+}
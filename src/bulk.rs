@@ -0,0 +1,83 @@
+//! Evaluation of many queries at once, in the order of the storage instead of the order of the caller.
+
+use PathMatrix;
+
+impl<T> PathMatrix<T> {
+    /// Returns the positions of ```pairs``` sorted by the position of their entries in the storage,
+    /// so walking the queries in this order reads the matrix front to back.
+    fn storage_order(&self, pairs: &[(usize, usize)]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..pairs.len()).collect();
+        order.sort_by_cached_key(|&p| self.idx(pairs[p].0, pairs[p].1));
+        order
+    }
+
+    /// Returns the nodes on the shortest path from ```i``` to ```j``` in that order, including both endpoints,
+    /// or ```None```, if there is no path between them.
+    fn path_nodes(&self, i: usize, j: usize) -> Option<Vec<usize>> {
+        self.distance(i, j)?;
+        Some(self.path_iter(i, j).collect())
+    }
+
+    /// Returns the distances of all given pairs in the same order, where ```None``` means that there is no path.
+    /// The queries are answered in the order of the storage, which is considerably faster than looping over ```get_path_len```
+    /// for large, unordered batches.
+    pub fn query_many(&self, pairs: &[(usize, usize)]) -> Vec<Option<usize>> {
+        let mut out = vec![None; pairs.len()];
+        for p in self.storage_order(pairs) {
+            out[p] = self.distance(pairs[p].0, pairs[p].1);
+        }
+        out
+    }
+
+    /// Returns the shortest paths of all given pairs in the same order, as node indices including both endpoints like ```PathCache::path```.
+    /// ```None``` means that there is no path. The queries are answered in the order of the storage.
+    pub fn query_many_paths(&self, pairs: &[(usize, usize)]) -> Vec<Option<Vec<usize>>> {
+        let mut out = vec![None; pairs.len()];
+        for p in self.storage_order(pairs) {
+            out[p] = self.path_nodes(pairs[p].0, pairs[p].1);
+        }
+        out
+    }
+
+    /// Does the same as ```query_many```, but answers the queries on all available cores.
+    #[cfg(feature = "rayon")]
+    pub fn query_many_par(&self, pairs: &[(usize, usize)]) -> Vec<Option<usize>>
+    where
+        T: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        let order = self.storage_order(pairs);
+        let answers: Vec<Option<usize>> = order
+            .par_iter()
+            .map(|&p| self.distance(pairs[p].0, pairs[p].1))
+            .collect();
+
+        let mut out = vec![None; pairs.len()];
+        for (p, d) in order.into_iter().zip(answers) {
+            out[p] = d;
+        }
+        out
+    }
+
+    /// Does the same as ```query_many_paths```, but answers the queries on all available cores.
+    #[cfg(feature = "rayon")]
+    pub fn query_many_paths_par(&self, pairs: &[(usize, usize)]) -> Vec<Option<Vec<usize>>>
+    where
+        T: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        let order = self.storage_order(pairs);
+        let answers: Vec<Option<Vec<usize>>> = order
+            .par_iter()
+            .map(|&p| self.path_nodes(pairs[p].0, pairs[p].1))
+            .collect();
+
+        let mut out = vec![None; pairs.len()];
+        for (p, path) in order.into_iter().zip(answers) {
+            out[p] = path;
+        }
+        out
+    }
+}
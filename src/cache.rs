@@ -0,0 +1,100 @@
+//! A cache of reconstructed paths with a bounded size, which evicts the least recently used path.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use PathMatrix;
+
+/// A cache for the paths of a ```PathMatrix```, which keeps at most ```capacity``` paths and evicts the least recently used one.
+/// The paths are reconstructed from the intermediate nodes and stored as node indices, so they are not materialized in the matrix itself,
+/// whose memory would grow with every pair ever asked for.
+#[derive(Clone, Debug)]
+pub struct PathCache<'a, T: 'a> {
+    m: &'a PathMatrix<T>,
+    capacity: usize,
+    /// The cached paths together with the time of their last use.
+    paths: HashMap<(usize, usize), (Vec<usize>, u64)>,
+    /// The pairs in the cache by the time of their last use, so the first one is evicted next.
+    recent: BTreeMap<u64, (usize, usize)>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<T> PathMatrix<T> {
+    /// Returns a cache for the paths of this matrix, which keeps at most ```capacity``` paths.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if ```capacity``` is 0.
+    pub fn path_cache(&self, capacity: usize) -> PathCache<'_, T> {
+        assert!(capacity > 0, "the capacity must not be 0");
+        PathCache {
+            m: self,
+            capacity,
+            paths: HashMap::new(),
+            recent: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<'a, T> PathCache<'a, T> {
+    /// Returns the nodes on the shortest path from ```i``` to ```j``` in that order, including both endpoints.
+    /// The path is reconstructed only, if it isn't in the cache yet. This is ```None```, if there is no path between them.
+    pub fn path(&mut self, i: usize, j: usize) -> Option<&[usize]> {
+        self.m.distance(i, j)?;
+        self.clock += 1;
+
+        if let Some(entry) = self.paths.get_mut(&(i, j)) {
+            self.recent.remove(&entry.1);
+            entry.1 = self.clock;
+            self.recent.insert(self.clock, (i, j));
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            if self.paths.len() == self.capacity {
+                let (_, pair) = self.recent.pop_first().unwrap();
+                self.paths.remove(&pair);
+            }
+
+            let nodes = self.m.path_iter(i, j).collect();
+            self.paths.insert((i, j), (nodes, self.clock));
+            self.recent.insert(self.clock, (i, j));
+        }
+
+        self.paths.get(&(i, j)).map(|(nodes, _)| &nodes[..])
+    }
+
+    /// Returns the number of paths in the cache.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// If the cache doesn't contain any path, this returns true.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns the number of queries, which were answered from the cache.
+    #[inline]
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of queries, which had to reconstruct their path.
+    #[inline]
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Removes all paths from the cache. The counters of hits and misses are kept.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.recent.clear();
+    }
+}
@@ -0,0 +1,118 @@
+//! Runs, which can be stopped between two iterations of the dynamic program, and which return their partial result.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use kernel::Kernel;
+use Options;
+use PathMatrix;
+
+/// The error of a run, which was stopped before it finished, together with the matrix computed so far.
+///
+/// The partial matrix is incomplete: Every stored path is a path of the graph and its length is correct,
+/// but it's only the shortest one over the intermediate nodes of the finished iterations, and some connected pairs may have no path yet.
+#[derive(Debug)]
+pub struct Interrupted<T> {
+    /// The matrix after the finished iterations.
+    pub partial: PathMatrix<T>,
+    /// The number of finished iterations. The run would have needed one iteration per node.
+    pub completed: usize,
+}
+
+/// Runs the algorithm like ```compute```, but relaxes one intermediate node at a time and stops, once ```stop``` returns true before an iteration.
+pub(crate) fn run_until<G, F>(
+    g: G,
+    options: &Options,
+    mut stop: F,
+) -> Result<PathMatrix<G::NodeWeight>, Interrupted<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+    F: FnMut() -> bool,
+{
+    let mut m = PathMatrix::new(g.node_count());
+    let order = ::prepare(g, &mut m);
+
+    let mut ids = order.clone();
+    ids.sort_unstable();
+
+    let mut kernel = Kernel::default();
+    kernel.load(&m);
+    let tile = options.choose_tile_size(&kernel, &ids);
+    options.start(&kernel, 0);
+
+    for (completed, k) in order.chunks(1).enumerate() {
+        if stop() {
+            kernel.store(&mut m);
+            return Err(Interrupted {
+                partial: m,
+                completed,
+            });
+        }
+        options.relax(&mut kernel, k, &ids, tile);
+    }
+
+    kernel.store(&mut m);
+    Ok(m)
+}
+
+/// This function computes the same matrix as ```floyd_warshall_with_options```, but checks ```cancel``` before every iteration of the dynamic program.
+/// Once it's set, for example by another thread, the run stops and returns the matrix computed so far as an ```Interrupted``` error.
+pub fn floyd_warshall_cancellable<G>(
+    g: G,
+    options: &Options,
+    cancel: &AtomicBool,
+) -> Result<PathMatrix<G::NodeWeight>, Interrupted<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    run_until(g, options, || cancel.load(Ordering::Relaxed))
+}
+
+/// This function computes the same matrix as ```floyd_warshall```, but only for the given time budget. It's checked before every iteration of the dynamic program,
+/// so the run may take up to one iteration longer. If the budget is exhausted before the last iteration, the matrix computed so far is returned as an ```Interrupted``` error,
+/// which marks it as incomplete.
+pub fn run_with_deadline<G>(
+    g: G,
+    budget: Duration,
+) -> Result<PathMatrix<G::NodeWeight>, Interrupted<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let start = Instant::now();
+    run_until(g, &Options::default(), || start.elapsed() >= budget)
+}
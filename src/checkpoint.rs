@@ -0,0 +1,147 @@
+//! Periodic checkpoints, which allow long runs to be resumed after an interruption.
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use kernel::Kernel;
+use Options;
+use PathMatrix;
+
+/// Describes, where and how often the state of a run is written to disk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The file, which the checkpoints are written to. Every checkpoint replaces the previous one.
+    pub path: PathBuf,
+    /// The number of iterations of the dynamic program between two checkpoints.
+    pub every: usize,
+}
+
+impl Checkpoint {
+    /// Describes a checkpoint in the given file, which is written after every iteration of the dynamic program.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Checkpoint {
+        Checkpoint {
+            path: path.into(),
+            every: 1,
+        }
+    }
+
+    /// Writes the state of ```kernel``` before the iteration at position ```next``` to the checkpoint file.
+    /// The state is written to a temporary file first and then moved over the previous checkpoint,
+    /// so an interruption while writing never destroys the last complete checkpoint.
+    fn write(&self, kernel: &Kernel, next: usize) -> io::Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+
+        let mut w = BufWriter::new(File::create(&tmp)?);
+        kernel.save(&mut w, next)?;
+        w.into_inner()?.sync_all()?;
+
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+/// This function computes the same matrix as ```floyd_warshall_with_options```, but writes a checkpoint every ```checkpoint.every``` iterations.
+/// If the run is interrupted, it can be continued from the last checkpoint by ```resume_from_checkpoint```.
+/// The checkpoint file is left in place after the run.
+pub fn floyd_warshall_checkpointed<G>(
+    g: G,
+    options: &Options,
+    checkpoint: &Checkpoint,
+) -> io::Result<PathMatrix<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut m = PathMatrix::new(g.node_count());
+    let order = ::prepare(g, &mut m);
+
+    let mut kernel = Kernel::default();
+    kernel.load(&m);
+
+    run(&mut kernel, &order, 0, options, checkpoint)?;
+    kernel.store(&mut m);
+    Ok(m)
+}
+
+/// Continues a run of ```floyd_warshall_checkpointed``` on the same graph from the last checkpoint written to ```checkpoint.path```.
+/// Further checkpoints are written like before. If the checkpoint doesn't belong to a graph of this size, an error of kind ```InvalidData``` is returned.
+/// The checkpoints keep the path counts of ```Options::random_ties```, so with the same options, the ties are broken like in an uninterrupted run.
+pub fn resume_from_checkpoint<G>(
+    g: G,
+    options: &Options,
+    checkpoint: &Checkpoint,
+) -> io::Result<PathMatrix<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut m = PathMatrix::new(g.node_count());
+    let order = ::prepare(g, &mut m);
+
+    let mut r = BufReader::new(File::open(&checkpoint.path)?);
+    let (mut kernel, next) = Kernel::restore(&mut r, m.node_count(), m.is_directed())?;
+    if next > order.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the checkpoint belongs to a different graph",
+        ));
+    }
+
+    run(&mut kernel, &order, next, options, checkpoint)?;
+    kernel.store(&mut m);
+    Ok(m)
+}
+
+/// Runs the relaxation for the intermediate nodes in ```order``` from position ```start``` on, and writes a checkpoint between the chunks.
+fn run(
+    kernel: &mut Kernel,
+    order: &[usize],
+    start: usize,
+    options: &Options,
+    checkpoint: &Checkpoint,
+) -> io::Result<()> {
+    let mut ids = order.to_vec();
+    ids.sort_unstable();
+    let tile = options.choose_tile_size(kernel, &ids);
+    options.start(kernel, start);
+
+    let mut next = start;
+    while next < order.len() {
+        let end = (next + checkpoint.every.max(1)).min(order.len());
+        options.relax(kernel, &order[next..end], &ids, tile);
+        next = end;
+
+        if next < order.len() {
+            checkpoint.write(kernel, next)?;
+        }
+    }
+
+    Ok(())
+}
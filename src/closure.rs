@@ -0,0 +1,96 @@
+//! A reachability-only mode, which computes the transitive closure of a graph on bitsets.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+/// The number of nodes per word of a row.
+const BITS: usize = 64;
+
+/// The transitive closure of a graph: For every two nodes, this tells whether there is a path between them.
+/// Every row is stored as a bitset, so this needs a single bit per pair.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reachability {
+    rows: Box<[u64]>,
+    words: usize,
+    n: usize,
+}
+
+impl Reachability {
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Is there a path from ```i``` to ```j```? Every node reaches itself.
+    #[inline]
+    pub fn is_reachable(&self, i: usize, j: usize) -> bool {
+        assert!(j < self.n);
+        self.rows[i * self.words + j / BITS] & (1 << (j % BITS)) != 0
+    }
+
+    /// Returns the nodes reachable from ```i``` in ascending order, which includes ```i``` itself.
+    pub fn reachable_from<'a>(&'a self, i: usize) -> impl Iterator<Item = usize> + 'a {
+        let row = &self.rows[i * self.words..(i + 1) * self.words];
+        row.iter().enumerate().flat_map(|(w, &bits)| {
+            (0..BITS)
+                .filter(move |b| bits & (1 << b) != 0)
+                .map(move |b| w * BITS + b)
+        })
+    }
+
+    /// Returns the number of nodes reachable from ```i```, which includes ```i``` itself.
+    pub fn count_reachable(&self, i: usize) -> usize {
+        let row = &self.rows[i * self.words..(i + 1) * self.words];
+        row.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+/// Computes the transitive closure of the given graph by Warshall's algorithm.
+/// Instead of the distances, only a bit per pair is stored, and a whole row is merged by word-wide ORs.
+/// Both directed and undirected graphs are supported.
+pub fn transitive_closure<G>(g: G) -> Reachability
+where
+    G: GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+{
+    let n = g.node_count();
+    let words = n.div_ceil(BITS);
+    let mut rows = vec![0u64; n * words];
+
+    let mut set = |i: usize, j: usize| rows[i * words + j / BITS] |= 1 << (j % BITS);
+    for i in 0..n {
+        set(i, i);
+    }
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        set(u, v);
+        if !g.is_directed() {
+            set(v, u);
+        }
+    }
+
+    let mut row_k = vec![0u64; words];
+    for k in 0..n {
+        row_k.copy_from_slice(&rows[k * words..(k + 1) * words]);
+
+        // Every node, which reaches k, also reaches everything k reaches.
+        for row in rows.chunks_mut(words.max(1)) {
+            if row[k / BITS] & (1 << (k % BITS)) != 0 {
+                for (a, b) in row.iter_mut().zip(&row_k) {
+                    *a |= *b;
+                }
+            }
+        }
+    }
+
+    Reachability {
+        rows: rows.into(),
+        words,
+        n,
+    }
+}
@@ -0,0 +1,234 @@
+//! Clustering of the nodes of a graph by their distances.
+
+use Distances;
+
+/// Returns the smaller of two distances, where ```None``` stands for infinity.
+#[inline]
+fn min_distance(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// The cost of a clustering: The number of nodes, which can't reach any medoid, and the sum of the distances of all other nodes to their medoid.
+/// Costs are compared lexicographically, so reaching more nodes is always preferred.
+type Cost = (usize, usize);
+
+#[inline]
+fn add_cost(c: Cost, d: Option<usize>) -> Cost {
+    match d {
+        Some(d) => (c.0, c.1.saturating_add(d)),
+        None => (c.0 + 1, c.1),
+    }
+}
+
+/// The position of the closest medoid in the list of medoids and the distance to it.
+type Nearest = (Option<usize>, Option<usize>);
+
+/// The result of a k-medoids clustering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KMedoids {
+    /// The nodes chosen as medoids.
+    pub medoids: Vec<usize>,
+    /// For every node, the position of its closest medoid in ```medoids```, or ```None```, if it can't reach any medoid.
+    pub assignment: Vec<Option<usize>>,
+    /// The sum of the distances of all assigned nodes to their medoid.
+    pub cost: usize,
+}
+
+/// Clusters the nodes into ```k``` clusters by the PAM algorithm (partitioning around medoids).
+/// The medoids are chosen greedily first and then improved by swapping a medoid with a non-medoid, as long as this lowers the total distance of all nodes to their closest medoid.
+/// Nodes, which can't reach any medoid, are left unassigned, and the algorithm reduces their number first.
+pub fn k_medoids<D: Distances>(d: &D, k: usize) -> KMedoids {
+    let n = d.node_count();
+    let k = k.min(n);
+    let mut medoids: Vec<usize> = Vec::with_capacity(k);
+
+    // For every node, the distance to the closest medoid so far.
+    let mut nearest: Vec<Option<usize>> = vec![None; n];
+
+    // BUILD: Greedily add the medoid, which lowers the cost the most.
+    while medoids.len() < k {
+        let mut best: Option<(usize, Cost)> = None;
+        for o in (0..n).filter(|o| !medoids.contains(o)) {
+            let cost = (0..n).fold((0, 0), |c, j| {
+                add_cost(c, min_distance(nearest[j], d.distance(o, j)))
+            });
+            if best.is_none_or(|(_, b)| cost < b) {
+                best = Some((o, cost));
+            }
+        }
+
+        let o = best.expect("there is a node left to choose").0;
+        medoids.push(o);
+        for (j, nj) in nearest.iter_mut().enumerate() {
+            *nj = min_distance(*nj, d.distance(o, j));
+        }
+    }
+
+    // SWAP: Replace a medoid by a non-medoid, as long as this lowers the cost.
+    loop {
+        let (first, second) = nearest_two(d, &medoids);
+        let current = first.iter().fold((0, 0), |c, &(_, dist)| add_cost(c, dist));
+
+        let mut best: Option<(usize, usize, Cost)> = None;
+        for mi in 0..medoids.len() {
+            for o in (0..n).filter(|o| !medoids.contains(o)) {
+                // Every node either keeps its closest medoid or falls back to the second closest one, unless o is closer.
+                let cost = (0..n).fold((0, 0), |c, j| {
+                    let kept = if first[j].0 == Some(mi) {
+                        second[j]
+                    } else {
+                        first[j].1
+                    };
+                    add_cost(c, min_distance(kept, d.distance(o, j)))
+                });
+
+                if cost < current && best.is_none_or(|(_, _, b)| cost < b) {
+                    best = Some((mi, o, cost));
+                }
+            }
+        }
+
+        match best {
+            Some((mi, o, _)) => medoids[mi] = o,
+            None => break,
+        }
+    }
+
+    let (first, _) = nearest_two(d, &medoids);
+    let cost = first
+        .iter()
+        .filter_map(|&(_, dist)| dist)
+        .fold(0usize, |c, dist| c.saturating_add(dist));
+    let assignment = first.into_iter().map(|(m, _)| m).collect();
+
+    KMedoids {
+        medoids,
+        assignment,
+        cost,
+    }
+}
+
+/// Returns for every node its closest medoid (as a position in ```medoids```) with the distance to it, and the distance to its second closest medoid.
+fn nearest_two<D: Distances>(d: &D, medoids: &[usize]) -> (Vec<Nearest>, Vec<Option<usize>>) {
+    let n = d.node_count();
+    let mut first = vec![(None, None); n];
+    let mut second = vec![None; n];
+
+    for j in 0..n {
+        for (mi, &m) in medoids.iter().enumerate() {
+            let dist = match d.distance(m, j) {
+                Some(dist) => dist,
+                None => continue,
+            };
+
+            match first[j] {
+                (_, Some(f)) if f <= dist => second[j] = min_distance(second[j], Some(dist)),
+                (_, f) => {
+                    second[j] = f;
+                    first[j] = (Some(mi), Some(dist));
+                }
+            }
+        }
+    }
+
+    (first, second)
+}
+
+/// A single step of a hierarchical clustering, which merges two clusters into a new one.
+/// The nodes are the clusters ```0..n```, and the cluster created by the ```i```-th merge has the id ```n + i```.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Merge {
+    /// The id of the first merged cluster.
+    pub left: usize,
+    /// The id of the second merged cluster.
+    pub right: usize,
+    /// The distance between the two clusters.
+    pub distance: usize,
+    /// The number of nodes in the new cluster.
+    pub size: usize,
+}
+
+/// Computes a single-linkage hierarchical clustering and returns its merges in ascending order of distance (the dendrogram).
+/// Clusters, which are not connected at all, are never merged, so a graph with ```c``` components yields ```n - c``` merges.
+///
+/// The merges are the edges of a minimum spanning tree of the metric, which is found by Prim's algorithm in **O(V^(2))** time.
+pub fn single_linkage<D: Distances>(d: &D) -> Vec<Merge> {
+    let n = d.node_count();
+
+    // Prim's algorithm on the complete graph of the metric, restarted for every component.
+    let mut in_tree = vec![false; n];
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; n];
+    let mut edges = Vec::with_capacity(n);
+
+    for root in 0..n {
+        if in_tree[root] {
+            continue;
+        }
+
+        let mut u = root;
+        loop {
+            in_tree[u] = true;
+            if let Some((dist, from)) = best[u] {
+                edges.push((dist, from, u));
+            }
+
+            for v in (0..n).filter(|&v| !in_tree[v]) {
+                if let Some(dist) = d.distance(u, v) {
+                    if best[v].is_none_or(|(b, _)| dist < b) {
+                        best[v] = Some((dist, u));
+                    }
+                }
+            }
+
+            let next = (0..n)
+                .filter(|&v| !in_tree[v])
+                .filter_map(|v| best[v].map(|(dist, _)| (dist, v)))
+                .min();
+            match next {
+                Some((_, v)) => u = v,
+                None => break,
+            }
+        }
+    }
+
+    edges.sort_by_key(|&(dist, _, _)| dist);
+
+    // Replay the tree edges in ascending order with a union-find structure, which tracks the current cluster id of every set.
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut cluster: Vec<usize> = (0..n).collect();
+    let mut size = vec![1; n];
+
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    edges
+        .into_iter()
+        .enumerate()
+        .map(|(i, (distance, u, v))| {
+            let ru = find(&mut parent, u);
+            let rv = find(&mut parent, v);
+
+            let merge = Merge {
+                left: cluster[ru],
+                right: cluster[rv],
+                distance,
+                size: size[ru] + size[rv],
+            };
+
+            parent[rv] = ru;
+            size[ru] = merge.size;
+            cluster[ru] = n + i;
+
+            merge
+        })
+        .collect()
+}
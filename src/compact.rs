@@ -0,0 +1,120 @@
+//! A distance-only mode, which stores the distances in 16 bits.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use minplus::relax_compact;
+use Distances;
+use Options;
+
+/// The length of a missing path.
+pub(crate) const INFINITY: u16 = u16::MAX;
+
+/// Every length from this value on doesn't fit and marks a path, which overflowed.
+pub(crate) const OVERFLOW: u16 = u16::MAX - 1;
+
+/// The reason, why the distances of a graph don't fit into a ```CompactDistances```.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceOverflow {
+    /// The weight of the edge between the given nodes is too large.
+    Edge(usize, usize),
+    /// The distance between the given nodes is too large.
+    Path(usize, usize),
+}
+
+/// The distances between every two nodes, stored in 16 bits each. Distances up to ```u16::MAX - 2``` can be stored.
+/// Other than a ```PathMatrix```, this doesn't contain the paths, so it needs only a fraction of the memory.
+#[derive(Clone, Debug)]
+pub struct CompactDistances {
+    d: Box<[u16]>,
+    n: usize,
+}
+
+impl CompactDistances {
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the distance between ```i``` and ```j```, or ```None```, if there is no path between them.
+    /// For a directed graph, this is the distance from ```i``` to ```j```.
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> Option<u16> {
+        match self.d[i * self.n + j] {
+            INFINITY => None,
+            d => Some(d),
+        }
+    }
+}
+
+impl Distances for CompactDistances {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.get(i, j).map(usize::from)
+    }
+}
+
+/// This function computes the distances between every two nodes in the graph like ```floyd_warshall```, but stores them in 16 bits.
+/// Every sum is checked, so if an edge weight or a distance doesn't fit, an error is returned instead of a wrong result.
+/// In that case, the caller can fall back to ```floyd_warshall```.
+/// Directed graphs are supported, their distances are stored for both directions of every pair.
+///
+/// The matrix is computed by the blocked variant of the algorithm, whose tiles are chosen to fit into a typical L1 cache.
+/// ```FloydWarshall::run_compact``` allows choosing the tile size.
+pub fn floyd_warshall_compact<G>(g: G) -> Result<CompactDistances, DistanceOverflow>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    compact(g, &Options::default())
+}
+
+/// Computes the ```CompactDistances``` of the graph by the blocked algorithm with the tile size and the kernel chosen by ```options```.
+pub(crate) fn compact<G>(g: G, options: &Options) -> Result<CompactDistances, DistanceOverflow>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let mut d = vec![INFINITY; n * n];
+    for i in 0..n {
+        d[i * n + i] = 0;
+    }
+
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+        if w >= OVERFLOW as usize {
+            return Err(DistanceOverflow::Edge(u, v));
+        }
+        if u != v {
+            d[u * n + v] = w as u16;
+            if !g.is_directed() {
+                d[v * n + u] = w as u16;
+            }
+        }
+    }
+
+    let (tile, vectorized) = options.choose_compact(&d, n);
+    relax_compact(&mut d, n, tile, vectorized);
+
+    // A pair, whose distance is still marked, has no path short enough to be stored.
+    // In an undirected graph, the first marked pair in row-major order has i < j, as its mirror comes later.
+    if let Some(p) = d.iter().position(|&x| x == OVERFLOW) {
+        return Err(DistanceOverflow::Path(p / n, p % n));
+    }
+
+    Ok(CompactDistances { d: d.into(), n })
+}
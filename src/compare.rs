@@ -0,0 +1,115 @@
+//! Comparison of the distances of two APSP solutions for the same set of nodes.
+
+use Distances;
+
+/// The result of comparing two distance matrices of the same dimension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// The number of pairs, which are connected in both matrices.
+    pub compared_pairs: usize,
+    /// The number of pairs, which are connected in exactly one of the matrices.
+    pub connectivity_changes: usize,
+    /// The Frobenius norm of the difference over the pairs, which are connected in both matrices.
+    /// Every unordered pair is counted once.
+    pub frobenius: f64,
+    /// The largest absolute difference of a distance over the pairs, which are connected in both matrices.
+    pub max_difference: usize,
+    /// Spearman's rank correlation of the distances over the pairs, which are connected in both matrices.
+    /// This is ```None```, if there are less than two such pairs or all distances in one of the matrices are equal.
+    pub rank_correlation: Option<f64>,
+}
+
+/// Returns the ranks of the given values, where tied values get the average of their ranks.
+fn ranks(values: &[usize]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|&p| values[p]);
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+
+        let rank = (start + end - 1) as f64 / 2.0;
+        for &p in &order[start..end] {
+            ranks[p] = rank;
+        }
+        start = end;
+    }
+
+    ranks
+}
+
+/// Returns the Pearson correlation of two equally long sequences, if it is defined.
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let n = x.len() as f64;
+    let mx = x.iter().sum::<f64>() / n;
+    let my = y.iter().sum::<f64>() / n;
+
+    let mut cov = 0.0;
+    let mut vx = 0.0;
+    let mut vy = 0.0;
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - mx) * (b - my);
+        vx += (a - mx) * (a - mx);
+        vy += (b - my) * (b - my);
+    }
+
+    if vx == 0.0 || vy == 0.0 {
+        None
+    } else {
+        Some(cov / (vx * vy).sqrt())
+    }
+}
+
+/// Compares the distances of two matrices over the same nodes, for example before and after a change of the network.
+///
+/// # Panics
+///
+/// Panics, if the matrices have different dimensions.
+pub fn compare<A: Distances, B: Distances>(a: &A, b: &B) -> Comparison {
+    let n = a.node_count();
+    assert_eq!(
+        n,
+        b.node_count(),
+        "the matrices must have the same dimension"
+    );
+
+    let mut da = Vec::new();
+    let mut db = Vec::new();
+    let mut connectivity_changes = 0;
+    let mut squares = 0.0;
+    let mut max_difference = 0;
+
+    for i in 0..n {
+        for j in i + 1..n {
+            match (a.distance(i, j), b.distance(i, j)) {
+                (Some(x), Some(y)) => {
+                    let diff = x.abs_diff(y);
+                    squares += (diff as f64) * (diff as f64);
+                    max_difference = max_difference.max(diff);
+                    da.push(x);
+                    db.push(y);
+                }
+                (None, None) => {}
+                _ => connectivity_changes += 1,
+            }
+        }
+    }
+
+    let rank_correlation = if da.len() < 2 {
+        None
+    } else {
+        pearson(&ranks(&da), &ranks(&db))
+    };
+
+    Comparison {
+        compared_pairs: da.len(),
+        connectivity_changes,
+        frobenius: squares.sqrt(),
+        max_difference,
+        rank_correlation,
+    }
+}
@@ -0,0 +1,111 @@
+//! Checks of a computed ```PathMatrix``` against the single-source algorithms of petgraph.
+
+use petgraph::algo::dijkstra;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdges;
+use petgraph::visit::NodeCount;
+use petgraph::visit::Visitable;
+
+use rng::SplitMix64;
+use PathMatrix;
+
+/// A pair, whose distance in the matrix differs from the one found by Dijkstra's algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The source of the search.
+    pub i: usize,
+    /// The target.
+    pub j: usize,
+    /// The distance in the matrix, or ```None```, if it has no path.
+    pub matrix: Option<usize>,
+    /// The distance found by Dijkstra's algorithm, or ```None```, if it found no path.
+    pub dijkstra: Option<usize>,
+}
+
+/// Runs Dijkstra's algorithm of petgraph from ```i``` and appends every target, whose distance differs from the matrix, to ```out```.
+fn check_source<G, T>(m: &PathMatrix<T>, g: G, i: usize, out: &mut Vec<Mismatch>)
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + IntoEdges + Visitable,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut found = vec![None; m.node_count()];
+    for (target, len) in dijkstra(g, NodeIndex::new(i), None, |e| e.weight().clone().into()) {
+        found[target.index()] = Some(len);
+    }
+
+    for (j, &d) in found.iter().enumerate() {
+        let expected = m.distance(i, j);
+        if expected != d {
+            out.push(Mismatch {
+                i,
+                j,
+                matrix: expected,
+                dijkstra: d,
+            });
+        }
+    }
+}
+
+/// Compares the distances from every node in the matrix with the ones found by Dijkstra's algorithm of petgraph in the given graph,
+/// and returns all pairs, where they differ. An empty result means, that the matrix is correct for this graph.
+/// This takes **O(V·(E + V log V))** time, see ```cross_check_sampled``` for a cheaper check.
+///
+/// The matrix only uses the last one of parallel edges, so a graph with a shorter earlier parallel edge is reported as well.
+///
+/// # Panics
+///
+/// Panics, if the graph has a different number of nodes than the matrix.
+pub fn cross_check<G, T>(m: &PathMatrix<T>, g: G) -> Vec<Mismatch>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdges + Visitable,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    assert_eq!(
+        m.node_count(),
+        g.node_count(),
+        "the graph doesn't belong to the matrix"
+    );
+
+    let mut out = Vec::new();
+    for i in 0..m.node_count() {
+        check_source(m, g, i, &mut out);
+    }
+    out
+}
+
+/// Does the same as ```cross_check```, but only runs Dijkstra's algorithm from ```sources``` random nodes,
+/// which makes it cheap enough to run as a canary after every import of a graph. The result is reproducible for the same ```seed```.
+///
+/// # Panics
+///
+/// Panics, if the graph has a different number of nodes than the matrix.
+pub fn cross_check_sampled<G, T>(
+    m: &PathMatrix<T>,
+    g: G,
+    sources: usize,
+    seed: u64,
+) -> Vec<Mismatch>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdges + Visitable,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    assert_eq!(
+        m.node_count(),
+        g.node_count(),
+        "the graph doesn't belong to the matrix"
+    );
+
+    let mut out = Vec::new();
+    if m.node_count() == 0 {
+        return out;
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    for _ in 0..sources {
+        check_source(m, g, rng.below(m.node_count()), &mut out);
+    }
+    out
+}
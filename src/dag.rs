@@ -0,0 +1,172 @@
+//! The directed acyclic graph of all shortest paths from a single source.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+
+use PathMatrix;
+
+/// The shortest-path DAG of a source, as returned by ```PathMatrix::shortest_path_dag```.
+/// It contains every tight edge ```(u, v)```, for which ```d(src, u) + w(u, v) = d(src, v)``` holds,
+/// so every path from the source along its edges is a shortest path, and every shortest path is such a path.
+///
+/// Edges of weight 0 between nodes at the same distance are tight in both directions, so they make this graph cyclic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShortestPathDag {
+    source: usize,
+    dist: Vec<Option<usize>>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl ShortestPathDag {
+    /// Returns the source.
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.dist.len()
+    }
+
+    /// Returns the distance from the source to ```v```, or ```None```, if ```v``` isn't reachable from it.
+    pub fn distance(&self, v: usize) -> Option<usize> {
+        self.dist[v]
+    }
+
+    /// Returns the nodes, to which a tight edge leads from ```u```, ordered by their index.
+    pub fn successors(&self, u: usize) -> &[usize] {
+        &self.successors[u]
+    }
+
+    /// Returns the nodes, from which a tight edge leads to ```v```, ordered by their index.
+    /// These are the possible predecessors of ```v``` on a shortest path from the source.
+    pub fn predecessors(&self, v: usize) -> &[usize] {
+        &self.predecessors[v]
+    }
+
+    /// Returns the number of tight edges.
+    pub fn edge_count(&self) -> usize {
+        self.successors.iter().map(|s| s.len()).sum()
+    }
+
+    /// Returns an iterator over all tight edges ```(u, v)```.
+    pub fn edges<'a>(&'a self) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(|(u, s)| s.iter().map(move |&v| (u, v)))
+    }
+
+    /// Returns the reachable nodes ordered by their distance from the source, which is a topological order of the edges.
+    pub fn order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.node_count())
+            .filter(|&v| self.dist[v].is_some())
+            .collect();
+        order.sort_by_key(|&v| self.dist[v]);
+        order
+    }
+
+    /// Returns every shortest path from the source to ```v``` as the sequence of its nodes including both endpoints, ordered lexicographically.
+    /// They are enumerated by following the tight edges back from ```v```. Walks over a cycle of edges of weight 0 aren't simple paths and are left out.
+    /// Note, that the number of paths can grow exponentially with the number of nodes.
+    pub fn paths_to(&self, v: usize) -> Vec<Vec<usize>> {
+        let mut paths = Vec::new();
+        if self.dist[v].is_none() {
+            return paths;
+        }
+
+        let mut on_path = vec![false; self.node_count()];
+        on_path[v] = true;
+        self.collect_paths(&mut vec![v], &mut on_path, &mut paths);
+
+        paths.sort();
+        paths
+    }
+
+    /// Extends the reversed partial path ```path``` back to the source in every possible way and appends the finished paths to ```paths```.
+    fn collect_paths(
+        &self,
+        path: &mut Vec<usize>,
+        on_path: &mut [bool],
+        paths: &mut Vec<Vec<usize>>,
+    ) {
+        let u = *path.last().expect("the path starts at the target");
+        if u == self.source {
+            paths.push(path.iter().rev().cloned().collect());
+            return;
+        }
+
+        for &p in &self.predecessors[u] {
+            if !on_path[p] {
+                on_path[p] = true;
+                path.push(p);
+                self.collect_paths(path, on_path, paths);
+                path.pop();
+                on_path[p] = false;
+            }
+        }
+    }
+}
+
+impl<T> PathMatrix<T> {
+    /// Builds the DAG of all shortest paths from ```src``` in the given graph, which consists of all tight edges.
+    /// The graph has to be the one this matrix was computed for. The weights of its edges are taken from the graph,
+    /// because the matrix only keeps one of several paths of equal length, so an edge, which ties with a longer path, may not be stored.
+    pub fn shortest_path_dag<G>(&self, g: G, src: usize) -> ShortestPathDag
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        let n = self.node_count();
+        let dist: Vec<Option<usize>> = (0..n).map(|v| self.distance(src, v)).collect();
+        let mut successors = vec![Vec::new(); n];
+        let mut predecessors = vec![Vec::new(); n];
+
+        let directed = self.is_directed();
+        for e in g.edge_references() {
+            let (a, b) = (e.source().index(), e.target().index());
+            let w: usize = e.weight().clone().into();
+
+            let reverse = if directed { None } else { Some((b, a)) };
+            for (u, v) in Some((a, b)).into_iter().chain(reverse) {
+                let tight = match dist[u] {
+                    Some(du) => u != v && du.checked_add(w) == dist[v],
+                    None => false,
+                };
+                if tight {
+                    successors[u].push(v);
+                    predecessors[v].push(u);
+                }
+            }
+        }
+
+        // Parallel edges of the same weight are a single edge of the DAG.
+        for list in successors.iter_mut().chain(predecessors.iter_mut()) {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        ShortestPathDag {
+            source: src,
+            dist,
+            successors,
+            predecessors,
+        }
+    }
+
+    /// Returns every shortest path from ```i``` to ```j``` in the given graph as the sequence of its nodes including both endpoints, ordered lexicographically.
+    /// Other than ```get_path```, which only keeps one of several paths of equal length, this enumerates all of them,
+    /// which are found in the DAG of the tight edges from ```i```, see ```ShortestPathDag::paths_to```.
+    /// This is empty, if there is no path between them.
+    pub fn all_shortest_paths<G>(&self, g: G, i: usize, j: usize) -> Vec<Vec<usize>>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        self.shortest_path_dag(g, i).paths_to(j)
+    }
+}
@@ -0,0 +1,181 @@
+//! An alternative backend, which runs Dijkstra's algorithm from every node instead of the dynamic program.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeRef;
+
+use PathMatrix;
+
+const INFINITY: usize = usize::MAX;
+
+/// Runs Dijkstra's algorithm from ```s```. Of all shortest paths to a node, the one with the fewest edges is chosen,
+/// whose number of edges is stored in ```hops``` and whose last intermediate node is stored in ```pred```.
+pub(crate) fn dijkstra(
+    adj: &[Vec<(usize, usize)>],
+    s: usize,
+    dist: &mut [usize],
+    hops: &mut [usize],
+    pred: &mut [usize],
+) {
+    for u in 0..adj.len() {
+        dist[u] = INFINITY;
+        hops[u] = INFINITY;
+        pred[u] = s;
+    }
+    dist[s] = 0;
+    hops[s] = 0;
+
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((0, 0, s)));
+    while let Some(Reverse((d, h, u))) = heap.pop() {
+        if (d, h) > (dist[u], hops[u]) {
+            continue;
+        }
+
+        for &(v, w) in &adj[u] {
+            let next = (d.saturating_add(w), h + 1);
+            if next < (dist[v], hops[v]) {
+                dist[v] = next.0;
+                hops[v] = next.1;
+                pred[v] = u;
+                heap.push(Reverse((next.0, next.1, v)));
+            }
+        }
+    }
+}
+
+/// The adjacency list of a graph as (neighbour, weight) pairs, where an undirected edge is listed at both of its nodes.
+/// Loops are left out, because they are never part of a shortest path.
+fn adjacency<G>(g: G) -> Vec<Vec<(usize, usize)>>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut adj = vec![Vec::new(); g.node_count()];
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+        if u != v {
+            adj[u].push((v, w));
+            if !g.is_directed() {
+                adj[v].push((u, w));
+            }
+        }
+    }
+    adj
+}
+
+/// Returns the (distance, intermediate node) pairs of the paths from ```s``` to the nodes from ```first``` on,
+/// where a missing path has the distance ```INFINITY```, and a single edge has no intermediate node.
+fn row(
+    adj: &[Vec<(usize, usize)>],
+    s: usize,
+    first: usize,
+    buffers: &mut (Vec<usize>, Vec<usize>, Vec<usize>),
+) -> Vec<(usize, Option<usize>)> {
+    let (ref mut dist, ref mut hops, ref mut pred) = *buffers;
+    dijkstra(adj, s, dist, hops, pred);
+
+    // A path is split at its last intermediate node. The path to it is a shortest path with one edge less,
+    // so the splits of the path with the fewest edges always end, even if there are edges of length 0.
+    (first..adj.len())
+        .map(|t| (dist[t], if hops[t] > 1 { Some(pred[t]) } else { None }))
+        .collect()
+}
+
+/// Creates the matrix for the graph and stores the rows returned by ```row```, which end at the last node.
+fn assemble<G, I>(g: G, rows: I) -> PathMatrix<G::NodeWeight>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoNodeReferences + GraphProp,
+    G::NodeWeight: Clone,
+    I: IntoIterator<Item = Vec<(usize, Option<usize>)>>,
+{
+    let n = g.node_count();
+    let mut m = if g.is_directed() {
+        PathMatrix::new_directed(n)
+    } else {
+        PathMatrix::new(n)
+    };
+    m.set_node_weights(g.node_references().map(|n| n.weight().clone()));
+
+    for (s, row) in rows.into_iter().enumerate() {
+        let first = n - row.len();
+        for (t, (d, via)) in (first..n).zip(row) {
+            if d == INFINITY {
+                continue;
+            }
+            m.set_path_len(s, t, d);
+            if let Some(k) = via {
+                m.get_path_mut(s, t).set_via(k);
+            }
+        }
+    }
+    m
+}
+
+/// This function computes the same matrix as ```floyd_warshall```, but runs Dijkstra's algorithm from every node.
+/// This takes **O(V * (E + V) * log(V))** runtime, which is faster than the dynamic program on sparse graphs.
+/// Of several shortest paths, a different one may be chosen, and of several parallel edges, the lightest one is used.
+pub fn apsp_dijkstra<G>(g: G) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let adj = adjacency(g);
+    let directed = g.is_directed();
+    let mut buffers = (vec![0; n], vec![0; n], vec![0; n]);
+
+    // In an undirected graph, the pairs with a smaller node were already found by the search from that node.
+    let rows = (0..n).map(|s| row(&adj, s, if directed { 0 } else { s }, &mut buffers));
+    assemble(g, rows)
+}
+
+/// This function computes the same matrix as ```apsp_dijkstra```, but runs the searches from the nodes on all available cores.
+/// Afterwards, all paths are reconstructed up front in parallel like ```floyd_warshall_par```.
+#[cfg(feature = "rayon")]
+pub fn apsp_dijkstra_par<G>(g: G) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone + Send + Sync,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    use rayon::prelude::*;
+
+    let n = g.node_count();
+    let adj = adjacency(g);
+    let directed = g.is_directed();
+
+    let rows: Vec<_> = (0..n)
+        .into_par_iter()
+        .map_init(
+            || (vec![0; n], vec![0; n], vec![0; n]),
+            |buffers, s| row(&adj, s, if directed { 0 } else { s }, buffers),
+        )
+        .collect();
+
+    let mut m = assemble(g, rows);
+    m.par_materialize_paths();
+    m
+}
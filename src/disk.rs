@@ -0,0 +1,365 @@
+//! An out-of-core mode for graphs, whose distance matrix doesn't fit into memory.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::mem::size_of;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use DistanceMatrix;
+
+/// The stored length of a missing path.
+const INFINITY: u64 = u64::MAX;
+
+/// The number of tiles, which have to be in memory at once: The relaxed one and the two, which hold the paths to and from the intermediate nodes.
+const RESIDENT: usize = 3;
+
+/// Counts the tile files of this process, so every run gets a file of its own.
+static FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Describes, how much memory an out-of-core run may use, and where its tiles are stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfCore {
+    /// The number of bytes, which the tiles in memory may take up. The tile size is chosen so that at least three tiles fit.
+    pub memory_budget: usize,
+    /// The directory, in which the file of the tiles is created.
+    pub dir: PathBuf,
+}
+
+impl OutOfCore {
+    /// Describes a run with the given memory budget in bytes, whose tiles are stored in the temporary directory of the system.
+    pub fn new(memory_budget: usize) -> OutOfCore {
+        OutOfCore {
+            memory_budget,
+            dir: env::temp_dir(),
+        }
+    }
+
+    /// Returns the number of nodes per side of a tile of a matrix with ```n``` nodes.
+    fn tile_size(&self, n: usize) -> usize {
+        let tile_bytes = self.memory_budget / RESIDENT;
+        (((tile_bytes / size_of::<u64>()) as f64).sqrt() as usize).clamp(1, n.max(1))
+    }
+}
+
+/// The file of the tiles of a matrix, which is deleted, when this is dropped.
+#[derive(Debug)]
+struct TileFile {
+    file: Mutex<File>,
+    path: PathBuf,
+}
+
+impl Drop for TileFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl TileFile {
+    /// Creates a new file in the given directory.
+    fn create(dir: &Path) -> io::Result<TileFile> {
+        let name = format!(
+            "floyd-warshall-{}-{}.tiles",
+            process::id(),
+            FILES.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(TileFile {
+            file: Mutex::new(file),
+            path,
+        })
+    }
+
+    /// Reads ```len``` lengths from the given position in the file.
+    fn read(&self, pos: u64, len: usize) -> io::Result<Vec<u64>> {
+        let mut bytes = vec![0; len * size_of::<u64>()];
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.seek(SeekFrom::Start(pos * size_of::<u64>() as u64))?;
+        file.read_exact(&mut bytes)?;
+
+        Ok(bytes
+            .chunks_exact(size_of::<u64>())
+            .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+            .collect())
+    }
+
+    /// Writes the lengths to the given position in the file.
+    fn write(&self, pos: u64, values: &[u64]) -> io::Result<()> {
+        let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.seek(SeekFrom::Start(pos * size_of::<u64>() as u64))?;
+        file.write_all(&bytes)
+    }
+}
+
+/// The distances between every two nodes, which are stored in a file instead of memory.
+/// The file is split into square tiles, which are read one at a time, and it's deleted, when this is dropped.
+#[derive(Debug)]
+pub struct DiskDistances {
+    file: TileFile,
+    n: usize,
+    tile: usize,
+    tiles: usize,
+}
+
+impl DiskDistances {
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the position of the first length of the tile in the given row and column of tiles in the file.
+    fn tile_pos(&self, a: usize, b: usize) -> u64 {
+        ((a * self.tiles + b) * self.tile * self.tile) as u64
+    }
+
+    /// Returns the distance between ```i``` and ```j```, or ```None```, if there is no path between them. This reads the distance from the file.
+    pub fn get(&self, i: usize, j: usize) -> io::Result<Option<usize>> {
+        assert!(i < self.n && j < self.n);
+        let pos = self.tile_pos(i / self.tile, j / self.tile)
+            + ((i % self.tile) * self.tile + j % self.tile) as u64;
+        Ok(match self.file.read(pos, 1)?[0] {
+            INFINITY => None,
+            d => Some(d as usize),
+        })
+    }
+
+    /// Reads all distances into a ```DistanceMatrix```, which needs ```8 * V * V``` bytes of memory.
+    pub fn to_distance_matrix(&self) -> io::Result<DistanceMatrix> {
+        let n = self.n;
+        let mut m = vec![DistanceMatrix::INFINITY; n * n];
+        for a in 0..self.tiles {
+            for b in 0..self.tiles {
+                let values = self.file.read(self.tile_pos(a, b), self.tile * self.tile)?;
+                for (r, i) in (a * self.tile..n.min((a + 1) * self.tile)).enumerate() {
+                    for (c, j) in (b * self.tile..n.min((b + 1) * self.tile)).enumerate() {
+                        if values[r * self.tile + c] != INFINITY {
+                            m[i * n + j] = values[r * self.tile + c] as usize;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(DistanceMatrix::from_vec(n, m))
+    }
+}
+
+/// The tiles, which are currently in memory. Every tile is written back, when it's evicted, if it has changed.
+struct Cache<'a> {
+    d: &'a DiskDistances,
+    tiles: HashMap<(usize, usize), (Vec<u64>, bool)>,
+    /// The tiles in the order of their last use, where the least recently used one comes first.
+    lru: VecDeque<(usize, usize)>,
+    capacity: usize,
+}
+
+impl<'a> Cache<'a> {
+    /// Makes sure, that the tile is in memory, and marks it as the most recently used one.
+    fn load(&mut self, t: (usize, usize)) -> io::Result<()> {
+        if self.tiles.contains_key(&t) {
+            self.lru.retain(|&u| u != t);
+            self.lru.push_back(t);
+            return Ok(());
+        }
+
+        while self.tiles.len() >= self.capacity {
+            let evicted = self.lru.pop_front().expect("the cache is empty");
+            let (values, dirty) = self.tiles.remove(&evicted).expect("the tile isn't cached");
+            if dirty {
+                self.d
+                    .file
+                    .write(self.d.tile_pos(evicted.0, evicted.1), &values)?;
+            }
+        }
+
+        let values = self
+            .d
+            .file
+            .read(self.d.tile_pos(t.0, t.1), self.d.tile * self.d.tile)?;
+        self.tiles.insert(t, (values, false));
+        self.lru.push_back(t);
+        Ok(())
+    }
+
+    /// Relaxes the tile ```c``` over the intermediate nodes of the tile column ```k```,
+    /// which requires the tiles with the paths to and from them, so all three are loaded.
+    fn relax(&mut self, c: (usize, usize), k: usize) -> io::Result<()> {
+        let to_k = (c.0, k);
+        let from_k = (k, c.1);
+        self.load(to_k)?;
+        self.load(from_k)?;
+        self.load(c)?;
+
+        // The relaxed tile is taken out of the cache, so the other ones can be read while it's written.
+        // A tile, which is the relaxed one itself, is read from it instead.
+        let (mut values, _) = self.tiles.remove(&c).expect("the tile isn't cached");
+        {
+            let to_k = if to_k == c {
+                None
+            } else {
+                Some(&self.tiles[&to_k].0[..])
+            };
+            let from_k = if from_k == c {
+                None
+            } else {
+                Some(&self.tiles[&from_k].0[..])
+            };
+            relax_tile(&mut values, to_k, from_k, self.d.tile);
+        }
+        self.tiles.insert(c, (values, true));
+        Ok(())
+    }
+
+    /// Writes all changed tiles back into the file.
+    fn flush(&mut self) -> io::Result<()> {
+        for (&t, &mut (ref values, ref mut dirty)) in &mut self.tiles {
+            if *dirty {
+                self.d.file.write(self.d.tile_pos(t.0, t.1), values)?;
+                *dirty = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Relaxes the pairs of the tile ```c``` over the intermediate nodes of a tile, where ```to_k``` holds the paths to them,
+/// and ```from_k``` the paths from them. If one of these is ```None```, it's ```c``` itself.
+fn relax_tile(c: &mut [u64], to_k: Option<&[u64]>, from_k: Option<&[u64]>, t: usize) {
+    for k in 0..t {
+        for i in 0..t {
+            let d_ik = match to_k {
+                Some(a) => a[i * t + k],
+                None => c[i * t + k],
+            };
+            if d_ik == INFINITY {
+                continue;
+            }
+
+            for j in 0..t {
+                let d_kj = match from_k {
+                    Some(b) => b[k * t + j],
+                    None => c[k * t + j],
+                };
+                // If one of the parts is missing, this saturates to INFINITY.
+                let over_k = d_ik.saturating_add(d_kj);
+                if over_k < c[i * t + j] {
+                    c[i * t + j] = over_k;
+                }
+            }
+        }
+    }
+}
+
+/// This function computes the distances between every two nodes in the graph like ```floyd_warshall```,
+/// but stores them in a file, so the matrix doesn't need to fit into memory. The paths aren't stored.
+///
+/// The file is split into square tiles, of which only as many are kept in memory, as fit into ```config.memory_budget```.
+/// They are relaxed by the blocked variant of the algorithm, which relaxes every tile by a whole block of intermediate nodes at once,
+/// so every tile is only read and written about three times per block.
+pub fn floyd_warshall_out_of_core<G>(g: G, config: &OutOfCore) -> io::Result<DiskDistances>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let tile = config.tile_size(n);
+    let tiles = n.div_ceil(tile);
+    let d = DiskDistances {
+        file: TileFile::create(&config.dir)?,
+        n,
+        tile,
+        tiles,
+    };
+
+    // The edges are sorted into their tiles, so every tile is written once. Of several parallel edges, the last one is used like in the matrix.
+    let mut edges: HashMap<(usize, usize), Vec<(usize, u64)>> = HashMap::new();
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+        if u == v {
+            continue;
+        }
+
+        let mut add = |i: usize, j: usize| {
+            let p = (i % tile) * tile + j % tile;
+            edges
+                .entry((i / tile, j / tile))
+                .or_default()
+                .push((p, w as u64));
+        };
+        add(u, v);
+        if !g.is_directed() {
+            add(v, u);
+        }
+    }
+
+    // The nodes of the incomplete tiles at the end don't exist, so all their lengths stay INFINITY.
+    for a in 0..tiles {
+        for b in 0..tiles {
+            let mut values = vec![INFINITY; tile * tile];
+            if a == b {
+                for r in 0..tile.min(n - a * tile) {
+                    values[r * tile + r] = 0;
+                }
+            }
+            for &(p, w) in edges.get(&(a, b)).into_iter().flatten() {
+                values[p] = w;
+            }
+            d.file.write(d.tile_pos(a, b), &values)?;
+        }
+    }
+
+    {
+        let mut cache = Cache {
+            d: &d,
+            tiles: HashMap::new(),
+            lru: VecDeque::new(),
+            capacity: (config.memory_budget / (tile * tile * size_of::<u64>())).max(RESIDENT),
+        };
+
+        // Each block of intermediate nodes is first used to relax its tile on the diagonal, then the other tiles in its row and column,
+        // which only depend on the diagonal tile, and then all remaining tiles, which only depend on the tiles in its row and column.
+        for k in 0..tiles {
+            cache.relax((k, k), k)?;
+            for b in (0..tiles).filter(|&b| b != k) {
+                cache.relax((k, b), k)?;
+                cache.relax((b, k), k)?;
+            }
+            for a in (0..tiles).filter(|&a| a != k) {
+                for b in (0..tiles).filter(|&b| b != k) {
+                    cache.relax((a, b), k)?;
+                }
+            }
+        }
+        cache.flush()?;
+    }
+
+    Ok(d)
+}
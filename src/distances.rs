@@ -0,0 +1,49 @@
+//! A distance-only mode, which skips all bookkeeping of the paths.
+
+use std::mem::size_of;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use minplus::relax_tiled;
+use DistanceMatrix;
+use Options;
+
+const INFINITY: usize = DistanceMatrix::INFINITY;
+
+/// This function computes the distances between every two nodes in the graph like ```floyd_warshall```, but doesn't store the paths.
+/// The distances are kept in a single flat array, which is relaxed by the blocked variant of the algorithm,
+/// so this needs only ```size_of::<usize>() * V * V``` bytes, like ```estimate_memory``` predicts for ```MemoryMode::Distances```, and runs faster than ```floyd_warshall```. The node weights aren't needed.
+pub fn floyd_warshall_distances<G>(g: G) -> DistanceMatrix
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let mut d = vec![INFINITY; n * n];
+    for i in 0..n {
+        d[i * n + i] = 0;
+    }
+
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+        if u != v {
+            d[u * n + v] = w;
+            if !g.is_directed() {
+                d[v * n + u] = w;
+            }
+        }
+    }
+
+    let tile = Options::default().tile_size_for(size_of::<usize>());
+    relax_tiled(&mut d, n, tile);
+
+    DistanceMatrix::from_vec(n, d)
+}
@@ -0,0 +1,114 @@
+//! An event log of a run in the JSON-lines format, which external tools can replay.
+
+use std::fmt;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use kernel::Kernel;
+use Relaxation;
+
+/// The writer together with the first error, which happened while writing to it.
+struct Sink {
+    w: BufWriter<Box<dyn Write + Send>>,
+    error: Option<io::Error>,
+}
+
+/// A writer for the events of a run, which is passed to the run by ```Options::event_log```.
+/// This is a handle, so a clone of it still refers to the same writer.
+///
+/// Every line is a JSON object with an ```"event"``` field. A run writes:
+///
+/// * ```{"event":"start","nodes":n}``` at the beginning,
+/// * ```{"event":"edge","i":i,"j":j,"len":len}``` for every pair with ```i < j```, which is connected by an edge initially,
+/// * ```{"event":"iteration","k":k}``` before the iteration over the intermediate node ```k``` and
+/// * ```{"event":"relax","k":k,"i":i,"j":j,"old":old,"new":new}``` for every relaxation, that makes a path shorter, where ```old``` is ```null```, if there was no path yet.
+///
+/// Errors of the writer don't stop the run. The first one is kept and returned by ```finish```.
+#[derive(Clone)]
+pub struct EventLog(Arc<Mutex<Sink>>);
+
+impl EventLog {
+    /// Creates an event log, which writes to ```w```. The output is buffered, so ```finish``` has to be called after the run.
+    pub fn new<W: Write + Send + 'static>(w: W) -> EventLog {
+        EventLog(Arc::new(Mutex::new(Sink {
+            w: BufWriter::new(Box::new(w)),
+            error: None,
+        })))
+    }
+
+    /// Writes a single line, unless writing failed before.
+    fn line(&self, args: fmt::Arguments<'_>) {
+        let mut sink = self.0.lock().unwrap();
+        if sink.error.is_none() {
+            if let Err(e) = sink.w.write_fmt(args).and_then(|_| sink.w.write_all(b"\n")) {
+                sink.error = Some(e);
+            }
+        }
+    }
+
+    /// Writes the start of a run on the loaded ```kernel``` together with its initial edges.
+    pub(crate) fn start(&self, kernel: &Kernel) {
+        let n = kernel.node_count();
+        self.line(format_args!(r#"{{"event":"start","nodes":{}}}"#, n));
+
+        // An undirected graph lists every edge once, a directed graph every arc on its own.
+        let d = kernel.distances();
+        for i in 0..n {
+            let first = if kernel.is_directed() { 0 } else { i + 1 };
+            for j in (first..n).filter(|&j| j != i) {
+                if d[i * n + j] != usize::MAX {
+                    self.line(format_args!(
+                        r#"{{"event":"edge","i":{},"j":{},"len":{}}}"#,
+                        i,
+                        j,
+                        d[i * n + j]
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Writes the beginning of the iteration over the intermediate node ```k```.
+    pub(crate) fn iteration(&self, k: usize) {
+        self.line(format_args!(r#"{{"event":"iteration","k":{}}}"#, k));
+    }
+
+    /// Writes a relaxation.
+    pub(crate) fn relax(&self, r: &Relaxation) {
+        let old = match r.old {
+            Some(old) => old.to_string(),
+            None => "null".to_string(),
+        };
+        self.line(format_args!(
+            r#"{{"event":"relax","k":{},"i":{},"j":{},"old":{},"new":{}}}"#,
+            r.k, r.i, r.j, old, r.new
+        ));
+    }
+
+    /// Flushes the writer and returns the first error, which happened while writing the log.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut sink = self.0.lock().unwrap();
+        match sink.error.take() {
+            Some(e) => Err(e),
+            None => sink.w.flush(),
+        }
+    }
+}
+
+impl fmt::Debug for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventLog")
+    }
+}
+
+impl PartialEq for EventLog {
+    fn eq(&self, other: &EventLog) -> bool {
+        // Two logs are only the same, if they are the same object.
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for EventLog {}
@@ -0,0 +1,87 @@
+//! A readable itinerary of a shortest path, for example for reports.
+
+use std::fmt;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdges;
+
+use PathMatrix;
+
+/// A single hop of a path, which is passed to the formatting closure of ```PathMatrix::explain_path```.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hop<'a, T: 'a, E> {
+    /// The position of the hop on the path, starting at 1.
+    pub step: usize,
+    /// The index of the node, where the hop starts.
+    pub from: usize,
+    /// The index of the node, where the hop ends.
+    pub to: usize,
+    /// The weight of the node, where the hop starts.
+    pub from_label: &'a T,
+    /// The weight of the node, where the hop ends.
+    pub to_label: &'a T,
+    /// The edge of the graph, which is traversed by the hop.
+    pub edge: E,
+    /// The weight of the edge.
+    pub weight: usize,
+    /// The length of the path up to and including this hop.
+    pub distance: usize,
+}
+
+/// Formats the hop as ```step. from -> to: weight (total distance)```.
+impl<'a, T: fmt::Display, E> fmt::Display for Hop<'a, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}. {} -> {}: {} (total {})",
+            self.step, self.from_label, self.to_label, self.weight, self.distance
+        )
+    }
+}
+
+impl<T: Clone> PathMatrix<T> {
+    /// Renders the shortest path from ```i``` to ```j``` as an itinerary with one line per hop, which is produced by ```fmt```.
+    /// The lines are separated by newlines, so ```|h| h.to_string()``` renders lines like ```2. B -> C: 4 (total 7)```.
+    /// The graph has to be the one this matrix was computed for. If ```i == j```, the itinerary is empty.
+    /// This is ```None```, if there is no path between them.
+    ///
+    /// # Panics
+    ///
+    /// The node weights are used as labels, so this panics for a matrix, which wasn't computed from a graph.
+    pub fn explain_path<G, F>(&self, i: usize, j: usize, g: G, mut fmt: F) -> Option<String>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdges,
+        G::EdgeWeight: Clone + Into<usize>,
+        F: FnMut(&Hop<'_, T, G::EdgeId>) -> String,
+    {
+        let edges = self.get_path_edges(i, j, g)?;
+
+        let nodes: Vec<usize> = self.path_iter(i, j).collect();
+
+        let label = |n| self.node_weight(n).expect("the node weights are unknown");
+        let mut distance = 0;
+        let lines: Vec<String> = nodes
+            .windows(2)
+            .zip(edges)
+            .enumerate()
+            .map(|(step, (pair, edge))| {
+                let weight = self.get_path_len(pair[0], pair[1]);
+                distance += weight;
+                fmt(&Hop {
+                    step: step + 1,
+                    from: pair[0],
+                    to: pair[1],
+                    from_label: label(pair[0]),
+                    to_label: label(pair[1]),
+                    edge,
+                    weight,
+                    distance,
+                })
+            })
+            .collect();
+
+        Some(lines.join("\n"))
+    }
+}
@@ -0,0 +1,104 @@
+//! Facility location heuristics on the metric of a ```PathMatrix```.
+
+use PathMatrix;
+
+/// The result of a k-center selection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KCenters {
+    /// The chosen centers in the order they were selected.
+    pub centers: Vec<usize>,
+    /// The covering radius, which is the largest distance of any node to its closest center.
+    /// If some node can't reach any center, this is ```None```.
+    pub radius: Option<usize>,
+}
+
+impl<T> PathMatrix<T> {
+    /// Selects ```k``` centers by the greedy farthest-point heuristic, which is a 2-approximation of the optimal covering radius.
+    /// The first center is node 0, every further center is the node farthest away from all centers chosen so far, which isn't a center itself.
+    /// Nodes, which can't reach any center yet, are preferred, so every connected component gets a center as long as possible.
+    pub fn k_center(&self, k: usize) -> KCenters {
+        let n = self.node_count();
+        let mut centers = Vec::new();
+
+        // For every node, the distance to the closest center so far. None means, that no center is reachable.
+        let mut closest: Vec<Option<usize>> = vec![None; n];
+        let mut is_center = vec![false; n];
+
+        let mut next = 0;
+        while centers.len() < k.min(n) {
+            centers.push(next);
+            is_center[next] = true;
+            for (j, c) in closest.iter_mut().enumerate() {
+                if let Some(d) = self.distance(next, j) {
+                    *c = Some(c.map_or(d, |c| c.min(d)));
+                }
+            }
+
+            // The next center is the node, which is farthest away from all centers.
+            // Covered nodes are at distance 0 like the centers themselves, so the centers have to be skipped explicitly.
+            let mut farthest = None;
+            for (j, &c) in closest.iter().enumerate().filter(|&(j, _)| !is_center[j]) {
+                let better = match (farthest, c) {
+                    (None, _) => true,
+                    (Some((_, None)), _) => false,
+                    (Some(_), None) => true,
+                    (Some((_, Some(fd))), Some(d)) => d > fd,
+                };
+                if better {
+                    farthest = Some((j, c));
+                }
+            }
+            next = match farthest {
+                Some((j, _)) => j,
+                None => break,
+            };
+        }
+
+        let radius = if centers.is_empty() {
+            None
+        } else {
+            closest
+                .iter()
+                .try_fold(0, |r: usize, &c| c.map(|c| r.max(c)))
+        };
+
+        KCenters { centers, radius }
+    }
+
+    /// Returns the node, which minimizes the total distance to all other nodes, together with that total distance.
+    /// Nodes, which can't reach every other node, are not considered, so this is ```None``` for a disconnected graph.
+    /// Ties are broken by the smaller node index.
+    pub fn one_median(&self) -> Option<(usize, usize)> {
+        self.weighted_one_median(&vec![1; self.node_count()])
+    }
+
+    /// Returns the node, which minimizes the sum of ```demand[j] * d(i, j)``` over all nodes ```j```, together with that sum.
+    /// Nodes, which can't reach every node with a positive demand, are not considered.
+    /// Ties are broken by the smaller node index.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if ```demand``` doesn't contain an entry for every node.
+    pub fn weighted_one_median(&self, demand: &[usize]) -> Option<(usize, usize)> {
+        let n = self.node_count();
+        assert_eq!(demand.len(), n, "there must be a demand for every node");
+
+        let mut best: Option<(usize, usize)> = None;
+        for i in 0..n {
+            let cost = (0..n)
+                .filter(|&j| demand[j] > 0)
+                .try_fold(0usize, |sum, j| {
+                    self.distance(i, j)
+                        .map(|d| sum.saturating_add(d.saturating_mul(demand[j])))
+                });
+
+            if let Some(cost) = cost {
+                if best.is_none_or(|(_, b)| cost < b) {
+                    best = Some((i, cost));
+                }
+            }
+        }
+
+        best
+    }
+}
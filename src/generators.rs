@@ -0,0 +1,103 @@
+//! Generators for random and regular graphs, which give reproducible workloads for tests and benchmarks.
+//!
+//! Every generator returns an undirected ```Graph```, whose node weights are the node indices.
+//! The edge weights are drawn uniformly from ```1..=max_weight```, and the same ```seed``` always gives the same graph.
+
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+use petgraph::Undirected;
+
+use rng::SplitMix64;
+
+/// An undirected graph as returned by the generators.
+pub type UnGraph = Graph<usize, usize, Undirected>;
+
+/// Creates a graph with ```n``` nodes and no edges.
+fn nodes(n: usize) -> UnGraph {
+    let mut g = Graph::with_capacity(n, 0);
+    for i in 0..n {
+        g.add_node(i);
+    }
+    g
+}
+
+/// Adds an edge between ```a``` and ```b``` with a random weight in ```1..=max_weight```.
+fn add_edge(g: &mut UnGraph, rng: &mut SplitMix64, a: usize, b: usize, max_weight: usize) {
+    let w = 1 + rng.below(max_weight.max(1));
+    g.add_edge(NodeIndex::new(a), NodeIndex::new(b), w);
+}
+
+/// Generates an Erdős–Rényi graph G(n, p) with ```n``` nodes, where every pair of nodes is connected with probability ```p```.
+pub fn gnp(n: usize, p: f64, max_weight: usize, seed: u64) -> UnGraph {
+    let mut rng = SplitMix64::new(seed);
+    let mut g = nodes(n);
+    for a in 0..n {
+        for b in a + 1..n {
+            if rng.next_f64() < p {
+                add_edge(&mut g, &mut rng, a, b, max_weight);
+            }
+        }
+    }
+    g
+}
+
+/// Generates a grid with ```rows * cols``` nodes, where every node is connected to its right and lower neighbour.
+/// The node in row ```r``` and column ```c``` has the index ```r * cols + c```.
+pub fn grid(rows: usize, cols: usize, max_weight: usize, seed: u64) -> UnGraph {
+    let mut rng = SplitMix64::new(seed);
+    let mut g = nodes(rows * cols);
+    for r in 0..rows {
+        for c in 0..cols {
+            let i = r * cols + c;
+            if c + 1 < cols {
+                add_edge(&mut g, &mut rng, i, i + 1, max_weight);
+            }
+            if r + 1 < rows {
+                add_edge(&mut g, &mut rng, i, i + cols, max_weight);
+            }
+        }
+    }
+    g
+}
+
+/// Generates a complete graph with ```n``` nodes.
+pub fn complete(n: usize, max_weight: usize, seed: u64) -> UnGraph {
+    gnp(n, 1.0, max_weight, seed)
+}
+
+/// Generates a scale-free graph with ```n``` nodes by the Barabási–Albert model.
+/// It starts with a complete graph on ```m + 1``` nodes, and every further node is connected to ```m``` distinct earlier nodes,
+/// which are chosen with a probability proportional to their degree.
+pub fn scale_free(n: usize, m: usize, max_weight: usize, seed: u64) -> UnGraph {
+    let mut rng = SplitMix64::new(seed);
+    let mut g = nodes(n);
+    let start = (m + 1).min(n);
+
+    // Every node appears in this list once per incident edge, so drawing from it picks nodes proportional to their degree.
+    let mut endpoints = Vec::new();
+    for a in 0..start {
+        for b in a + 1..start {
+            add_edge(&mut g, &mut rng, a, b, max_weight);
+            endpoints.push(a);
+            endpoints.push(b);
+        }
+    }
+
+    let mut targets = Vec::with_capacity(m);
+    for a in start..n {
+        targets.clear();
+        while targets.len() < m {
+            let b = endpoints[rng.below(endpoints.len())];
+            if !targets.contains(&b) {
+                targets.push(b);
+            }
+        }
+
+        for &b in &targets {
+            add_edge(&mut g, &mut rng, a, b, max_weight);
+            endpoints.push(a);
+            endpoints.push(b);
+        }
+    }
+    g
+}
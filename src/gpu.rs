@@ -0,0 +1,310 @@
+//! A backend, which runs the dynamic program in a compute shader on the GPU.
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::thread;
+use std::thread::Thread;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use wgpu::util::DeviceExt;
+
+use DistanceMatrix;
+use DistanceOverflow;
+
+/// The length of a missing path on the GPU.
+const INFINITY: u32 = u32::MAX;
+
+/// Every length from this value on doesn't fit and marks a path, which overflowed.
+const OVERFLOW: u32 = u32::MAX - 1;
+
+/// The number of threads per side of a square workgroup.
+const WORKGROUP: u32 = 16;
+
+/// Relaxes every pair over the intermediate node ```k``` in one thread per pair.
+/// The row and the column of ```k``` don't change in this iteration, so the threads don't depend on each other.
+const SHADER: &str = r#"
+struct Params {
+    n: u32,
+    k: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> dist: array<u32>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+const INFINITY: u32 = 0xffffffffu;
+const OVERFLOW: u32 = 0xfffffffeu;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = params.n;
+    let k = params.k;
+    let i = id.y;
+    let j = id.x;
+    if (i >= n || j >= n) {
+        return;
+    }
+
+    let to_k = dist[i * n + k];
+    let from_k = dist[k * n + j];
+    if (to_k == INFINITY || from_k == INFINITY) {
+        return;
+    }
+
+    // A sum, which doesn't fit, is clamped to OVERFLOW. As every path built on top of it is at least as long, it stays marked.
+    var over_k = to_k + from_k;
+    if (over_k < to_k || over_k > OVERFLOW) {
+        over_k = OVERFLOW;
+    }
+    if (over_k < dist[i * n + j]) {
+        dist[i * n + j] = over_k;
+    }
+}
+"#;
+
+/// The reason, why ```floyd_warshall_gpu``` couldn't compute the distances.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GpuError {
+    /// There is no GPU, which supports compute shaders.
+    NoAdapter,
+    /// The GPU couldn't be opened. This contains the message of ```wgpu```.
+    Device(String),
+    /// The matrix is larger than the largest buffer of the GPU.
+    TooLarge,
+    /// An edge weight or a distance doesn't fit into the 32 bits per distance on the GPU.
+    Overflow(DistanceOverflow),
+}
+
+/// Wakes up the thread, which waits for a future in ```block_on```.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Waits for the future on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// This function computes the distances between every two nodes in the graph like ```floyd_warshall```, but runs the dynamic program
+/// in a compute shader on the GPU, one dispatch per intermediate node. Afterwards, the distances are copied back into a ```DistanceMatrix```.
+/// The paths aren't stored, and every distance is stored in 32 bits on the GPU, so an error is returned, if one doesn't fit.
+///
+/// For graphs with many thousands of nodes, this is much faster than the algorithm on the CPU.
+/// The matrix has to fit into a single buffer of the GPU, which holds ```4 * V * V``` bytes.
+pub fn floyd_warshall_gpu<G>(g: G) -> Result<DistanceMatrix, GpuError>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let mut d = vec![INFINITY; n * n];
+    for i in 0..n {
+        d[i * n + i] = 0;
+    }
+
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+        if w >= OVERFLOW as usize {
+            return Err(GpuError::Overflow(DistanceOverflow::Edge(u, v)));
+        }
+        if u != v {
+            d[u * n + v] = w as u32;
+            if !g.is_directed() {
+                d[v * n + u] = w as u32;
+            }
+        }
+    }
+
+    if n > 0 {
+        relax(&mut d, n)?;
+    }
+
+    // A pair, whose distance is still marked, has no path short enough to be stored.
+    if let Some(p) = d.iter().position(|&x| x == OVERFLOW) {
+        return Err(GpuError::Overflow(DistanceOverflow::Path(p / n, p % n)));
+    }
+
+    let d = d
+        .into_iter()
+        .map(|x| match x {
+            INFINITY => DistanceMatrix::INFINITY,
+            x => x as usize,
+        })
+        .collect();
+    Ok(DistanceMatrix::from_vec(n, d))
+}
+
+/// Runs all iterations of the dynamic program on the ```n * n``` distances ```d``` on the GPU, and copies the result back into ```d```.
+fn relax(d: &mut [u32], n: usize) -> Result<(), GpuError> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    }))
+    .ok_or(GpuError::NoAdapter)?;
+
+    // The whole matrix is bound at once, so the largest buffers of the GPU are requested.
+    let limits = adapter.limits();
+    let bytes = (n * n * 4) as u64;
+    if n > u32::MAX as usize / n
+        || bytes > limits.max_buffer_size
+        || bytes > limits.max_storage_buffer_binding_size as u64
+    {
+        return Err(GpuError::TooLarge);
+    }
+    let (device, queue) = block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: None,
+            required_features: wgpu::Features::empty(),
+            required_limits: limits.clone(),
+            memory_hints: wgpu::MemoryHints::Performance,
+        },
+        None,
+    ))
+    .map_err(|e| GpuError::Device(e.to_string()))?;
+
+    let dist = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("distances"),
+        contents: &d.iter().flat_map(|x| x.to_ne_bytes()).collect::<Vec<u8>>(),
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+    });
+
+    // The parameters of every iteration are stored one after another, each at an offset, which the GPU can bind.
+    let stride = limits.min_uniform_buffer_offset_alignment.max(8) as usize;
+    let mut params = vec![0u8; n * stride];
+    for k in 0..n {
+        params[k * stride..k * stride + 4].copy_from_slice(&(n as u32).to_ne_bytes());
+        params[k * stride + 4..k * stride + 8].copy_from_slice(&(k as u32).to_ne_bytes());
+    }
+    let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("parameters"),
+        contents: &params,
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: None,
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(8),
+                },
+                count: None,
+            },
+        ],
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: dist.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &params,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(8),
+                }),
+            },
+        ],
+    });
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: Some(
+            &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&layout],
+                push_constant_ranges: &[],
+            }),
+        ),
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    // Every iteration runs in a pass of its own, so it sees all writes of the previous one.
+    let groups = (n as u32).div_ceil(WORKGROUP);
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    for k in 0..n {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[(k * stride) as u32]);
+        pass.dispatch_workgroups(groups, groups, 1);
+    }
+
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("staging"),
+        size: bytes,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    encoder.copy_buffer_to_buffer(&dist, 0, &staging, 0, bytes);
+    queue.submit(Some(encoder.finish()));
+
+    let (sender, receiver) = mpsc::channel();
+    staging
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+    device.poll(wgpu::Maintain::Wait);
+    receiver
+        .recv()
+        .map_err(|e| GpuError::Device(e.to_string()))?
+        .map_err(|e| GpuError::Device(e.to_string()))?;
+
+    let view = staging.slice(..).get_mapped_range();
+    for (x, bytes) in d.iter_mut().zip(view.chunks_exact(4)) {
+        *x = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    Ok(())
+}
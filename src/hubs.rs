@@ -0,0 +1,210 @@
+//! A 2-hop cover of the distances, which answers distance queries from small per-node labels.
+
+use Distances;
+
+/// The labels of all nodes, flattened into their offsets and entries.
+type Labels = (Vec<usize>, Vec<(usize, usize)>);
+
+/// A 2-hop cover (hub labeling) of the distances of a graph.
+/// Every node has a label, which is a list of hubs together with their distances to the node.
+/// The distance between two nodes is the smallest sum of the distances to a hub, which is in both labels.
+///
+/// If the distances aren't symmetric, as in a directed graph, every node has a second label with the distances from the hubs to the node.
+/// The distance from ```i``` to ```j``` is then found over the hubs in the first label of ```i``` and the second label of ```j```.
+#[derive(Clone, Debug)]
+pub struct HubLabels {
+    n: usize,
+    /// The hubs ordered by their rank, so ```order[r]``` is the node with rank ```r```.
+    order: Vec<usize>,
+    /// The label of node ```i``` is ```entries[offsets[i]..offsets[i + 1]]```, sorted by the rank of the hub.
+    offsets: Vec<usize>,
+    /// The entries of all labels as pairs of (rank of the hub, distance to the hub).
+    entries: Vec<(usize, usize)>,
+    /// The offsets and entries of the labels with the distances from the hubs, if the distances aren't symmetric.
+    backward: Option<Labels>,
+}
+
+/// Returns the smallest sum of distances over the hubs, which are in both labels. Both labels have to be sorted by rank.
+fn merge(a: &[(usize, usize)], b: &[(usize, usize)]) -> Option<usize> {
+    let mut best = None;
+    let mut x = 0;
+    let mut y = 0;
+    while x < a.len() && y < b.len() {
+        if a[x].0 < b[y].0 {
+            x += 1;
+        } else if a[x].0 > b[y].0 {
+            y += 1;
+        } else {
+            let d = a[x].1.saturating_add(b[y].1);
+            best = Some(best.map_or(d, |best: usize| best.min(d)));
+            x += 1;
+            y += 1;
+        }
+    }
+    best
+}
+
+/// Builds a hub labeling of the given distances. The nodes are used as hubs in the order of ascending total distance to all other nodes,
+/// so central nodes are tried first. See ```hub_labels_with_order``` for details.
+pub fn hub_labels<D: Distances>(d: &D) -> HubLabels {
+    let n = d.node_count();
+
+    // Unreachable nodes are counted with a large distance, so the nodes of larger components come first.
+    let mut order: Vec<(usize, usize)> = (0..n)
+        .map(|i| {
+            let total = (0..n).fold(0usize, |total, j| {
+                total.saturating_add(d.distance(i, j).unwrap_or(n.saturating_mul(n)))
+            });
+            (total, i)
+        })
+        .collect();
+    order.sort_unstable();
+
+    let order: Vec<usize> = order.into_iter().map(|(_, i)| i).collect();
+    hub_labels_with_order(d, &order)
+}
+
+/// Does every distance equal the distance of the reversed pair?
+fn is_symmetric<D: Distances>(d: &D) -> bool {
+    let n = d.node_count();
+    (0..n).all(|i| (i + 1..n).all(|j| d.distance(i, j) == d.distance(j, i)))
+}
+
+/// Flattens the given labels into the offsets and the entries of all labels.
+fn flatten(labels: Vec<Vec<(usize, usize)>>) -> Labels {
+    let mut offsets = Vec::with_capacity(labels.len() + 1);
+    let mut entries = Vec::with_capacity(labels.iter().map(Vec::len).sum());
+    offsets.push(0);
+    for label in labels {
+        entries.extend(label);
+        offsets.push(entries.len());
+    }
+    (offsets, entries)
+}
+
+/// Builds a hub labeling of the given distances, which tries the nodes as hubs in the given order.
+/// A node becomes a hub of another node only, if the labels so far don't give their exact distance yet (pruned labeling),
+/// so the labels stay small, if the first nodes in the order lie on many shortest paths.
+/// This takes **O(V^(2))** distance lookups and label merges.
+///
+/// # Panics
+///
+/// Panics, if ```order``` isn't a permutation of the nodes.
+pub fn hub_labels_with_order<D: Distances>(d: &D, order: &[usize]) -> HubLabels {
+    let n = d.node_count();
+    assert_eq!(order.len(), n, "the order has to contain every node once");
+
+    let mut seen = vec![false; n];
+    for &h in order {
+        assert!(!seen[h], "the order has to contain every node once");
+        seen[h] = true;
+    }
+
+    // The forward label of a node holds the distances from it to its hubs, and the backward label the distances from its hubs to it.
+    // Both are the same for symmetric distances, so only one of them is built then.
+    let symmetric = is_symmetric(d);
+    let mut forward: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    let mut backward: Vec<Vec<(usize, usize)>> = vec![Vec::new(); if symmetric { 0 } else { n }];
+
+    for (rank, &h) in order.iter().enumerate() {
+        // The ranks are increasing, so every label stays sorted by rank.
+        // No other label contains h yet, so adding h to its own label doesn't change the pruning below.
+        forward[h].push((rank, 0));
+        if symmetric {
+            for u in (0..n).filter(|&u| u != h) {
+                if let Some(len) = d.distance(h, u) {
+                    if merge(&forward[h], &forward[u]).is_none_or(|covered| covered > len) {
+                        forward[u].push((rank, len));
+                    }
+                }
+            }
+            continue;
+        }
+
+        backward[h].push((rank, 0));
+        for u in (0..n).filter(|&u| u != h) {
+            if let Some(len) = d.distance(u, h) {
+                if merge(&forward[u], &backward[h]).is_none_or(|covered| covered > len) {
+                    forward[u].push((rank, len));
+                }
+            }
+        }
+        for v in (0..n).filter(|&v| v != h) {
+            if let Some(len) = d.distance(h, v) {
+                if merge(&forward[h], &backward[v]).is_none_or(|covered| covered > len) {
+                    backward[v].push((rank, len));
+                }
+            }
+        }
+    }
+
+    let (offsets, entries) = flatten(forward);
+    HubLabels {
+        n,
+        order: order.to_vec(),
+        offsets,
+        entries,
+        backward: if symmetric {
+            None
+        } else {
+            Some(flatten(backward))
+        },
+    }
+}
+
+impl HubLabels {
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the label of node ```i``` as pairs of (hub, distance to the hub).
+    pub fn label<'a>(&'a self, i: usize) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.entries[self.offsets[i]..self.offsets[i + 1]]
+            .iter()
+            .map(move |&(rank, len)| (self.order[rank], len))
+    }
+
+    /// Returns the second label of node ```i``` as pairs of (hub, distance from the hub).
+    /// For symmetric distances, this is the same as ```label```.
+    pub fn backward_label<'a>(&'a self, i: usize) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.backward_entries(i)
+            .iter()
+            .map(move |&(rank, len)| (self.order[rank], len))
+    }
+
+    /// Returns the entries of the second label of node ```i```.
+    fn backward_entries(&self, i: usize) -> &[(usize, usize)] {
+        match self.backward {
+            Some((ref offsets, ref entries)) => &entries[offsets[i]..offsets[i + 1]],
+            None => &self.entries[self.offsets[i]..self.offsets[i + 1]],
+        }
+    }
+
+    /// Returns the total number of entries in all labels, which is a measure of the size of this structure.
+    #[inline]
+    pub fn total_label_size(&self) -> usize {
+        self.entries.len() + self.backward.as_ref().map_or(0, |b| b.1.len())
+    }
+
+    /// Returns the distance from ```i``` to ```j```, or ```None```, if there is no path between them.
+    pub fn get(&self, i: usize, j: usize) -> Option<usize> {
+        merge(
+            &self.entries[self.offsets[i]..self.offsets[i + 1]],
+            self.backward_entries(j),
+        )
+    }
+}
+
+impl Distances for HubLabels {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.get(i, j)
+    }
+}
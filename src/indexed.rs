@@ -0,0 +1,100 @@
+//! A view on a ```PathMatrix```, which is addressed by the node indices of petgraph instead of raw positions.
+
+use std::ops::Index;
+
+use petgraph::graph::NodeIndex;
+
+use Path;
+use PathMatrix;
+
+/// A view on a ```PathMatrix```, whose accessors take ```NodeIndex``` instead of ```usize```.
+/// Passing a position, which isn't a node index of the graph, becomes a compile error this way.
+#[derive(Clone, Copy, Debug)]
+pub struct NodeIndexed<'a, T: 'a> {
+    m: &'a PathMatrix<T>,
+}
+
+impl<T> PathMatrix<T> {
+    /// Returns a view on this matrix, which is addressed by node indices.
+    #[inline]
+    pub fn indexed(&self) -> NodeIndexed<'_, T> {
+        NodeIndexed { m: self }
+    }
+}
+
+impl<W> PathMatrix<NodeIndex, W> {
+    /// Returns the weights of the intermediate nodes on the shortest path from ```i``` to ```j``` in the order from ```i``` to ```j```,
+    /// which are looked up in the graph, for which this matrix was computed by ```floyd_warshall_indices```.
+    pub fn resolve<'g, G>(&self, g: &'g G, i: usize, j: usize) -> Vec<&'g G::Output>
+    where
+        G: Index<NodeIndex> + ?Sized,
+    {
+        self.path_from(i, j).map(|&a| &g[a]).collect()
+    }
+}
+
+impl<'a, T> NodeIndexed<'a, T> {
+    /// Returns the underlying matrix.
+    #[inline]
+    pub fn matrix(&self) -> &'a PathMatrix<T> {
+        self.m
+    }
+
+    /// Returns the weight of node ```a```, if the matrix was computed from a graph.
+    #[inline]
+    pub fn node_weight(&self, a: NodeIndex) -> Option<&'a T> {
+        self.m.node_weight(a.index())
+    }
+
+    /// Returns the distance between ```a``` and ```b```, or ```None```, if there is no path between them.
+    #[inline]
+    pub fn distance(&self, a: NodeIndex, b: NodeIndex) -> Option<usize> {
+        self.m.distance(a.index(), b.index())
+    }
+
+    /// If the matrix contains a path between ```a``` and ```b```, this returns true.
+    #[inline]
+    pub fn does_path_exist(&self, a: NodeIndex, b: NodeIndex) -> bool {
+        self.m.does_path_exist(a.index(), b.index())
+    }
+
+    /// Returns the length of the shortest path between ```a``` and ```b```.
+    #[inline]
+    pub fn get_path_len(&self, a: NodeIndex, b: NodeIndex) -> usize {
+        self.m.get_path_len(a.index(), b.index())
+    }
+
+    /// Returns the shortest path between ```a``` and ```b```.
+    #[inline]
+    pub fn get_path(&self, a: NodeIndex, b: NodeIndex) -> &'a Path<T>
+    where
+        T: Clone,
+    {
+        self.m.get_path(a.index(), b.index())
+    }
+
+    /// Returns the length of the shortest path from ```a``` to ```b``` together with all nodes on it, like ```PathMatrix::shortest_path```.
+    #[inline]
+    pub fn shortest_path(&self, a: NodeIndex, b: NodeIndex) -> Option<(usize, Vec<&'a T>)>
+    where
+        T: Clone,
+    {
+        self.m.shortest_path(a.index(), b.index())
+    }
+
+    /// Returns the ```k``` nodes closest to ```a``` together with their distances, like ```PathMatrix::k_nearest```.
+    pub fn k_nearest(&self, a: NodeIndex, k: usize) -> Vec<(NodeIndex, usize)> {
+        self.m
+            .k_nearest(a.index(), k)
+            .into_iter()
+            .map(|(j, d)| (NodeIndex::new(j), d))
+            .collect()
+    }
+
+    /// Returns the node reachable from ```a``` with the largest distance to it, like ```PathMatrix::farthest```.
+    pub fn farthest(&self, a: NodeIndex) -> Option<(NodeIndex, usize)> {
+        self.m
+            .farthest(a.index())
+            .map(|(j, d)| (NodeIndex::new(j), d))
+    }
+}
@@ -0,0 +1,73 @@
+//! Shortest paths in graphs, whose edge weights depend on the time interval, like travel times at peak and off-peak hours.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeRef;
+
+use kernel::Kernel;
+use Options;
+use PathMatrix;
+
+/// Computes one matrix for each of the time intervals ```0..intervals```, where ```weight(w, t)``` returns the weight of an edge with the weight ```w``` in interval ```t```.
+/// Every matrix is the same, which ```floyd_warshall``` computes for the graph with these weights.
+///
+/// The graph is only traversed once, and the buffers of the dynamic program are shared between the intervals,
+/// so this is cheaper than building and solving one graph per interval.
+/// Directed graphs are supported, their matrices store the paths in both directions of every pair like for ```floyd_warshall```.
+pub fn floyd_warshall_intervals<G, F>(
+    g: G,
+    intervals: usize,
+    mut weight: F,
+) -> Vec<PathMatrix<G::NodeWeight>>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    F: FnMut(&G::EdgeWeight, usize) -> usize,
+{
+    let n = g.node_count();
+    let weights: Vec<G::NodeWeight> = g.node_references().map(|n| n.weight().clone()).collect();
+    let edges: Vec<G::EdgeRef> = g.edge_references().collect();
+    let order: Vec<usize> = g.node_identifiers().map(|n| n.index()).collect();
+
+    let options = Options::default();
+    let mut kernel = Kernel::default();
+
+    (0..intervals)
+        .map(|t| {
+            let mut m = if g.is_directed() {
+                PathMatrix::new_directed(n)
+            } else {
+                PathMatrix::new(n)
+            };
+            m.set_node_weights(weights.iter().cloned());
+            for i in 0..n {
+                m.set_path_len(i, i, 0);
+            }
+
+            // Like in floyd_warshall, a self-loop must not overwrite the 0 on the diagonal.
+            for e in &edges {
+                let u = e.source().index();
+                let v = e.target().index();
+                if u != v {
+                    m.set_path_len(u, v, weight(e.weight(), t));
+                }
+            }
+
+            ::compute_prepared(&order, &options, &mut m, &mut kernel);
+            m
+        })
+        .collect()
+}
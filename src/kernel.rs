@@ -0,0 +1,520 @@
+//! The relaxation kernel, which runs the dynamic program of the algorithm on dense buffers.
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use rng::SplitMix64;
+use PathMatrix;
+use Relaxation;
+
+/// The first bytes of a file written by ```Kernel::save```, which also encode the version of the format.
+const MAGIC: &[u8; 8] = b"FWKERNL2";
+
+/// The number of nodes of the submatrix, on which the kernels are benchmarked by ```tune```.
+const TUNE_NODES: usize = 256;
+
+/// The number of iterations of the dynamic program, which are run per benchmarked kernel.
+const TUNE_ITERATIONS: usize = 8;
+
+/// The length of a missing path. Adding anything to it saturates, so a path over a missing path never wins against an existing one.
+/// This also means, that a path of exactly this length is treated as missing by the kernel.
+const INFINITY: usize = usize::MAX;
+
+/// The intermediate node of a path, which is a single edge.
+const NO_VIA: usize = usize::MAX;
+
+/// Checks, whether the intermediate node with the given row of lengths can't improve any pair.
+/// In an undirected graph, at least two other nodes have to reach it so far.
+/// This skips isolated nodes, leaves and nodes, which are in a different component than all nodes processed before.
+/// In a directed graph, it has to reach at least one other node, because the node, which reaches it, may be a different one.
+#[inline]
+fn is_useless(row: &[usize], directed: bool) -> bool {
+    let reached = row.iter().filter(|&&d| d != INFINITY);
+    let needed = if directed { 2 } else { 3 };
+    reached.take(needed).count() < needed
+}
+
+/// Dense (n * n) buffers of the path lengths and the intermediate nodes the paths were built over.
+/// Other than the ```PathMatrix```, these don't need any flags or branches to look up a length, so the relaxation runs without mispredictions.
+/// The buffers are kept between runs, so a ```Workspace``` doesn't allocate them again.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct Kernel {
+    n: usize,
+    /// If this is set, the path from ```i``` to ```j``` can differ from the path from ```j``` to ```i```, so both triangles are computed.
+    directed: bool,
+    dist: Vec<usize>,
+    via: Vec<usize>,
+    /// The number of shortest paths found so far per pair, which is only kept by ```relax_random```.
+    /// It's saved together with the other buffers, so a resumed run breaks the remaining ties like an uninterrupted one.
+    counts: Vec<f64>,
+}
+
+impl Kernel {
+    /// Copies the lengths of the paths, which are already known in ```m```, into the dense buffers.
+    pub(crate) fn load<T>(&mut self, m: &PathMatrix<T>) {
+        let n = m.node_count();
+        self.n = n;
+        self.directed = m.is_directed();
+
+        self.dist.clear();
+        self.dist.resize(n * n, INFINITY);
+        self.via.clear();
+        self.via.resize(n * n, NO_VIA);
+        self.counts.clear();
+
+        for i in 0..n {
+            self.dist[i * n + i] = 0;
+            for j in i + 1..n {
+                if let Some(d) = m.distance(i, j) {
+                    // Both triangles are kept, so that every row of the buffer can be read contiguously.
+                    self.dist[i * n + j] = d;
+                    self.dist[j * n + i] = d;
+                }
+                if self.directed {
+                    self.dist[j * n + i] = m.distance(j, i).unwrap_or(INFINITY);
+                }
+            }
+        }
+    }
+
+    /// Runs the relaxation for every intermediate node in ```order```.
+    /// The pairs are visited in tiles of ```tile``` nodes out of the ascending node indices ```ids```.
+    pub(crate) fn relax(&mut self, order: &[usize], ids: &[usize], tile: usize) {
+        if self.directed {
+            self.relax_directed(order, ids, tile);
+            return;
+        }
+
+        let n = self.n;
+        let dist = &mut self.dist;
+        let via = &mut self.via;
+
+        // k is the "intermediate" node which is currently considered.
+        // Pairs containing k don't need to be skipped: The distance of k to itself stays 0, so the path over k is never shorter for them.
+        for &k in order {
+            if is_useless(&dist[k * n..(k + 1) * n], false) {
+                continue;
+            }
+
+            // For every pair (n1, n2) of two disjunct nodes in the graph check, if the path over k is shorter than the previously found one.
+            // The pairs are visited tile by tile, so that the accessed part of the matrix stays in the cache.
+            // Only the tiles on and above the diagonal are visited, so that only one triangle of the matrix is computed.
+            for (a, t1) in ids.chunks(tile).enumerate() {
+                for (b, t2) in ids.chunks(tile).enumerate().skip(a) {
+                    for (p, &n1) in t1.iter().enumerate() {
+                        // Within a tile on the diagonal, only the nodes after n1 are paired with it.
+                        let t2 = if a == b { &t2[p + 1..] } else { t2 };
+                        let to_k = dist[n1 * n + k];
+
+                        for &n2 in t2 {
+                            // The path from node 1 to node k to node 2. If one of its parts is missing, this saturates to INFINITY.
+                            let over_k = to_k.saturating_add(dist[k * n + n2]);
+                            let current = dist[n1 * n + n2];
+
+                            // Select the shorter one without branching, so this compiles to conditional moves.
+                            let shorter = over_k < current;
+                            let d = if shorter { over_k } else { current };
+                            dist[n1 * n + n2] = d;
+                            dist[n2 * n + n1] = d;
+                            via[n1 * n + n2] = if shorter { k } else { via[n1 * n + n2] };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Does the same as ```relax``` for a directed graph, where every tile of the matrix is visited, and no pair is mirrored.
+    fn relax_directed(&mut self, order: &[usize], ids: &[usize], tile: usize) {
+        let n = self.n;
+        let dist = &mut self.dist;
+        let via = &mut self.via;
+
+        for &k in order {
+            if is_useless(&dist[k * n..(k + 1) * n], true) {
+                continue;
+            }
+
+            for t1 in ids.chunks(tile) {
+                for t2 in ids.chunks(tile) {
+                    for &n1 in t1 {
+                        let to_k = dist[n1 * n + k];
+
+                        for &n2 in t2 {
+                            let over_k = to_k.saturating_add(dist[k * n + n2]);
+                            let current = dist[n1 * n + n2];
+
+                            let shorter = over_k < current;
+                            dist[n1 * n + n2] = if shorter { over_k } else { current };
+                            via[n1 * n + n2] = if shorter { k } else { via[n1 * n + n2] };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Does the same as ```relax```, but reports every relaxation, which makes a path shorter, to ```observer```.
+    /// The pairs are visited row by row without tiles, so the relaxations are reported in a simple order.
+    pub(crate) fn relax_observed<F>(&mut self, order: &[usize], ids: &[usize], mut observer: F)
+    where
+        F: FnMut(Relaxation),
+    {
+        let n = self.n;
+        let directed = self.directed;
+        let dist = &mut self.dist;
+        let via = &mut self.via;
+
+        for &k in order {
+            for (p, &i) in ids.iter().enumerate() {
+                let to_k = dist[i * n + k];
+                if to_k == INFINITY {
+                    continue;
+                }
+
+                // In a directed graph, i is paired with every other node, because the pair (j, i) isn't the same as (i, j).
+                let js = if directed { ids } else { &ids[p + 1..] };
+                for &j in js.iter().filter(|&&j| j != i) {
+                    let over_k = to_k.saturating_add(dist[k * n + j]);
+                    let current = dist[i * n + j];
+                    if over_k < current {
+                        dist[i * n + j] = over_k;
+                        if !directed {
+                            dist[j * n + i] = over_k;
+                        }
+                        via[i * n + j] = k;
+                        observer(Relaxation {
+                            k,
+                            i,
+                            j,
+                            old: if current == INFINITY { None } else { Some(current) },
+                            new: over_k,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Does the same as ```relax_observed```, but also breaks ties between equally long paths at random.
+    /// Every pair keeps the number of shortest paths found so far, and a tie replaces the intermediate node of a pair
+    /// with a probability proportional to the number of new paths over ```k```, so the chosen paths are spread over the alternatives.
+    /// The random numbers of every intermediate node are drawn from its own generator, which is seeded by ```seed``` and the node,
+    /// so the result doesn't depend on how the iterations are split into calls.
+    ///
+    /// A tie is only taken, if both parts of the path over ```k``` have a positive length. Otherwise, edges of length 0 could make the recorded intermediate nodes cyclic.
+    pub(crate) fn relax_random<F>(
+        &mut self,
+        order: &[usize],
+        ids: &[usize],
+        seed: u64,
+        mut observer: F,
+    ) where
+        F: FnMut(Relaxation),
+    {
+        let n = self.n;
+        if self.counts.len() != n * n {
+            // Every known path is a single edge, until the first relaxation.
+            self.counts = self
+                .dist
+                .iter()
+                .map(|&d| if d == INFINITY || d == 0 { 0.0 } else { 1.0 })
+                .collect();
+        }
+
+        let directed = self.directed;
+        let dist = &mut self.dist;
+        let via = &mut self.via;
+        let counts = &mut self.counts;
+
+        for &k in order {
+            let mut rng = SplitMix64::new(seed.wrapping_add(k as u64));
+
+            for (p, &i) in ids.iter().enumerate() {
+                let to_k = dist[i * n + k];
+                if to_k == INFINITY {
+                    continue;
+                }
+
+                let js = if directed { ids } else { &ids[p + 1..] };
+                for &j in js.iter().filter(|&&j| j != i) {
+                    let from_k = dist[k * n + j];
+                    let over_k = to_k.saturating_add(from_k);
+                    let current = dist[i * n + j];
+                    let paths = counts[i * n + k] * counts[k * n + j];
+
+                    if over_k < current {
+                        dist[i * n + j] = over_k;
+                        via[i * n + j] = k;
+                        counts[i * n + j] = paths;
+                        if !directed {
+                            dist[j * n + i] = over_k;
+                            counts[j * n + i] = paths;
+                        }
+                        observer(Relaxation {
+                            k,
+                            i,
+                            j,
+                            old: if current == INFINITY { None } else { Some(current) },
+                            new: over_k,
+                        });
+                    } else if over_k == current && over_k != INFINITY && to_k > 0 && from_k > 0 {
+                        let total = counts[i * n + j] + paths;
+                        counts[i * n + j] = total;
+                        if !directed {
+                            counts[j * n + i] = total;
+                        }
+                        if rng.next_f64() * total < paths {
+                            via[i * n + j] = k;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns a copy of the current path lengths, where ```usize::MAX``` means that there is no path.
+    pub(crate) fn distances(&self) -> Vec<usize> {
+        self.dist.clone()
+    }
+
+    /// Does the same as ```load```, but fills the rows of the buffers in parallel.
+    /// If ```first_touch``` is set, the buffers are allocated anew, so that every page is first touched by a thread, which fills its rows.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn load_par<T: Send + Sync>(&mut self, m: &PathMatrix<T>, first_touch: bool) {
+        use rayon::prelude::*;
+
+        let n = m.node_count();
+        self.n = n;
+        self.directed = m.is_directed();
+
+        if first_touch {
+            // A zeroed allocation is mapped lazily by the operating system, so its pages stay untouched until the rows are filled.
+            self.dist = vec![0; n * n];
+            self.via = vec![0; n * n];
+        } else {
+            self.dist.resize(n * n, 0);
+            self.via.resize(n * n, 0);
+        }
+
+        let rows = self.dist.par_chunks_mut(n.max(1));
+        let vias = self.via.par_chunks_mut(n.max(1));
+        rows.zip(vias).enumerate().for_each(|(i, (row, via))| {
+            for (j, (d, v)) in row.iter_mut().zip(via).enumerate() {
+                *d = m.distance(i, j).unwrap_or(INFINITY);
+                *v = NO_VIA;
+            }
+        });
+    }
+
+    /// Runs the relaxation for every intermediate node in ```order```, where the rows of the buffers are relaxed in parallel.
+    /// Other than ```relax```, this computes both triangles, so that every row can be updated independently of the others.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn relax_par(&mut self, order: &[usize]) {
+        use rayon::prelude::*;
+
+        let n = self.n;
+        let mut row_k = Vec::with_capacity(n);
+
+        for &k in order {
+            if is_useless(&self.dist[k * n..(k + 1) * n], self.directed) {
+                continue;
+            }
+
+            // Row k doesn't change in this iteration, so a copy of it can be shared by all threads.
+            row_k.clear();
+            row_k.extend_from_slice(&self.dist[k * n..(k + 1) * n]);
+            let row_k = &row_k;
+
+            let rows = self.dist.par_chunks_mut(n);
+            let vias = self.via.par_chunks_mut(n);
+            rows.zip(vias).for_each(|(row, via)| {
+                let to_k = row[k];
+                for ((d, v), &from_k) in row.iter_mut().zip(via.iter_mut()).zip(row_k) {
+                    let over_k = to_k.saturating_add(from_k);
+                    let shorter = over_k < *d;
+                    *d = if shorter { over_k } else { *d };
+                    *v = if shorter { k } else { *v };
+                }
+            });
+        }
+    }
+
+    /// Benchmarks the relaxation with each of the given tile sizes on a submatrix of the loaded buffers and returns the fastest one.
+    /// A tile size of at least the node count is the naive kernel, which visits the whole triangle row by row.
+    /// The submatrix consists of the first nodes of ```ids```, and only the first iterations of the dynamic program are run on it.
+    pub(crate) fn tune(&self, ids: &[usize], candidates: &[usize]) -> usize {
+        let n = self.n;
+        let p = ids.len().min(TUNE_NODES);
+
+        // The benchmark runs on a copy, so the loaded buffers stay untouched.
+        let mut sub = Kernel {
+            n: p,
+            directed: self.directed,
+            dist: Vec::with_capacity(p * p),
+            via: vec![NO_VIA; p * p],
+            counts: Vec::new(),
+        };
+        for &i in &ids[..p] {
+            sub.dist
+                .extend(ids[..p].iter().map(|&j| self.dist[i * n + j]));
+        }
+
+        let local: Vec<usize> = (0..p).collect();
+        let order = &local[..p.min(TUNE_ITERATIONS)];
+
+        let mut best = (Duration::MAX, candidates[0]);
+        for &tile in candidates {
+            let mut run = sub.clone();
+            let start = Instant::now();
+            run.relax(order, &local, tile.max(1));
+            best = best.min((start.elapsed(), tile));
+        }
+
+        best.1
+    }
+
+    /// Returns the number of nodes of the loaded buffers.
+    #[inline]
+    pub(crate) fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// If the loaded buffers belong to a directed graph, this returns true.
+    #[inline]
+    pub(crate) fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Checks, whether the buffers fit to the node count and every intermediate node exists, which isn't guaranteed for deserialized buffers.
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_consistent(&self) -> bool {
+        let n = self.n;
+        n.checked_mul(n).is_some_and(|len| {
+            self.dist.len() == len
+                && self.via.len() == len
+                && (self.counts.is_empty() || self.counts.len() == len)
+        }) && self.via.iter().all(|&k| k == NO_VIA || k < n)
+    }
+
+    /// Writes the buffers and the position ```next``` of the next intermediate node to ```w```.
+    /// The path counts of ```relax_random``` follow the other buffers, if there are any.
+    pub(crate) fn save<W: Write>(&self, w: &mut W, next: usize) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&(self.n as u64).to_le_bytes())?;
+        w.write_all(&(next as u64).to_le_bytes())?;
+        w.write_all(&[!self.counts.is_empty() as u8])?;
+        for &x in self.dist.iter().chain(&self.via) {
+            w.write_all(&(x as u64).to_le_bytes())?;
+        }
+        for &c in &self.counts {
+            w.write_all(&c.to_bits().to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads buffers written by ```save``` for a graph with ```n``` nodes from ```r``` and returns them together with the position of the next intermediate node.
+    /// The header is checked against ```n``` before the buffers are read, so the checkpoint of a different graph is rejected with an error of kind ```InvalidData```.
+    pub(crate) fn restore<R: Read>(
+        r: &mut R,
+        n: usize,
+        directed: bool,
+    ) -> io::Result<(Kernel, usize)> {
+        fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+            let mut b = [0; 8];
+            r.read_exact(&mut b)?;
+            Ok(u64::from_le_bytes(b))
+        }
+
+        fn invalid(msg: &str) -> io::Error {
+            io::Error::new(io::ErrorKind::InvalidData, msg)
+        }
+
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a checkpoint of this version"));
+        }
+
+        // The stored sizes aren't trusted, so nothing is allocated before they match the graph.
+        let stored = read_u64(r)?;
+        let next = read_u64(r)?;
+        if stored != n as u64 || next > n as u64 {
+            return Err(invalid("the checkpoint belongs to a different graph"));
+        }
+        let mut has_counts = [0];
+        r.read_exact(&mut has_counts)?;
+        if has_counts[0] > 1 {
+            return Err(invalid("not a checkpoint of this version"));
+        }
+        let len = n
+            .checked_mul(n)
+            .ok_or_else(|| invalid("the checkpoint is too large"))?;
+
+        let mut dist = Vec::with_capacity(len);
+        for _ in 0..len {
+            dist.push(read_u64(r)? as usize);
+        }
+        let mut via = Vec::with_capacity(len);
+        for _ in 0..len {
+            let k = read_u64(r)? as usize;
+            if k != NO_VIA && k >= n {
+                return Err(invalid("the checkpoint contains an unknown node"));
+            }
+            via.push(k);
+        }
+        let mut counts = Vec::new();
+        if has_counts[0] == 1 {
+            counts.reserve(len);
+            for _ in 0..len {
+                let c = f64::from_bits(read_u64(r)?);
+                if c.is_nan() || c < 0.0 {
+                    return Err(invalid("the checkpoint contains an invalid path count"));
+                }
+                counts.push(c);
+            }
+        }
+
+        Ok((
+            Kernel {
+                n,
+                directed,
+                dist,
+                via,
+                counts,
+            },
+            next as usize,
+        ))
+    }
+
+    /// Copies the found lengths and intermediate nodes back into ```m```, which has to be of the same mode as the buffers.
+    pub(crate) fn store<T>(&self, m: &mut PathMatrix<T>) {
+        let n = self.n;
+        debug_assert_eq!(m.is_directed(), self.directed);
+
+        for i in 0..n {
+            // In a directed graph, the pairs below the diagonal are paths on their own.
+            let js = if self.directed { 0..n } else { i + 1..n };
+            for j in js.filter(|&j| j != i) {
+                let d = self.dist[i * n + j];
+                if d != INFINITY {
+                    m.set_path_len(i, j, d);
+                }
+
+                let k = self.via[i * n + j];
+                if k != NO_VIA {
+                    m.get_path_mut(i, j).set_via(k);
+                }
+            }
+        }
+    }
+}
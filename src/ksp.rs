@@ -0,0 +1,179 @@
+//! The k shortest loopless paths between two nodes by Yen's algorithm.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::collections::BinaryHeap;
+use std::collections::HashSet;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use analysis::Adjacency;
+use PathMatrix;
+
+/// The state of the searches for spur paths, whose buffers are reused between the searches.
+struct SpurSearch<'a, T: 'a> {
+    m: &'a PathMatrix<T>,
+    adj: Adjacency,
+    dist: Vec<Option<usize>>,
+    pred: Vec<usize>,
+    blocked_nodes: Vec<bool>,
+    blocked_edges: HashSet<(usize, usize)>,
+}
+
+impl<'a, T> SpurSearch<'a, T> {
+    /// Returns the weight of the lightest edge from ```u``` to ```v```.
+    fn weight(&self, u: usize, v: usize) -> usize {
+        self.adj
+            .neighbours(u)
+            .iter()
+            .filter(|&&(x, _, _)| x == v)
+            .map(|&(_, w, _)| w)
+            .min()
+            .expect("the graph doesn't belong to this matrix")
+    }
+
+    /// Finds the shortest path from ```s``` to ```j```, which avoids the blocked nodes and edges, by the A* algorithm.
+    /// The distances in the matrix are exact for the graph without blocked elements, so they are admissible estimates of the remaining distance.
+    /// Returns the length and the nodes of the path including both endpoints.
+    fn search(&mut self, s: usize, j: usize) -> Option<(usize, Vec<usize>)> {
+        for d in self.dist.iter_mut() {
+            *d = None;
+        }
+
+        let mut heap = BinaryHeap::new();
+        self.dist[s] = Some(0);
+        heap.push(Reverse((self.m.distance(s, j)?, 0, s)));
+
+        while let Some(Reverse((_, d, u))) = heap.pop() {
+            if self.dist[u].is_some_and(|du| du < d) {
+                continue;
+            }
+
+            if u == j {
+                let mut path = vec![j];
+                while *path.last().unwrap() != s {
+                    path.push(self.pred[*path.last().unwrap()]);
+                }
+                path.reverse();
+                return Some((d, path));
+            }
+
+            for &(v, w, _) in self.adj.neighbours(u) {
+                if self.blocked_nodes[v] || self.blocked_edges.contains(&(u, v)) {
+                    continue;
+                }
+
+                // Nodes, which can't reach j even in the whole graph, are never part of a spur path.
+                let rest = match self.m.distance(v, j) {
+                    Some(rest) => rest,
+                    None => continue,
+                };
+
+                let nd = d.saturating_add(w);
+                if self.dist[v].is_none_or(|dv| nd < dv) {
+                    self.dist[v] = Some(nd);
+                    self.pred[v] = u;
+                    heap.push(Reverse((nd.saturating_add(rest), nd, v)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns up to ```k``` shortest loopless paths from ```i``` to ```j``` in the graph, ordered by increasing length, by Yen's algorithm.
+/// Every path is given by its length and its nodes including both endpoints.
+///
+/// The first path is taken from the matrix, and the searches for the further paths use its distances as lower bounds,
+/// so they only explore the part of the graph, which can lead to ```j```.
+/// The graph must not have parallel edges, because the matrix only knows the last one of them.
+///
+/// # Panics
+///
+/// Panics, if the graph has a different number of nodes than the matrix.
+pub fn k_shortest_paths<G, T>(
+    i: usize,
+    j: usize,
+    k: usize,
+    g: G,
+    m: &PathMatrix<T>,
+) -> Vec<(usize, Vec<usize>)>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    assert_eq!(
+        m.node_count(),
+        g.node_count(),
+        "the graph doesn't belong to the matrix"
+    );
+
+    let first = match m.distance(i, j) {
+        Some(d) if k > 0 => d,
+        _ => return Vec::new(),
+    };
+    if i == j {
+        return vec![(0, vec![i])];
+    }
+
+    let path: Vec<usize> = m.path_iter(i, j).collect();
+
+    let n = m.node_count();
+    let mut search = SpurSearch {
+        m,
+        adj: Adjacency::directed(g, m.is_directed()),
+        dist: vec![None; n],
+        pred: vec![0; n],
+        blocked_nodes: vec![false; n],
+        blocked_edges: HashSet::new(),
+    };
+
+    let mut found = vec![(first, path)];
+    let mut candidates = BTreeSet::new();
+
+    while found.len() < k {
+        let prev = found.last().unwrap().1.clone();
+
+        // Every node of the previous path except the last one is the spur node of a deviation from it.
+        let mut root_len = 0;
+        for s in 0..prev.len() - 1 {
+            let root = &prev[..=s];
+
+            // Paths with the same root must leave it over another edge.
+            search.blocked_edges.clear();
+            for (_, p) in &found {
+                if p.len() > s + 1 && &p[..=s] == root {
+                    search.blocked_edges.insert((p[s], p[s + 1]));
+                }
+            }
+
+            // The spur path must not return to the root, so the paths stay loopless.
+            for &u in &root[..s] {
+                search.blocked_nodes[u] = true;
+            }
+
+            if let Some((spur_len, spur)) = search.search(prev[s], j) {
+                let mut p = root[..s].to_vec();
+                p.extend(spur);
+                candidates.insert((root_len + spur_len, p));
+            }
+
+            for &u in &root[..s] {
+                search.blocked_nodes[u] = false;
+            }
+            root_len += search.weight(prev[s], prev[s + 1]);
+        }
+
+        match candidates.pop_first() {
+            Some(c) => found.push(c),
+            None => break,
+        }
+    }
+
+    found
+}
@@ -0,0 +1,84 @@
+//! Queries on a ```PathMatrix```, which address the nodes by a label instead of their index.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use PathMatrix;
+
+/// A map from labels to the nodes of a ```PathMatrix```, which answers queries by label.
+/// If several nodes have the same label, the one with the smallest index is used.
+#[derive(Clone, Debug)]
+pub struct Labels<'a, T: 'a, K> {
+    m: &'a PathMatrix<T>,
+    index: HashMap<K, usize>,
+}
+
+impl<T> PathMatrix<T> {
+    /// Builds a label map, which uses the node weights as labels.
+    ///
+    /// # Panics
+    ///
+    /// The node weights have to be known, so this panics for a matrix with nodes, which wasn't computed from a graph.
+    pub fn labels(&self) -> Labels<'_, T, T>
+    where
+        T: Clone + Hash + Eq,
+    {
+        self.labels_by(|w| w.clone())
+    }
+
+    /// Builds a label map, which labels every node by applying ```key``` to its weight.
+    ///
+    /// # Panics
+    ///
+    /// The node weights have to be known, so this panics for a matrix with nodes, which wasn't computed from a graph.
+    pub fn labels_by<K, F>(&self, mut key: F) -> Labels<'_, T, K>
+    where
+        K: Hash + Eq,
+        F: FnMut(&T) -> K,
+    {
+        let mut index = HashMap::new();
+        for i in 0..self.node_count() {
+            let w = self.node_weight(i).expect("the node weights are unknown");
+            index.entry(key(w)).or_insert(i);
+        }
+
+        Labels { m: self, index }
+    }
+}
+
+impl<'a, T, K> Labels<'a, T, K>
+where
+    K: Hash + Eq,
+{
+    /// Returns the index of the node with the given label, or ```None```, if there is no such node.
+    #[inline]
+    pub fn index_of<Q>(&self, label: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(label).cloned()
+    }
+
+    /// Returns the distance between the nodes with the labels ```a``` and ```b```.
+    /// This is ```None```, if one of the labels is unknown or there is no path between the nodes.
+    pub fn distance_by_label<Q>(&self, a: &Q, b: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.m.distance(self.index_of(a)?, self.index_of(b)?)
+    }
+
+    /// Returns the length of the shortest path between the nodes with the labels ```a``` and ```b``` together with all nodes on it,
+    /// like ```PathMatrix::shortest_path```. This is ```None```, if one of the labels is unknown or there is no path between the nodes.
+    pub fn shortest_path_by_label<Q>(&self, a: &Q, b: &Q) -> Option<(usize, Vec<&'a T>)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        T: Clone,
+    {
+        self.m.shortest_path(self.index_of(a)?, self.index_of(b)?)
+    }
+}
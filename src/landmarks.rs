@@ -0,0 +1,115 @@
+//! Distance estimates from a few landmark nodes, as a cheap alternative to the full matrix.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use analysis::Adjacency;
+
+/// Bounds on the distance between two nodes, which follow from the triangle inequality.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DistanceBounds {
+    /// A lower bound of the distance.
+    pub lower: usize,
+    /// An upper bound of the distance, or ```None```, if no landmark is reachable from both nodes.
+    pub upper: Option<usize>,
+}
+
+/// The exact distances from a set of landmark nodes to all nodes of a graph.
+/// This needs **O(L·V)** memory for ```L``` landmarks instead of **O(V^(2))** for the full matrix,
+/// and answers queries between arbitrary nodes with bounds instead of exact distances.
+#[derive(Clone, Debug)]
+pub struct Landmarks {
+    n: usize,
+    landmarks: Vec<usize>,
+    dist: Vec<Option<usize>>,
+}
+
+impl Landmarks {
+    /// Computes the distances from every node in ```landmarks``` to all nodes of the given graph by a single-source search each.
+    ///
+    /// # Panics
+    ///
+    /// The graph has to be undirected, and the landmarks have to be nodes of it.
+    pub fn new<G>(g: G, landmarks: &[usize]) -> Landmarks
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        assert!(!g.is_directed(), "the graph has to be undirected");
+
+        let n = g.node_count();
+        let adj = Adjacency::new(g);
+
+        let mut dist = vec![None; landmarks.len() * n];
+        for (row, &l) in dist.chunks_mut(n.max(1)).zip(landmarks) {
+            assert!(l < n, "the landmark {} is not a node of the graph", l);
+            adj.distances_from(l, row);
+        }
+
+        Landmarks {
+            n,
+            landmarks: landmarks.to_vec(),
+            dist,
+        }
+    }
+
+    /// Returns the number of nodes of the graph.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the landmark nodes.
+    #[inline]
+    pub fn landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    /// Returns the exact distance between the landmark at position ```l``` and node ```i```, or ```None```, if there is no path between them.
+    #[inline]
+    pub fn landmark_distance(&self, l: usize, i: usize) -> Option<usize> {
+        self.dist[l * self.n + i]
+    }
+
+    /// Returns bounds on the distance between ```i``` and ```j```.
+    /// The lower bound is the largest difference ```|d(l, i) - d(l, j)|``` and the upper bound the smallest sum ```d(i, l) + d(l, j)``` over all landmarks ```l```.
+    /// If a landmark reaches exactly one of the nodes, there is no path between them, and ```None``` is returned.
+    pub fn bounds(&self, i: usize, j: usize) -> Option<DistanceBounds> {
+        if i == j {
+            return Some(DistanceBounds {
+                lower: 0,
+                upper: Some(0),
+            });
+        }
+
+        let mut bounds = DistanceBounds {
+            lower: 0,
+            upper: None,
+        };
+        for l in 0..self.landmarks.len() {
+            match (self.landmark_distance(l, i), self.landmark_distance(l, j)) {
+                (Some(a), Some(b)) => {
+                    let lower = a.abs_diff(b);
+                    let upper = a.saturating_add(b);
+                    bounds.lower = bounds.lower.max(lower);
+                    bounds.upper = Some(bounds.upper.map_or(upper, |u| u.min(upper)));
+                }
+                (None, None) => {}
+                _ => return None,
+            }
+        }
+
+        Some(bounds)
+    }
+
+    /// Returns the upper bound on the distance between ```i``` and ```j```, which is the length of a path over a landmark.
+    /// This is ```None```, if no landmark is reachable from both nodes.
+    #[inline]
+    pub fn estimate(&self, i: usize, j: usize) -> Option<usize> {
+        self.bounds(i, j).and_then(|b| b.upper)
+    }
+}
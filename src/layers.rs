@@ -0,0 +1,153 @@
+//! Graphs with several layers over the same nodes, like a walking and a transit network, which are connected by transfer edges.
+
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+use petgraph::Undirected;
+
+use floyd_warshall;
+use PathMatrix;
+
+/// A node in a layer, given as ```(layer, node)```.
+pub type LayerNode = (usize, usize);
+
+/// An undirected graph, where every one of its ```layers``` has its own copy of the same ```nodes```.
+/// Edges within a layer are added by ```add_edge```, and transfers between layers, which carry their own costs, by ```add_transfer```.
+#[derive(Clone, Debug)]
+pub struct LayeredGraph {
+    nodes: usize,
+    layers: usize,
+    g: Graph<LayerNode, usize, Undirected>,
+}
+
+impl LayeredGraph {
+    /// Creates a graph with ```layers``` layers of ```nodes``` nodes each and no edges.
+    pub fn new(nodes: usize, layers: usize) -> LayeredGraph {
+        let mut g = Graph::with_capacity(nodes * layers, 0);
+        for layer in 0..layers {
+            for node in 0..nodes {
+                g.add_node((layer, node));
+            }
+        }
+
+        LayeredGraph { nodes, layers, g }
+    }
+
+    /// Returns the number of nodes per layer.
+    pub fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns the number of layers.
+    pub fn layer_count(&self) -> usize {
+        self.layers
+    }
+
+    /// Returns the index of ```(layer, node)``` in the flattened graph.
+    fn index(&self, (layer, node): LayerNode) -> NodeIndex {
+        assert!(
+            layer < self.layers && node < self.nodes,
+            "there is no node {} in layer {}",
+            node,
+            layer
+        );
+        NodeIndex::new(layer * self.nodes + node)
+    }
+
+    /// Connects the nodes ```a``` and ```b``` within ```layer``` by an edge. Adding an edge again replaces its weight.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if ```a == b``` or one of the nodes doesn't exist.
+    pub fn add_edge(&mut self, layer: usize, a: usize, b: usize, weight: usize) {
+        self.add_transfer((layer, a), (layer, b), weight);
+    }
+
+    /// Connects two nodes in possibly different layers by a transfer edge with the given cost. Adding a transfer again replaces its cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if ```a == b``` or one of the nodes doesn't exist.
+    pub fn add_transfer(&mut self, a: LayerNode, b: LayerNode, cost: usize) {
+        // A loop would overwrite the distance of every node to itself in the matrix.
+        assert!(a != b, "loops aren't supported");
+        let (a, b) = (self.index(a), self.index(b));
+        self.g.update_edge(a, b, cost);
+    }
+
+    /// Returns the flattened graph, whose node weights are the ```(layer, node)``` pairs.
+    pub fn graph(&self) -> &Graph<LayerNode, usize, Undirected> {
+        &self.g
+    }
+
+    /// Computes the shortest paths between every two nodes in every two layers.
+    pub fn solve(&self) -> LayeredMatrix {
+        LayeredMatrix {
+            nodes: self.nodes,
+            layers: self.layers,
+            m: floyd_warshall(&self.g),
+        }
+    }
+}
+
+/// The shortest paths of a ```LayeredGraph```, which keep the layer of every node.
+#[derive(Debug)]
+pub struct LayeredMatrix {
+    nodes: usize,
+    layers: usize,
+    m: PathMatrix<LayerNode>,
+}
+
+impl LayeredMatrix {
+    /// Returns the number of nodes per layer.
+    pub fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns the number of layers.
+    pub fn layer_count(&self) -> usize {
+        self.layers
+    }
+
+    /// Returns the index of ```(layer, node)``` in the flattened matrix.
+    fn index(&self, (layer, node): LayerNode) -> usize {
+        assert!(
+            layer < self.layers && node < self.nodes,
+            "there is no node {} in layer {}",
+            node,
+            layer
+        );
+        layer * self.nodes + node
+    }
+
+    /// Returns the distance between ```a``` and ```b```, or ```None```, if there is no path between them.
+    pub fn distance(&self, a: LayerNode, b: LayerNode) -> Option<usize> {
+        self.m.distance(self.index(a), self.index(b))
+    }
+
+    /// Returns the intermediate nodes on the shortest path from ```a``` to ```b``` together with their layers,
+    /// or ```None```, if there is no path between them.
+    pub fn path(&self, a: LayerNode, b: LayerNode) -> Option<Vec<LayerNode>> {
+        let (i, j) = (self.index(a), self.index(b));
+        if i != j && !self.m.does_path_exist(i, j) {
+            return None;
+        }
+        Some(self.m.path_from(i, j).cloned().collect())
+    }
+
+    /// Returns the distances from every node in layer ```from``` to every node in layer ```to``` as a row-major ```nodes * nodes``` vector,
+    /// where ```None``` means, that there is no path.
+    pub fn layer_distances(&self, from: usize, to: usize) -> Vec<Option<usize>> {
+        let mut d = Vec::with_capacity(self.nodes * self.nodes);
+        for a in 0..self.nodes {
+            for b in 0..self.nodes {
+                d.push(self.distance((from, a), (to, b)));
+            }
+        }
+        d
+    }
+
+    /// Returns the matrix of the flattened graph, whose node weights are the ```(layer, node)``` pairs.
+    pub fn matrix(&self) -> &PathMatrix<LayerNode> {
+        &self.m
+    }
+}
@@ -0,0 +1,168 @@
+//! Distance-faithful 2D layouts of a graph, computed from its distances.
+
+use rng::SplitMix64;
+use Distances;
+
+/// Returns all distances as a dense matrix, where infinite distances are replaced by a finite stand-in.
+fn dense_distances<D: Distances>(d: &D) -> Vec<Vec<f64>> {
+    let n = d.node_count();
+
+    let max = (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .filter_map(|(i, j)| d.distance(i, j))
+        .max()
+        .unwrap_or(0);
+
+    // Disconnected nodes are placed a bit farther away than the farthest connected pair.
+    let inf = (max + 1) as f64;
+
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| d.distance(i, j).map_or(inf, |d| d as f64))
+                .collect()
+        })
+        .collect()
+}
+
+/// Makes ```v``` orthogonal to all (normalized) vectors in ```deflate``` and normalizes it. Returns the norm before normalization.
+fn orthonormalize(v: &mut [f64], deflate: &[Vec<f64>]) -> f64 {
+    for u in deflate {
+        let dot: f64 = v.iter().zip(u).map(|(a, b)| a * b).sum();
+        for (x, y) in v.iter_mut().zip(u) {
+            *x -= dot * y;
+        }
+    }
+
+    let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for x in v.iter_mut() {
+            *x /= norm;
+        }
+    }
+    norm
+}
+
+/// Finds the eigenvector with the largest eigenvalue of the symmetric matrix ```b``` by power iteration, and returns it with its eigenvalue.
+/// The result is orthogonal to all vectors in ```deflate```. ```shift``` has to be an upper bound for the absolute values of the eigenvalues.
+fn dominant_eigenvector(
+    b: &[Vec<f64>],
+    shift: f64,
+    deflate: &[Vec<f64>],
+    rng: &mut SplitMix64,
+) -> (Vec<f64>, f64) {
+    let n = b.len();
+    let mut v: Vec<f64> = (0..n)
+        .map(|_| (rng.next_u64() % 1000) as f64 / 1000.0 - 0.5)
+        .collect();
+    orthonormalize(&mut v, deflate);
+
+    // Multiplies with b + shift * I, whose eigenvalues are all non-negative.
+    let apply = |v: &[f64]| -> Vec<f64> {
+        (0..n)
+            .map(|i| b[i].iter().zip(v).map(|(a, b)| a * b).sum::<f64>() + shift * v[i])
+            .collect()
+    };
+
+    let mut lambda = 0.0;
+    for _ in 0..10_000 {
+        let mut w = apply(&v);
+        lambda = orthonormalize(&mut w, deflate);
+
+        let change = w
+            .iter()
+            .zip(&v)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>();
+        v = w;
+        if lambda == 0.0 || change < 1e-24 {
+            break;
+        }
+    }
+
+    (v, lambda - shift)
+}
+
+/// Computes a 2D layout by classical multidimensional scaling: The coordinates are given by the two largest eigenvectors of the double-centered squared distance matrix.
+/// If the distances are Euclidean in the plane, they are reproduced exactly. Disconnected nodes are placed as if they were slightly farther away than the farthest connected pair.
+pub fn mds_layout<D: Distances>(d: &D) -> Vec<(f64, f64)> {
+    let n = d.node_count();
+    let dist = dense_distances(d);
+
+    // b = -1/2 * J * D^2 * J, where J is the centering matrix.
+    let sq: Vec<Vec<f64>> = dist
+        .iter()
+        .map(|r| r.iter().map(|x| x * x).collect())
+        .collect();
+    let row_mean: Vec<f64> = sq
+        .iter()
+        .map(|r| r.iter().sum::<f64>() / n as f64)
+        .collect();
+    let total_mean = row_mean.iter().sum::<f64>() / n as f64;
+
+    let b: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| -0.5 * (sq[i][j] - row_mean[i] - row_mean[j] + total_mean))
+                .collect()
+        })
+        .collect();
+
+    // Every eigenvalue is bounded by the largest absolute row sum (Gershgorin).
+    let shift = b
+        .iter()
+        .map(|r| r.iter().map(|x| x.abs()).sum::<f64>())
+        .fold(0.0, f64::max);
+
+    let mut rng = SplitMix64::new(0x5EED);
+    let (v1, l1) = dominant_eigenvector(&b, shift, &[], &mut rng);
+    let (v2, l2) = dominant_eigenvector(&b, shift, ::std::slice::from_ref(&v1), &mut rng);
+
+    let s1 = l1.max(0.0).sqrt();
+    let s2 = l2.max(0.0).sqrt();
+
+    v1.iter().zip(&v2).map(|(x, y)| (x * s1, y * s2)).collect()
+}
+
+/// Computes a 2D layout by stress majorization, starting from the classical MDS layout.
+/// Every iteration moves each node to the position, which minimizes the weighted stress ```sum w_ij * (|x_i - x_j| - d_ij)^2``` with ```w_ij = d_ij^-2```, while all other nodes stay fixed.
+/// Disconnected pairs don't contribute to the stress.
+pub fn stress_layout<D: Distances>(d: &D, iterations: usize) -> Vec<(f64, f64)> {
+    let n = d.node_count();
+    let mut pos = mds_layout(d);
+
+    for _ in 0..iterations {
+        for i in 0..n {
+            let mut sum_w = 0.0;
+            let mut x = 0.0;
+            let mut y = 0.0;
+
+            for j in (0..n).filter(|&j| j != i) {
+                let dij = match d.distance(i, j) {
+                    Some(dij) if dij > 0 => dij as f64,
+                    _ => continue,
+                };
+                let w = 1.0 / (dij * dij);
+
+                let dx = pos[i].0 - pos[j].0;
+                let dy = pos[i].1 - pos[j].1;
+                let norm = (dx * dx + dy * dy).sqrt();
+                let (ux, uy) = if norm > 0.0 {
+                    (dx / norm, dy / norm)
+                } else {
+                    (0.0, 0.0)
+                };
+
+                sum_w += w;
+                x += w * (pos[j].0 + dij * ux);
+                y += w * (pos[j].1 + dij * uy);
+            }
+
+            if sum_w > 0.0 {
+                pos[i] = (x / sum_w, y / sum_w);
+            }
+        }
+    }
+
+    pos
+}
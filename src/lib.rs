@@ -1,36 +1,313 @@
-//! This crate contains an implementation of the Floyd-Warshall algorithm to solve the all-pairs-shortest-paths problem in undirected graphs.
+//! This crate contains an implementation of the Floyd-Warshall algorithm to solve the all-pairs-shortest-paths problem in undirected and directed graphs.
+//!
+//! # Features
+//!
+//! The core algorithm on edge lists, ```floyd_warshall_edges```, and the queries on its result need no optional dependency. Everything else is behind these features:
+//!
+//! * ```petgraph``` (default): The entry points and algorithms, which take a petgraph graph, like ```floyd_warshall```. The other features, which take a graph, only add to these.
+//! * ```rayon```: The parallel functions like ```floyd_warshall_par```.
+//! * ```serde```: Serialization of ```FloydWarshallState```.
+//! * ```export```: Writing ```RoutingTables``` and the usage counts of the paths as CSV or JSON.
+//! * ```arbitrary```: An implementation of ```arbitrary::Arbitrary``` for ```GraphSpec```.
+//! * ```indicatif```: Reporting the ```Progress``` of a run to a progress bar of ```indicatif```.
+//! * ```simd```: A vectorized kernel for ```floyd_warshall_compact``` on x86-64 processors with AVX2, which is detected at runtime.
+//! * ```gpu```: The compute shader backend ```floyd_warshall_gpu``` based on ```wgpu```.
+//! * ```clustering``` and ```layout```: Clustering and graph layout on the distances.
+//! * ```generators``` and ```reference```: Graph generators and a brute-force reference implementation for tests and benchmarks.
 
 #![deny(missing_docs)]
-#![feature(conservative_impl_trait)]
+// Without petgraph, some internal helpers of the graph algorithms stay unused.
+#![cfg_attr(not(feature = "petgraph"), allow(dead_code))]
 
+#[cfg(feature = "petgraph")]
 extern crate petgraph;
 
-#[cfg(test)]
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "indicatif")]
+extern crate indicatif;
+#[cfg(feature = "gpu")]
+extern crate wgpu;
+
+#[cfg(all(test, feature = "petgraph"))]
 extern crate rand;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "petgraph"))]
 #[macro_use]
 extern crate text_io;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "petgraph", feature = "serde"))]
+extern crate serde_json;
+
+#[cfg(all(test, feature = "petgraph"))]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests;
 
 mod matrices;
 pub use matrices::*;
 
+mod queries;
+pub use queries::*;
+
+#[cfg(feature = "petgraph")]
+mod indexed;
+#[cfg(feature = "petgraph")]
+pub use indexed::*;
+
+mod labels;
+pub use labels::*;
+
+#[cfg(feature = "petgraph")]
+mod bound;
+#[cfg(feature = "petgraph")]
+pub use bound::*;
+
+#[cfg(feature = "petgraph")]
+mod revalidate;
+
+#[cfg(feature = "petgraph")]
+mod analysis;
+#[cfg(feature = "petgraph")]
+pub use analysis::*;
+
+#[cfg(feature = "petgraph")]
+mod sensitivity;
+#[cfg(feature = "petgraph")]
+pub use sensitivity::*;
+
+mod metric;
+pub use metric::*;
+
+mod facility;
+pub use facility::*;
+
+#[cfg(feature = "petgraph")]
+mod approx;
+#[cfg(feature = "petgraph")]
+pub use approx::*;
+
+mod compare;
+pub use compare::*;
+
+mod sampling;
+pub use sampling::*;
+
+#[cfg(feature = "petgraph")]
+mod landmarks;
+#[cfg(feature = "petgraph")]
+pub use landmarks::*;
+
+mod hubs;
+pub use hubs::*;
+
+#[cfg(feature = "petgraph")]
+mod spanner;
+#[cfg(feature = "petgraph")]
+pub use spanner::*;
+
+mod cache;
+pub use cache::*;
+
+mod bulk;
+
+#[cfg(feature = "clustering")]
+mod clustering;
+#[cfg(feature = "clustering")]
+pub use clustering::*;
+
+#[cfg(feature = "layout")]
+mod layout;
+#[cfg(feature = "layout")]
+pub use layout::*;
+
+#[cfg(feature = "generators")]
+pub mod generators;
+
+#[cfg(feature = "reference")]
+pub mod reference;
+
+mod options;
+pub use options::*;
+
+#[cfg(feature = "petgraph")]
+mod workspace;
+#[cfg(feature = "petgraph")]
+pub use workspace::*;
+
+#[cfg(feature = "petgraph")]
+mod batch;
+#[cfg(feature = "petgraph")]
+pub use batch::*;
+
+#[cfg(feature = "petgraph")]
+mod compact;
+#[cfg(feature = "petgraph")]
+pub use compact::*;
+
+#[cfg(feature = "petgraph")]
+mod distances;
+#[cfg(feature = "petgraph")]
+pub use distances::*;
+
+#[cfg(feature = "petgraph")]
+mod minplus;
+
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "gpu")]
+pub use gpu::*;
+
+#[cfg(feature = "petgraph")]
+mod disk;
+#[cfg(feature = "petgraph")]
+pub use disk::*;
+
+#[cfg(feature = "petgraph")]
+mod memory;
+#[cfg(feature = "petgraph")]
+pub use memory::*;
+
+#[cfg(feature = "petgraph")]
+mod closure;
+#[cfg(feature = "petgraph")]
+pub use closure::*;
+
+#[cfg(feature = "petgraph")]
+mod checkpoint;
+#[cfg(feature = "petgraph")]
+pub use checkpoint::*;
+
+#[cfg(feature = "petgraph")]
+mod cancel;
+#[cfg(feature = "petgraph")]
+pub use cancel::*;
+
+#[cfg(feature = "petgraph")]
+mod spawn;
+#[cfg(feature = "petgraph")]
+pub use spawn::*;
+
+mod snapshots;
+pub use snapshots::*;
+
+mod events;
+pub use events::*;
+
+#[cfg(feature = "petgraph")]
+mod crosscheck;
+#[cfg(feature = "petgraph")]
+pub use crosscheck::*;
+
+#[cfg(feature = "petgraph")]
+mod spec;
+#[cfg(feature = "petgraph")]
+pub use spec::*;
+
+mod routing;
+pub use routing::*;
+
+#[cfg(feature = "petgraph")]
+mod intervals;
+#[cfg(feature = "petgraph")]
+pub use intervals::*;
+
+#[cfg(feature = "petgraph")]
+mod layers;
+#[cfg(feature = "petgraph")]
+pub use layers::*;
+
+#[cfg(feature = "petgraph")]
+mod ksp;
+#[cfg(feature = "petgraph")]
+pub use ksp::*;
+
+#[cfg(feature = "petgraph")]
+mod usage;
+
+#[cfg(feature = "petgraph")]
+mod dag;
+#[cfg(feature = "petgraph")]
+pub use dag::*;
+
+#[cfg(feature = "petgraph")]
+mod state;
+#[cfg(feature = "petgraph")]
+pub use state::*;
+
+#[cfg(feature = "petgraph")]
+mod builder;
+#[cfg(feature = "petgraph")]
+pub use builder::*;
+
+mod progress;
+pub use progress::*;
+
+mod oracle;
+pub use oracle::*;
+
+#[cfg(feature = "petgraph")]
+mod explain;
+#[cfg(feature = "petgraph")]
+pub use explain::*;
+
+#[cfg(feature = "petgraph")]
+mod repair;
+#[cfg(feature = "petgraph")]
+pub use repair::*;
+
+#[cfg(feature = "petgraph")]
+mod measure;
+#[cfg(feature = "petgraph")]
+pub use measure::*;
+
+#[cfg(feature = "petgraph")]
+mod bfs;
+#[cfg(feature = "petgraph")]
+pub use bfs::*;
+
+#[cfg(feature = "petgraph")]
+mod dijkstra;
+#[cfg(feature = "petgraph")]
+pub use dijkstra::*;
+
+#[cfg(feature = "petgraph")]
+mod auto;
+#[cfg(feature = "petgraph")]
+pub use auto::*;
+
+mod rng;
+
+mod kernel;
+use kernel::Kernel;
+
+#[cfg(feature = "petgraph")]
 use petgraph::graph::NodeIndex;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::NodeRef;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::Data;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::GraphBase;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::NodeCount;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::IntoNodeIdentifiers;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::IntoNodeReferences;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::IntoEdgeReferences;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::EdgeRef;
+#[cfg(feature = "petgraph")]
 use petgraph::visit::GraphProp;
 
 /// This function computes a distance matrix containing the shortest paths between every two nodes in the graph.
 /// By using the Floyd-Warshall algorithm, this is computed in **O(V^(3))** runtime.
+#[cfg(feature = "petgraph")]
 pub fn floyd_warshall<G>(g: G) -> PathMatrix<G::NodeWeight>
 where
     G: Data
@@ -43,104 +320,242 @@ where
     G::NodeWeight: Clone,
     G::EdgeWeight: Clone + Into<usize>,
 {
-    // We currently only support directed graphs.
-    assert!(!g.is_directed());
+    floyd_warshall_with_options(g, &Options::default())
+}
 
+/// This function computes the same matrix as ```floyd_warshall```, but allows tuning the algorithm by the given ```Options```.
+#[cfg(feature = "petgraph")]
+pub fn floyd_warshall_with_options<G>(g: G, options: &Options) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
     let mut m = PathMatrix::new(g.node_count());
+    compute(g, options, &mut m, &mut Kernel::default());
+    m
+}
 
-    // Each node has a distance of 0 to itself.
-    // Note, that this sets the distance of every node to itself to 0, due to the matrix representation.
-    m.set_path_len(0, 0, 0);
+/// This function computes the same matrix as ```floyd_warshall```, but calls ```f``` after every iteration of the dynamic program
+/// with the number of finished iterations and the number of all iterations, so it can drive a progress bar. See ```Progress```.
+#[cfg(feature = "petgraph")]
+pub fn floyd_warshall_with_progress<G, F>(g: G, f: F) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+    F: Fn(usize, usize) + Send + Sync + 'static,
+{
+    let options = Options {
+        progress: Some(Progress::new(f)),
+        ..Options::default()
+    };
+    floyd_warshall_with_options(g, &options)
+}
 
-    // Update the matrix to represent the actual edges in the graph.
-    for e in g.edge_references() {
-        let n1 = e.source().index();
-        let n2 = e.target().index();
+/// This function computes the same matrix as ```floyd_warshall```, but its paths consist of the node indices instead of clones of the node weights.
+/// The node weights don't need to be cloneable this way, and the paths are easily correlated back to the graph, see ```PathMatrix::resolve```.
+#[cfg(feature = "petgraph")]
+pub fn floyd_warshall_indices<G>(g: G) -> PathMatrix<NodeIndex>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoEdgeReferences
+        + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut m = PathMatrix::new(g.node_count());
+    m.set_node_weights(g.node_identifiers());
+    let order = prepare_edges(g, &mut m);
+    compute_prepared(&order, &Options::default(), &mut m, &mut Kernel::default());
+    m
+}
+
+/// This function computes the same matrix as ```floyd_warshall``` for a graph, which is given by its number of nodes ```n``` and its edges ```(u, v, weight)```.
+/// It doesn't need petgraph, so it's available without the default feature ```petgraph```. The paths consist of the node indices.
+///
+/// # Panics
+///
+/// Panics, if an edge has an endpoint, which isn't smaller than ```n```.
+pub fn floyd_warshall_edges(
+    n: usize,
+    directed: bool,
+    edges: &[(usize, usize, usize)],
+) -> PathMatrix<usize> {
+    let mut m = PathMatrix::new(n);
+    m.set_node_weights(0..n);
+    store_edges(&mut m, directed, edges.iter().cloned());
+    let order: Vec<usize> = (0..n).collect();
+    compute_prepared(&order, &Options::default(), &mut m, &mut Kernel::default());
+    m
+}
+
+/// This function computes the same matrix as ```floyd_warshall```, but runs on all available cores.
+/// Within every iteration of the dynamic program, the rows of the matrix are relaxed in parallel. Afterwards, all paths are reconstructed up front in parallel.
+#[cfg(all(feature = "rayon", feature = "petgraph"))]
+pub fn floyd_warshall_par<G>(g: G) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone + Send + Sync,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    floyd_warshall_par_with_options(g, &Options::default())
+}
+
+/// This function computes the same matrix as ```floyd_warshall_par```, but allows tuning the algorithm and selecting the threads by the given ```Options```.
+#[cfg(all(feature = "rayon", feature = "petgraph"))]
+pub fn floyd_warshall_par_with_options<G>(g: G, options: &Options) -> PathMatrix<G::NodeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone + Send + Sync,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut m = PathMatrix::new(g.node_count());
+    let order = prepare(g, &mut m);
+    let mut kernel = Kernel::default();
+
+    options.install(|| {
+        kernel.load_par(&m, options.first_touch);
+        kernel.relax_par(&order);
+        kernel.store(&mut m);
+        m.par_materialize_paths();
+    });
+
+    m
+}
+
+/// Runs the Floyd-Warshall algorithm on the given graph and stores the result in ```m```, which has to be freshly reset to the node count of the graph.
+/// Only the lengths and the intermediate node each path was built over are recorded, the paths themselves are reconstructed on first access.
+/// The dynamic program runs on the dense buffers of ```kernel```, which can be reused across runs.
+#[cfg(feature = "petgraph")]
+pub(crate) fn compute<G>(
+    g: G,
+    options: &Options,
+    m: &mut PathMatrix<G::NodeWeight>,
+    kernel: &mut Kernel,
+) where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let order = prepare(g, m);
+    compute_prepared(&order, options, m, kernel);
+}
+
+/// Runs the Floyd-Warshall algorithm on ```m```, which already holds the node weights and edges of a graph, and uses the nodes in the given ```order``` as intermediate nodes.
+pub(crate) fn compute_prepared<T>(
+    order: &[usize],
+    options: &Options,
+    m: &mut PathMatrix<T>,
+    kernel: &mut Kernel,
+) {
+    // The node indices in ascending order, which are visited in tiles of the given size.
+    let mut ids = order.to_vec();
+    ids.sort_unstable();
+    kernel.load(m);
+    let tile = options.choose_tile_size(kernel, &ids);
+    options.start(kernel, 0);
+    options.relax(kernel, order, &ids, tile);
+    kernel.store(m);
+}
+
+/// Stores the node weights and the edges of the given graph in ```m```, which has to be freshly reset to the node count of the graph.
+/// Returns the node indices in the order, in which they are used as intermediate nodes.
+#[cfg(feature = "petgraph")]
+pub(crate) fn prepare<G>(g: G, m: &mut PathMatrix<G::NodeWeight>) -> Vec<usize>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    m.set_node_weights(g.node_references().map(|n| n.weight().clone()));
+    prepare_edges(g, m)
+}
+
+/// Stores the edges of the given graph in ```m``` like ```prepare```, but leaves the node weights of ```m``` alone.
+#[cfg(feature = "petgraph")]
+pub(crate) fn prepare_edges<G, T>(g: G, m: &mut PathMatrix<T>) -> Vec<usize>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoEdgeReferences
+        + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let edges = g.edge_references().map(|e| {
         let w: G::EdgeWeight = e.weight().clone();
-        let w: usize = w.into();
-        m.set_path_len(n1, n2, w);
+        (e.source().index(), e.target().index(), w.into())
+    });
+    store_edges(m, g.is_directed(), edges);
+
+    g.node_identifiers().map(|n| n.index()).collect()
+}
+
+/// Stores the given edges ```(u, v, weight)``` in ```m``` and switches it to the given mode, but leaves the node weights of ```m``` alone.
+pub(crate) fn store_edges<T, I>(m: &mut PathMatrix<T>, directed: bool, edges: I)
+where
+    I: IntoIterator<Item = (usize, usize, usize)>,
+{
+    // A directed graph needs the full matrix, because the paths in both directions can differ.
+    // Switching the mode keeps the node weights.
+    if m.is_directed() != directed {
+        m.set_directed(directed);
     }
 
-    // k is the "intermediate" node which is currently considered.
-    for k in g.node_references() {
-        let kw = k.weight();
-        let k = k.id().index();
-
-        // For every pair (n1, n2) of two disjunct nodes in the graph check, if the path over k is shorter than the previously found one.
-        for n1 in g.node_identifiers() {
-            let n1 = n1.index();
-
-            for n2 in g.node_identifiers() {
-                let n2 = n2.index();
-
-                // No need to do this for identical nodes.
-                if n1 == n2 {
-                    continue;
-                }
-
-                // No need to do this for both triangles in the matrix.
-                if n1 > n2 {
-                    continue;
-                }
-
-                // No need to do this for k == n1 or k == n2
-                if n1 == k || n2 == k {
-                    continue;
-                }
-
-                // These are the two options in this round to reach from node 1 to node 2:
-                // - v1, which is (if it exists) the saved path from n1 to n2, which is eiter a direct edge or a path using any intermediate nodes less than k.
-                let mut v1 = None;
-                if m.does_path_exist(n1, n2) {
-                    v1 = Some(m.get_path_len(n1, n2));
-                }
-
-                // - v2, which is the path from node 1 to node k to node 2 (if such a path exists, which means, that k is reachable from n1 and n2 is reachable from k).
-                let v2_exists = m.does_path_exist(n1, k);
-                let v2_exists = v2_exists && m.does_path_exist(k, n2);
-
-                let mut v2 = None;
-                if v2_exists {
-                    let part1 = m.get_path_len(n1, k);
-                    let part2 = m.get_path_len(k, n2);
-
-                    // .saturating_add is a relict of a time, when a path was usize::MAX as a sign for "there is no path here".
-                    // But as any other .add doesn't make any more sense, it will stay.
-                    v2 = Some(part1.saturating_add(part2));
-                }
-
-                // Whichever of these is minimal, can be used to reach from node 1 to node 2.
-                if v2.is_some() && (v1.is_none() || v2.unwrap() < v1.unwrap()) {
-                    let v2 = v2.unwrap();
-
-                    // Update the matrix to the minimum of these two.
-                    m.set_path_len(n1, n2, v2);
-
-                    // TODO: reuse vector here.
-                    let mut v: Vec<G::NodeWeight> = Vec::new();
-
-                    // Reverse path, if n1 < k or k < n2 not fulfilled:
-                    if n1 <= k {
-                        v.extend(m.get_path_iter(n1, k).cloned());
-                    } else {
-                        v.extend(m.get_path_iter(n1, k).rev().cloned());
-                    }
-
-                    // Push k in the middle of the path here.
-                    v.push(kw.clone());
-
-                    if k <= n2 {
-                        v.extend(m.get_path_iter(k, n2).cloned());
-                    } else {
-                        v.extend(m.get_path_iter(k, n2).rev().cloned());
-                    }
-
-                    // Save the path as new optimal path from node 1 to node 2.
-                    m.get_path_mut(n1, n2).set_vector(v);
-                }
-            }
+    // Each node has a distance of 0 to itself.
+    // Note, that in an undirected matrix this sets the distance of every node to itself to 0, due to the matrix representation.
+    if m.is_directed() {
+        for i in 0..m.node_count() {
+            m.set_path_len(i, i, 0);
         }
+    } else {
+        m.set_path_len(0, 0, 0);
     }
 
-    m
-}
\ No newline at end of file
+    // Update the matrix to represent the actual edges in the graph.
+    // A self-loop never shortens the distance of a node to itself, so it must not overwrite the 0 on the diagonal.
+    for (n1, n2, w) in edges {
+        if n1 == n2 {
+            continue;
+        }
+        m.set_path_len(n1, n2, w);
+    }
+}
@@ -1,83 +1,180 @@
-/// This represents a sequence of nodes. The length is also saved, and when ```exists = false```, this means "there is no path".
+use std::sync::OnceLock;
+
+/// A read-only view on the distances of an APSP solution.
+/// Algorithms, which only need the distances, are written against this trait, so they work with every matrix type of this crate.
+pub trait Distances {
+    /// Returns the number of nodes.
+    fn node_count(&self) -> usize;
+
+    /// Returns the distance between ```i``` and ```j```, or ```None```, if there is no path between them.
+    fn distance(&self, i: usize, j: usize) -> Option<usize>;
+}
+
+/// This represents a sequence of nodes. The length of type ```W``` is also saved, and when it's missing, this means "there is no path".
+/// The nodes of a path, which runs over an intermediate node, are reconstructed from the predecessor matrix on first access and cached afterwards.
 #[derive(Clone, Debug)]
-pub struct Path<T> {
-    v: Vec<T>,
-    len: usize,
-    exists: bool,
+pub struct Path<T, W = usize> {
+    v: OnceLock<Vec<T>>,
+    len: Option<W>,
+    via: Option<usize>,
 }
 
-impl<T> Path<T> {
+impl<T, W> Path<T, W> {
+    #[inline]
+    /// Replaces the intermediate nodes on this path by the given vector.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn set_vector(&mut self, v: Vec<T>) {
+        self.v = OnceLock::from(v);
+    }
+
+    #[inline]
+    /// Returns the intermediate nodes on this path, which are reconstructed by ```f``` if this didn't happen yet.
+    pub(crate) fn get_or_init<F: FnOnce() -> Vec<T>>(&self, f: F) -> &[T] {
+        self.v.get_or_init(f)
+    }
+
     #[inline]
-    pub(crate) fn set_vector(&mut self, t: Vec<T>) {
-        self.v = t
+    /// Has the node sequence of this path been reconstructed yet?
+    #[cfg(test)]
+    pub(crate) fn is_materialized(&self) -> bool {
+        self.v.get().is_some()
+    }
+
+    #[inline]
+    /// Turns this into "there is no path here" again, but keeps the allocated vector, if there is one.
+    pub(crate) fn clear(&mut self) {
+        match self.v.get_mut() {
+            Some(v) => v.clear(),
+            None => self.v = OnceLock::from(Vec::new()),
+        }
+        self.len = None;
+        self.via = None;
     }
 
     #[inline]
     /// Returns the intermediate nodes on this path as a slice.
-    pub fn get_slice<'a>(&'a self) -> &'a [T] {
-        &self.v
+    pub fn get_slice(&self) -> &[T] {
+        self.as_ref()
     }
 
     #[inline]
     /// Returns an iterator of the intermediat enodes on this path.
-    pub fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> {
-        self.v.iter()
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.get_slice().iter()
     }
 
     #[inline]
+    #[allow(clippy::len_without_is_empty)]
     /// Returns the length of this path.
-    pub fn len(&self) -> usize {
-        assert!(self.exists);
-        self.len
+    pub fn len(&self) -> W
+    where
+        W: Clone,
+    {
+        self.len.clone().expect("there is no path")
     }
 
     #[inline]
     /// Updates the length of this path. Also removes the "there is not path here"-flag.
-    pub(crate) fn set_len(&mut self, v: usize) {
-        self.len = v;
-        self.exists = true;
+    pub(crate) fn set_len(&mut self, v: W) {
+        self.len = Some(v);
+    }
+
+    #[inline]
+    /// Returns the intermediate node, which was used to build this path, or ```None``` if this path is a single edge.
+    pub fn via(&self) -> Option<usize> {
+        self.via
+    }
+
+    #[inline]
+    /// Records the intermediate node, which was used to build this path.
+    /// The node sequence is reconstructed from it later, so it's forgotten here.
+    pub(crate) fn set_via(&mut self, k: usize) {
+        self.via = Some(k);
+        self.v = OnceLock::new();
     }
 
     #[inline]
     /// Has this path finite length?
     pub fn exists(&self) -> bool {
-        self.exists
+        self.len.is_some()
     }
 }
 
-impl<T> AsRef<Vec<T>> for Path<T> {
+impl<T, W> AsRef<Vec<T>> for Path<T, W> {
     #[inline]
     fn as_ref(&self) -> &Vec<T> {
-        &self.v
+        // Every path handed out by a ```PathMatrix``` has been reconstructed already.
+        self.v.get().expect("the path wasn't reconstructed yet")
     }
 }
 
-impl<T> Default for Path<T> {
+impl<T, W> Default for Path<T, W> {
     #[inline]
     fn default() -> Self {
-        use std::usize::MAX;
         Path {
-            v: Vec::new(),
-            len: MAX,
-            exists: false,
+            v: OnceLock::from(Vec::new()),
+            len: None,
+            via: None,
         }
     }
 }
 
 /// This matrix is a solution to the APSP problem, calculated by the Floyd-Warshall algorithm.
 /// It contains the intermediate nodes on the shortest path between every two nodes.
+///
+/// The matrix of an undirected graph only stores one triangle, because the path from ```j``` to ```i``` is the reversed path from ```i``` to ```j```.
+/// The matrix of a directed graph stores every pair on its own, so the distances in both directions can differ.
+/// The analyses, which only look at the pairs ```(i, j)``` with ```i < j```, only see one direction of a directed matrix.
+///
+/// The lengths are of type ```W```, which is ```usize``` for the matrices computed by ```floyd_warshall```,
+/// and the edge weight for the ones computed by ```floyd_warshall_measure```. Most queries and analyses need ```usize``` lengths.
+///
+/// Only the length and the intermediate node of every pair are stored. The nodes of the paths aren't kept as vectors,
+/// but reconstructed on demand from the predecessor matrix, which is derived once from the intermediate nodes and takes ```V * V``` entries.
+/// The hop counts of ```hops``` are derived the same way on first access.
 #[derive(Debug)]
-pub struct PathMatrix<T> {
-    m: Box<[Path<T>]>,
+pub struct PathMatrix<T, W = usize> {
+    m: Box<[Path<T, W>]>,
     n: usize,
+    weights: Vec<T>,
+    directed: bool,
+    pred: OnceLock<Box<[usize]>>,
+    hops: OnceLock<Box<[usize]>>,
+}
+
+/// The entry of the predecessor matrix, if there is no path.
+const NO_NODE: usize = usize::MAX;
+
+/// Returns the number of stored entries of a matrix with ```n``` nodes.
+#[inline]
+pub(crate) fn storage_len(n: usize, directed: bool) -> usize {
+    if directed {
+        n * n
+    } else {
+        // One entry is shared by the diagonal.
+        1 + n * n.saturating_sub(1) / 2
+    }
 }
 
 impl<T> PathMatrix<T> {
     /// Creates a new ```PathMatrix``` with the given dimension (n * n), where no paths were found yet.
     /// That means, no nodes are yet connected in this matrix.
     pub fn new(n: usize) -> PathMatrix<T> {
+        PathMatrix::empty(n, false)
+    }
+
+    /// Creates a new ```PathMatrix``` for a directed graph with the given dimension (n * n), where no paths were found yet.
+    /// Other than ```new```, this stores the path from ```i``` to ```j``` apart from the path from ```j``` to ```i```.
+    pub fn new_directed(n: usize) -> PathMatrix<T> {
+        PathMatrix::empty(n, true)
+    }
+}
+
+impl<T, W> PathMatrix<T, W> {
+    /// Creates a matrix of the given mode with lengths of any type, where no paths were found yet.
+    pub(crate) fn empty(n: usize, directed: bool) -> PathMatrix<T, W> {
         let mut m = vec![];
-        let n_elems = 1 + n * (n - 1) / 2;
+        let n_elems = storage_len(n, directed);
 
         for _ in 0..n_elems {
             m.push(Path::default());
@@ -85,13 +182,86 @@ impl<T> PathMatrix<T> {
 
         let m = m.into();
 
-        PathMatrix { m, n }
+        PathMatrix {
+            m,
+            n,
+            weights: Vec::new(),
+            directed,
+            pred: OnceLock::new(),
+            hops: OnceLock::new(),
+        }
+    }
+
+    /// If this matrix stores the paths of a directed graph, this returns true.
+    #[inline]
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Switches the storage to the given mode. All paths are dropped, but the node weights are kept.
+    pub(crate) fn set_directed(&mut self, directed: bool) {
+        self.directed = directed;
+        self.forget_derived();
+        self.m = (0..storage_len(self.n, directed))
+            .map(|_| Path::default())
+            .collect();
+    }
+
+    /// Returns the dimension of this matrix, which is the number of nodes in the graph it was computed for.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the weight of node ```i```, if the matrix was computed from a graph.
+    #[inline]
+    pub fn node_weight(&self, i: usize) -> Option<&T> {
+        self.weights.get(i)
+    }
+
+    /// Saves the node weights of the graph this matrix is computed for.
+    #[inline]
+    pub(crate) fn set_node_weights<I: IntoIterator<Item = T>>(&mut self, weights: I) {
+        self.weights.clear();
+        self.weights.extend(weights);
+    }
+
+    /// Resets this matrix to the dimension (n * n), where no paths were found yet.
+    /// The allocated buffers are kept, so a matrix of the same or a smaller dimension doesn't allocate again.
+    pub(crate) fn reset(&mut self, n: usize) {
+        let n_elems = storage_len(n, self.directed);
+
+        if self.m.len() < n_elems {
+            let mut m = ::std::mem::take(&mut self.m).into_vec();
+            m.resize_with(n_elems, Path::default);
+            self.m = m.into();
+        }
+
+        // The index of a pair of an undirected matrix doesn't depend on the dimension, so a larger buffer can simply be used partially.
+        // The entries of a directed matrix are cleared all the same, so their moved indices don't matter.
+        for p in self.m[..n_elems].iter_mut() {
+            p.clear();
+        }
+
+        self.n = n;
+        self.weights.clear();
+        self.forget_derived();
+    }
+
+    /// Returns the number of stored entries, which is the length of the index range of ```idx```.
+    #[inline]
+    pub(crate) fn m_len(&self) -> usize {
+        storage_len(self.n, self.directed)
     }
 
     /// This method computes the "inner index" into the ```Vec``` by using the given X-Y-coordinates into the matrix.
     #[inline]
-    fn idx(&self, mut i: usize, mut j: usize) -> usize {
-        // Because we're only supporting undirected graphs and we only fill one half of the matrix,
+    pub(crate) fn idx(&self, mut i: usize, mut j: usize) -> usize {
+        if self.directed {
+            return i * self.n + j;
+        }
+
+        // Because the graph is undirected and we only fill one half of the matrix,
         // we can swap the two indices, so that i <= j.
         if i > j {
             ::std::mem::swap(&mut i, &mut j);
@@ -115,27 +285,241 @@ impl<T> PathMatrix<T> {
 
     /// This method returns the value at the given position.
     #[inline]
-    pub fn get_path_len(&self, i: usize, j: usize) -> usize {
+    pub fn get_path_len(&self, i: usize, j: usize) -> W
+    where
+        W: Clone,
+    {
         let idx = self.idx(i, j);
         self.m[idx].len()
     }
 
     /// This method returns the shortest path possible between i and i.
+    /// Its nodes are reconstructed on the first call for this pair.
     #[inline]
-    pub fn get_path(&self, i: usize, j: usize) -> &Path<T> {
+    pub fn get_path(&self, i: usize, j: usize) -> &Path<T, W>
+    where
+        T: Clone,
+    {
+        let p = self.entry(i, j);
+        p.get_or_init(|| {
+            // The paths of an undirected graph are stored in the direction from the smaller to the larger node index.
+            let mut v = Vec::new();
+            if self.directed {
+                self.expand_into(i, j, &mut v);
+            } else {
+                self.expand_into(i.min(j), i.max(j), &mut v);
+            }
+            v
+        });
+        p
+    }
+
+    /// Returns the stored path object for the two given nodes without reconstructing its nodes.
+    #[inline]
+    pub(crate) fn entry(&self, i: usize, j: usize) -> &Path<T, W> {
         let idx = self.idx(i, j);
         &self.m[idx]
     }
 
     /// This method returns the shortest path possible between i and i as an iterator.
     #[inline]
-    pub fn get_path_iter<'a>(
-        &'a self,
-        i: usize,
-        j: usize,
-    ) -> impl DoubleEndedIterator<Item = &'a T> {
-        let idx = self.idx(i, j);
-        self.m[idx].iter()
+    pub fn get_path_iter(&self, i: usize, j: usize) -> impl DoubleEndedIterator<Item = &T>
+    where
+        T: Clone,
+    {
+        self.get_path(i, j).iter()
+    }
+
+    /// Returns the intermediate nodes on the shortest path between i and j in the order from i to j.
+    /// Other than ```get_path_iter```, this reverses the stored path if necessary.
+    #[inline]
+    pub(crate) fn path_from(&self, i: usize, j: usize) -> impl Iterator<Item = &T>
+    where
+        T: Clone,
+    {
+        let rev = !self.directed && i > j;
+        let mut it = self.get_path_iter(i, j);
+        ::std::iter::from_fn(move || if rev { it.next_back() } else { it.next() })
+    }
+
+    /// Drops the tables, which are derived from the intermediate nodes, because the paths are about to change.
+    #[inline]
+    fn forget_derived(&mut self) {
+        self.pred.take();
+        self.hops.take();
+    }
+
+    /// Returns the predecessor matrix, which stores the node before ```j``` on the shortest path from ```i``` to ```j``` at ```i * n + j```.
+    /// It's derived from the intermediate nodes on first access: The path from ```i``` over ```k``` to ```j``` ends like the path from ```k``` to ```j```,
+    /// so every entry is found by following the intermediate nodes towards ```j``` until a single edge is left. Every entry is computed only once.
+    fn predecessors(&self) -> &[usize] {
+        self.pred.get_or_init(|| {
+            let n = self.n;
+            let mut pred = vec![NO_NODE; n * n];
+            let mut chain = Vec::new();
+
+            for j in 0..n {
+                for i in (0..n).filter(|&i| i != j) {
+                    if pred[i * n + j] != NO_NODE || !self.does_path_exist(i, j) {
+                        continue;
+                    }
+
+                    let mut s = i;
+                    let p = loop {
+                        if pred[s * n + j] != NO_NODE {
+                            break pred[s * n + j];
+                        }
+                        chain.push(s);
+                        match self.entry(s, j).via() {
+                            Some(k) => s = k,
+                            None => break s,
+                        }
+                    };
+
+                    for s in chain.drain(..) {
+                        pred[s * n + j] = p;
+                    }
+                }
+            }
+
+            pred.into()
+        })
+    }
+
+    /// Returns the node before ```j``` on the shortest path from ```i``` to ```j```, which is ```i``` itself, if the path is a single edge.
+    /// This is ```None```, if ```i == j``` or there is no path between them.
+    #[inline]
+    pub fn predecessor(&self, i: usize, j: usize) -> Option<usize> {
+        match self.predecessors()[i * self.n + j] {
+            NO_NODE => None,
+            p => Some(p),
+        }
+    }
+
+    /// Returns the number of edges on the shortest path from ```i``` to ```j```, which is 0 for ```i == j```, or ```None```, if there is no path between them.
+    /// The hop counts of all pairs are derived from the predecessor matrix on first access, so no path has to be reconstructed.
+    #[inline]
+    pub fn hops(&self, i: usize, j: usize) -> Option<usize> {
+        match self.hop_counts()[i * self.n + j] {
+            NO_NODE => None,
+            h => Some(h),
+        }
+    }
+
+    /// Returns the number of edges on the shortest path from ```i``` to ```j``` at ```i * n + j```, see ```hops```.
+    /// Every path is one edge longer than the path to the predecessor of its end, so every entry is computed once by walking back to a known entry.
+    fn hop_counts(&self) -> &[usize] {
+        self.hops.get_or_init(|| {
+            let n = self.n;
+            let pred = self.predecessors();
+            let mut hops = vec![NO_NODE; n * n];
+            let mut chain = Vec::new();
+
+            for i in 0..n {
+                hops[i * n + i] = 0;
+                for j in 0..n {
+                    let mut x = j;
+                    while hops[i * n + x] == NO_NODE && pred[i * n + x] != NO_NODE {
+                        chain.push(x);
+                        x = pred[i * n + x];
+                    }
+
+                    // Without a path, the walk stops right away at an unknown entry.
+                    let mut h = hops[i * n + x];
+                    if h == NO_NODE {
+                        continue;
+                    }
+                    for x in chain.drain(..).rev() {
+                        h += 1;
+                        hops[i * n + x] = h;
+                    }
+                }
+            }
+
+            hops.into()
+        })
+    }
+
+    /// Appends the intermediate nodes on the shortest path between i and j to ```out``` in the order from i to j.
+    /// They are found by walking the predecessor matrix back from j to i.
+    pub(crate) fn expand_into(&self, i: usize, j: usize, out: &mut Vec<T>)
+    where
+        T: Clone,
+    {
+        let n = self.n;
+        let pred = self.predecessors();
+        let start = out.len();
+
+        let mut p = pred[i * n + j];
+        while p != NO_NODE && p != i {
+            out.push(self.weights[p].clone());
+            p = pred[i * n + p];
+        }
+
+        out[start..].reverse();
+    }
+
+    /// Reconstructs the nodes of every path from the recorded intermediate nodes on all available cores,
+    /// instead of reconstructing them one by one on first access.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn par_materialize_paths(&mut self)
+    where
+        T: Clone + Send + Sync,
+        W: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        // The predecessor matrix is derived once before the threads share it.
+        self.predecessors();
+
+        if self.directed {
+            // Every pair (i, j) is stored at i * n + j, so the paths are collected row by row.
+            let n = self.n;
+            let paths: Vec<Vec<T>> = (0..n * n)
+                .into_par_iter()
+                .map(|p| {
+                    let mut v = Vec::new();
+                    self.expand_into(p / n, p % n, &mut v);
+                    v
+                })
+                .collect();
+
+            for (p, v) in self.m.iter_mut().zip(paths) {
+                p.set_vector(v);
+            }
+            return;
+        }
+
+        // The pairs (i, j) with i < j are stored row by row after the shared diagonal entry, so the paths are collected in that order.
+        let paths: Vec<Vec<T>> = (1..self.n)
+            .into_par_iter()
+            .flat_map_iter(|j| (0..j).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let mut v = Vec::new();
+                self.expand_into(i, j, &mut v);
+                v
+            })
+            .collect();
+
+        for (p, v) in self.m[1..].iter_mut().zip(paths) {
+            p.set_vector(v);
+        }
+    }
+
+    /// Returns the distance between ```i``` and ```j```, or ```None```, if there is no path between them.
+    /// The distance of a node to itself is ```W::default()```, which is 0 for all numbers.
+    #[inline]
+    pub fn distance(&self, i: usize, j: usize) -> Option<W>
+    where
+        W: Clone + Default,
+    {
+        if i == j {
+            Some(W::default())
+        } else if self.does_path_exist(i, j) {
+            Some(self.get_path_len(i, j))
+        } else {
+            None
+        }
     }
 
     /// If the matrix contains a path between i and j (which means, it has a set length), this returns true.
@@ -145,21 +529,47 @@ impl<T> PathMatrix<T> {
         self.m[idx].exists()
     }
 
+    /// Returns the intermediate node ```k```, which improved the path between ```i``` and ```j``` last during the run (the "via" table).
+    /// The shortest path consists of the paths from ```i``` to ```k``` and from ```k``` to ```j```, whose intermediate nodes can be asked for the same way.
+    /// This is ```None```, if the path is a single edge, ```i == j```, or there is no path between them.
+    #[inline]
+    pub fn via(&self, i: usize, j: usize) -> Option<usize> {
+        if i == j {
+            None
+        } else {
+            self.entry(i, j).via()
+        }
+    }
+
     /// Returns a mutable reference to the path object for the two given nodes.
     #[inline]
-    pub(crate) fn get_path_mut(&mut self, i: usize, j: usize) -> &mut Path<T> {
+    pub(crate) fn get_path_mut(&mut self, i: usize, j: usize) -> &mut Path<T, W> {
+        self.forget_derived();
         let idx = self.idx(i, j);
         &mut self.m[idx]
     }
 
     /// This method updates the value at the given position.
     #[inline]
-    pub fn set_path_len(&mut self, i: usize, j: usize, v: usize) {
+    pub fn set_path_len(&mut self, i: usize, j: usize, v: W) {
+        self.forget_derived();
         let idx = self.idx(i, j);
         self.m[idx].set_len(v);
     }
 }
 
+impl<T> Distances for PathMatrix<T> {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        PathMatrix::distance(self, i, j)
+    }
+}
+
 // impl<T> Debug for PathMatrix<T>
 // where
 //     T: Debug,
@@ -177,57 +587,51 @@ impl<T> PathMatrix<T> {
 //     }
 // }
 
-// /// This matrix is a solution to the APSP problem, calculated by the Floyd-Warshall algorithm. It contains the length of the shortest path for every pair of nodes in a given graph.
-// pub struct DistanceMatrix {
-//     m: Box<[usize]>,
-//     n: usize,
-// }
-
-// impl DistanceMatrix {
-//     /// Creates a new ```DistanceMatrix``` with the given dimension (n * n).
-//     pub(crate) fn new(n: usize) -> DistanceMatrix {
-//         use std::usize::MAX;
-//         let m = vec![MAX; n * n].into();
-//         DistanceMatrix { m, n }
-//     }
-
-//     /// This method computes the "inner index" into the ```Vec``` by using the given X-Y-coordinates into the matrix.
-//     fn idx(&self, mut i: usize, mut j: usize) -> usize {
-//         // We only fill one half of the matrix.
-//         if i > j {
-//             ::std::mem::swap(&mut i, &mut j);
-//         }
-//         assert!(i <= j);
+/// This matrix is a solution to the APSP problem, which only contains the length of the shortest path for every pair of nodes, but not the paths.
+/// The lengths of all pairs are stored in one flat array, so it needs only a fraction of the memory of a ```PathMatrix```.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistanceMatrix {
+    m: Box<[usize]>,
+    n: usize,
+}
 
-//         i + self.n * j
-//     }
+impl DistanceMatrix {
+    /// The stored length of a missing path.
+    pub(crate) const INFINITY: usize = usize::MAX;
 
-//     /// This method returns the value at the given position.
-//     pub fn get(&self, i: usize, j: usize) -> usize {
-//         let idx = self.idx(i, j);
-//         self.m[idx]
-//     }
+    /// Creates a ```DistanceMatrix``` from the row-major lengths of ```n * n``` pairs, where a missing path has the length ```INFINITY```.
+    pub(crate) fn from_vec(n: usize, m: Vec<usize>) -> DistanceMatrix {
+        assert_eq!(m.len(), n * n);
+        DistanceMatrix { m: m.into(), n }
+    }
 
-//     /// This method updates the value at the given position.
-//     pub fn set(&mut self, i: usize, j: usize, v: usize) {
-//         let idx = self.idx(i, j);
-//         self.m[idx] = v;
-//     }
-// }
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
 
-// impl fmt::Debug for DistanceMatrix {
-//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-//         use std::result::Result;
+    /// Returns the length of the shortest path from ```i``` to ```j```, or ```None```, if there is no path between them.
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> Option<usize> {
+        match self.m[i * self.n + j] {
+            DistanceMatrix::INFINITY => None,
+            d => Some(d),
+        }
+    }
+}
 
-//         for j in 0..self.n {
-//             let from = j * self.n;
-//             let to = j * self.n + j + 1;
-//             writeln!(f, "{:?}", &self.m[from..to])?
-//         }
+impl Distances for DistanceMatrix {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.n
+    }
 
-//         Result::Ok(())
-//     }
-// }
+    #[inline]
+    fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.get(i, j)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -264,4 +668,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[cfg(feature = "petgraph")]
+    fn test_lazy_paths() {
+        use floyd_warshall;
+        use petgraph::Graph;
+
+        let mut graph = Graph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        let c = graph.add_node(2);
+        let d = graph.add_node(3);
+        graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1)]);
+
+        let m = floyd_warshall(&graph);
+        assert!(!m.entry(0, 3).is_materialized());
+        assert!(!m.entry(1, 3).is_materialized());
+
+        assert_eq!(m.get_path(3, 0).get_slice(), &[1, 2]);
+        assert!(m.entry(0, 3).is_materialized());
+        assert!(!m.entry(1, 3).is_materialized());
+        assert_eq!(m.get_path_iter(1, 3).collect::<Vec<_>>(), vec![&2]);
+    }
 }
@@ -0,0 +1,106 @@
+//! The algorithm for edge weights of any numeric type, like floating point travel times.
+
+use petgraph::algo::Measure;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeRef;
+
+use PathMatrix;
+
+/// The intermediate node of a path, which is a single edge.
+const NO_VIA: usize = usize::MAX;
+
+/// This function computes the shortest paths between every two nodes like ```floyd_warshall```, but keeps the edge weights as they are,
+/// instead of converting them into ```usize```. The lengths of the returned matrix are of the edge weight type,
+/// so for example ```f64``` weights are summed up without losing precision.
+///
+/// Directed graphs may contain negative weights, as long as there is no cycle of negative length.
+/// Weights, which can't be compared, like ```NaN```, never make a path shorter.
+pub fn floyd_warshall_measure<G>(g: G) -> PathMatrix<G::NodeWeight, G::EdgeWeight>
+where
+    G: Data
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    G::NodeWeight: Clone,
+    G::EdgeWeight: Measure,
+{
+    let n = g.node_count();
+    let directed = g.is_directed();
+
+    // Dense buffers like the ones of the kernel, where a missing length is None.
+    let mut dist: Vec<Option<G::EdgeWeight>> = vec![None; n * n];
+    let mut via = vec![NO_VIA; n * n];
+
+    for i in 0..n {
+        dist[i * n + i] = Some(G::EdgeWeight::default());
+    }
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        if u == v {
+            continue;
+        }
+
+        // Of several parallel edges, the lightest one is used.
+        let w = e.weight().clone();
+        let mut set = |p: usize| {
+            if dist[p].as_ref().is_none_or(|d| w < *d) {
+                dist[p] = Some(w.clone());
+            }
+        };
+        set(u * n + v);
+        if !directed {
+            set(v * n + u);
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let to_k = match dist[i * n + k] {
+                Some(ref d) => d.clone(),
+                None => continue,
+            };
+
+            // In an undirected graph, only the pairs with i < j are computed and mirrored.
+            let start = if directed { 0 } else { i + 1 };
+            for j in start..n {
+                let over_k = match dist[k * n + j] {
+                    Some(ref d) => to_k.clone() + d.clone(),
+                    None => continue,
+                };
+
+                if dist[i * n + j].as_ref().is_none_or(|d| over_k < *d) {
+                    if !directed {
+                        dist[j * n + i] = Some(over_k.clone());
+                    }
+                    dist[i * n + j] = Some(over_k);
+                    via[i * n + j] = k;
+                }
+            }
+        }
+    }
+
+    let mut m = PathMatrix::empty(n, directed);
+    m.set_node_weights(g.node_references().map(|n| n.weight().clone()));
+    for i in 0..n {
+        let js = if directed { 0..n } else { i..n };
+        for j in js {
+            if let Some(d) = dist[i * n + j].take() {
+                m.set_path_len(i, j, d);
+            }
+            if via[i * n + j] != NO_VIA {
+                m.get_path_mut(i, j).set_via(via[i * n + j]);
+            }
+        }
+    }
+    m
+}
@@ -0,0 +1,61 @@
+//! Estimating the memory of a run before starting it.
+
+use std::mem::size_of;
+
+use petgraph::visit::Data;
+use petgraph::visit::GraphProp;
+use petgraph::visit::NodeCount;
+
+use matrices::storage_len;
+use Path;
+use PathMatrix;
+
+/// The kind of result, whose memory is estimated by ```estimate_memory```.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryMode {
+    /// A ```PathMatrix``` with the paths, which is computed by ```floyd_warshall```.
+    Paths,
+    /// Only the distances, which are computed by ```floyd_warshall_distances```.
+    Distances,
+    /// Only the distances in 16 bits, which are computed by ```floyd_warshall_compact```.
+    Compact,
+}
+
+impl<T, W> PathMatrix<T, W> {
+    /// Returns the number of bytes, which a matrix of an undirected graph with ```n``` nodes takes up.
+    /// The nodes of the paths are reconstructed on first access and cached afterwards, which isn't included.
+    /// The first reconstruction derives the predecessor matrix with ```n * n``` more entries of ```usize```, and so does the first call of ```hops```,
+    /// and each cached path takes ```size_of::<T>()``` more bytes per node on the path.
+    pub fn estimated_bytes(n: usize) -> usize {
+        PathMatrix::<T, W>::estimated_bytes_of(n, false)
+    }
+
+    /// Returns the number of bytes, which a matrix of a graph with ```n``` nodes in the given mode takes up, like ```estimated_bytes```.
+    pub(crate) fn estimated_bytes_of(n: usize, directed: bool) -> usize {
+        size_of::<PathMatrix<T, W>>()
+            + storage_len(n, directed) * size_of::<Path<T, W>>()
+            + n * size_of::<T>()
+    }
+}
+
+/// Predicts the peak number of bytes, which the computation of the given kind of result for the graph allocates.
+/// The graph itself isn't included. Nothing is allocated by the prediction, so it can be checked against the available memory before the run.
+///
+/// For ```MemoryMode::Paths```, this is the ```PathMatrix``` together with the dense buffers of the distances and intermediate nodes,
+/// which the dynamic program runs on. The paths, which are reconstructed later, aren't included, see ```PathMatrix::estimated_bytes```.
+pub fn estimate_memory<G>(g: G, mode: MemoryMode) -> usize
+where
+    G: Data + NodeCount + GraphProp,
+{
+    let n = g.node_count();
+    match mode {
+        MemoryMode::Paths => {
+            let matrix = PathMatrix::<G::NodeWeight>::estimated_bytes_of(n, g.is_directed());
+            // The distances and intermediate nodes of all pairs, and the order of the intermediate nodes together with the sorted node ids.
+            let buffers = 2 * n * n * size_of::<usize>() + 2 * n * size_of::<usize>();
+            matrix + buffers
+        }
+        MemoryMode::Distances => n * n * size_of::<usize>(),
+        MemoryMode::Compact => n * n * size_of::<u16>(),
+    }
+}
@@ -0,0 +1,170 @@
+//! Properties of the metric, which is given by the distances in a ```PathMatrix```.
+
+#[cfg(feature = "petgraph")]
+use petgraph::graph::NodeIndex;
+#[cfg(feature = "petgraph")]
+use petgraph::Graph;
+#[cfg(feature = "petgraph")]
+use petgraph::Undirected;
+
+use rng::SplitMix64;
+use PathMatrix;
+
+/// A violation of the metric axioms, which was found in a ```PathMatrix```.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricViolation {
+    /// The distance of the given node to itself isn't 0.
+    Diagonal(usize),
+    /// The distance from ```i``` to ```j``` differs from the distance from ```j``` to ```i```.
+    Symmetry(usize, usize),
+    /// The distance from ```i``` to ```j``` is greater than the distance from ```i``` to ```j``` over ```k```, given as ```(i, k, j)```.
+    Triangle(usize, usize, usize),
+}
+
+impl<T> PathMatrix<T> {
+    /// Returns the stored distance between ```i``` and ```j```, without assuming anything about the diagonal.
+    #[inline]
+    fn stored_distance(&self, i: usize, j: usize) -> Option<usize> {
+        if self.does_path_exist(i, j) {
+            Some(self.get_path_len(i, j))
+        } else {
+            None
+        }
+    }
+
+    /// Checks, whether the stored distances form a metric: Every node has distance 0 to itself,
+    /// the distances are symmetric and fulfill the triangle inequality. Unreachable pairs count as infinitely far apart.
+    /// This takes **O(V^(3))** time and returns the first violation found.
+    pub fn verify_metric(&self) -> Result<(), MetricViolation> {
+        let n = self.node_count();
+
+        for i in 0..n {
+            if self.stored_distance(i, i) != Some(0) {
+                return Err(MetricViolation::Diagonal(i));
+            }
+        }
+
+        for i in 0..n {
+            for j in i + 1..n {
+                if self.stored_distance(i, j) != self.stored_distance(j, i) {
+                    return Err(MetricViolation::Symmetry(i, j));
+                }
+            }
+        }
+
+        for i in 0..n {
+            for k in 0..n {
+                let d1 = match self.stored_distance(i, k) {
+                    Some(d) => d,
+                    None => continue,
+                };
+
+                for j in 0..n {
+                    let d2 = match self.stored_distance(k, j) {
+                        Some(d) => d,
+                        None => continue,
+                    };
+
+                    match self.stored_distance(i, j) {
+                        Some(d) if d <= d1.saturating_add(d2) => {}
+                        _ => return Err(MetricViolation::Triangle(i, k, j)),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the hyperbolicity of the quadruple ```(x, y, z, w)``` by the four-point condition, if all of them are connected
+    /// and the sums of their distances don't overflow.
+    fn quadruple_delta(&self, x: usize, y: usize, z: usize, w: usize) -> Option<f64> {
+        let s1 = self.distance(x, y)?.checked_add(self.distance(z, w)?)?;
+        let s2 = self.distance(x, z)?.checked_add(self.distance(y, w)?)?;
+        let s3 = self.distance(x, w)?.checked_add(self.distance(y, z)?)?;
+
+        let mut s = [s1, s2, s3];
+        s.sort_unstable();
+
+        Some((s[2] - s[1]) as f64 / 2.0)
+    }
+
+    /// Computes the Gromov δ-hyperbolicity of the metric by the four-point condition.
+    /// Only quadruples of nodes, which are pairwise connected, are taken into account.
+    /// Quadruples, whose sums of two distances don't fit into a ```usize```, are skipped.
+    /// If there is no such quadruple, ```None``` is returned.
+    ///
+    /// This checks every quadruple and takes **O(V^(4))** time, so it's only feasible for small graphs.
+    /// For larger graphs, use ```hyperbolicity_sampled```.
+    pub fn hyperbolicity(&self) -> Option<f64> {
+        let n = self.node_count();
+        let mut delta = None;
+
+        for x in 0..n {
+            for y in x + 1..n {
+                for z in y + 1..n {
+                    for w in z + 1..n {
+                        if let Some(d) = self.quadruple_delta(x, y, z, w) {
+                            delta = Some(delta.map_or(d, |delta: f64| delta.max(d)));
+                        }
+                    }
+                }
+            }
+        }
+
+        delta
+    }
+
+    /// Estimates the Gromov δ-hyperbolicity of the metric by checking ```samples``` random quadruples of nodes.
+    /// The result is a lower bound of the exact value and is reproducible for the same ```seed```.
+    /// Quadruples are skipped like in ```hyperbolicity```, so if none of the sampled quadruples is taken into account, ```None``` is returned.
+    pub fn hyperbolicity_sampled(&self, samples: usize, seed: u64) -> Option<f64> {
+        let n = self.node_count();
+        if n < 4 {
+            return None;
+        }
+
+        let mut rng = SplitMix64::new(seed);
+        let mut delta = None;
+
+        for _ in 0..samples {
+            let x = rng.below(n);
+            let y = rng.below(n);
+            let z = rng.below(n);
+            let w = rng.below(n);
+
+            if let Some(d) = self.quadruple_delta(x, y, z, w) {
+                delta = Some(delta.map_or(d, |delta: f64| delta.max(d)));
+            }
+        }
+
+        delta
+    }
+
+    /// Builds the metric closure of the given nodes: An undirected graph with one node per entry of ```nodes```,
+    /// which carries the node weight of that node, and an edge between every two of them, whose weight is their distance.
+    /// Node ```nodes[a]``` becomes the node with the index ```a```, and pairs without a path aren't connected.
+    ///
+    /// The result can directly be passed to the algorithms of petgraph, for example to ```min_spanning_tree``` for a Steiner tree approximation.
+    #[cfg(feature = "petgraph")]
+    pub fn metric_closure(&self, nodes: &[usize]) -> Graph<T, usize, Undirected>
+    where
+        T: Clone,
+    {
+        let mut g =
+            Graph::with_capacity(nodes.len(), nodes.len() * nodes.len().saturating_sub(1) / 2);
+        for &i in nodes {
+            let w = self.node_weight(i).expect("the node weights are unknown");
+            g.add_node(w.clone());
+        }
+
+        for (a, &i) in nodes.iter().enumerate() {
+            for (b, &j) in nodes.iter().enumerate().skip(a + 1) {
+                if let Some(d) = self.distance(i, j) {
+                    g.add_edge(NodeIndex::new(a), NodeIndex::new(b), d);
+                }
+            }
+        }
+        g
+    }
+}
@@ -0,0 +1,230 @@
+//! The loops of the distance-only modes: The blocked algorithm over a flat matrix and its innermost min-plus product of a row.
+//! With the feature ```simd```, a row of 16 bit distances is relaxed on 16 distances at once on x86-64 processors with AVX2.
+
+use std::ops::Range;
+use std::time::Duration;
+use std::time::Instant;
+
+use compact::INFINITY;
+use compact::OVERFLOW;
+use DistanceMatrix;
+
+/// The number of nodes of the submatrix, on which the traversals are benchmarked by ```tune```.
+const TUNE_NODES: usize = 128;
+
+/// A distance in a flat matrix of the distance-only modes.
+pub(crate) trait Cell: Copy + PartialEq {
+    /// The length of a missing path.
+    const MISSING: Self;
+
+    /// Relaxes every distance in ```row``` by the path over ```k```, where ```to_k``` is the distance to ```k```,
+    /// and ```from_k``` are the distances from ```k``` to the same nodes as in ```row```.
+    fn relax_row(row: &mut [Self], from_k: &[Self], to_k: Self);
+}
+
+impl Cell for u16 {
+    const MISSING: u16 = INFINITY;
+
+    #[inline]
+    fn relax_row(row: &mut [u16], from_k: &[u16], to_k: u16) {
+        relax_row(row, from_k, to_k);
+    }
+}
+
+impl Cell for usize {
+    const MISSING: usize = DistanceMatrix::INFINITY;
+
+    #[inline]
+    fn relax_row(row: &mut [usize], from_k: &[usize], to_k: usize) {
+        for (d, &from_k) in row.iter_mut().zip(from_k) {
+            // If the path from k is missing, this saturates to INFINITY, so it never wins.
+            let over_k = to_k.saturating_add(from_k);
+            if over_k < *d {
+                *d = over_k;
+            }
+        }
+    }
+}
+
+/// Relaxes the pairs of the rows ```rows``` and the columns ```cols``` over the intermediate nodes ```ks``` one after another with ```relax_row```.
+fn relax_block<C: Cell, F: Fn(&mut [C], &[C], C)>(
+    d: &mut [C],
+    n: usize,
+    rows: Range<usize>,
+    cols: Range<usize>,
+    ks: Range<usize>,
+    relax_row: &F,
+) {
+    for k in ks {
+        for i in rows.clone() {
+            let to_k = d[i * n + k];
+            // The row of k itself can't get shorter over k, as its distance to itself is 0.
+            if to_k == C::MISSING || i == k {
+                continue;
+            }
+
+            // The rows of i and k are split apart, so the row of k can be read while the row of i is written.
+            let (row, from_k) = if i < k {
+                let (head, tail) = d.split_at_mut(k * n);
+                (&mut head[i * n..(i + 1) * n], &tail[..n])
+            } else {
+                let (head, tail) = d.split_at_mut(i * n);
+                (&mut tail[..n], &head[k * n..(k + 1) * n])
+            };
+            relax_row(&mut row[cols.clone()], &from_k[cols.clone()], to_k);
+        }
+    }
+}
+
+/// Relaxes the row-major distances ```d``` of ```n``` nodes over all intermediate nodes by the blocked variant of the algorithm.
+///
+/// The intermediate nodes are processed block by block. Each block is first used to relax its tile on the diagonal,
+/// then the other tiles in its rows and columns, which only depend on the diagonal tile, and then all remaining tiles,
+/// which only depend on the tiles in its rows and columns. So every tile is relaxed by a whole block, while it stays in the cache.
+pub(crate) fn relax_tiled<C: Cell>(d: &mut [C], n: usize, tile: usize) {
+    relax_tiled_with(d, n, tile, &C::relax_row);
+}
+
+/// Does the same as ```relax_tiled```, but relaxes the rows with the given function.
+fn relax_tiled_with<C: Cell, F: Fn(&mut [C], &[C], C)>(
+    d: &mut [C],
+    n: usize,
+    tile: usize,
+    relax_row: &F,
+) {
+    let blocks: Vec<Range<usize>> = (0..n).step_by(tile).map(|b| b..(b + tile).min(n)).collect();
+    for kb in &blocks {
+        relax_block(d, n, kb.clone(), kb.clone(), kb.clone(), relax_row);
+        for b in blocks.iter().filter(|&b| b != kb) {
+            relax_block(d, n, kb.clone(), b.clone(), kb.clone(), relax_row);
+            relax_block(d, n, b.clone(), kb.clone(), kb.clone(), relax_row);
+        }
+        for rows in blocks.iter().filter(|&b| b != kb) {
+            for cols in blocks.iter().filter(|&b| b != kb) {
+                relax_block(d, n, rows.clone(), cols.clone(), kb.clone(), relax_row);
+            }
+        }
+    }
+}
+
+/// Relaxes the row-major 16 bit distances ```d``` of ```n``` nodes like ```relax_tiled```.
+/// If ```vectorized``` is set, the rows are relaxed by ```relax_row```, which uses AVX2 where it's available, otherwise by the portable kernel.
+pub(crate) fn relax_compact(d: &mut [u16], n: usize, tile: usize, vectorized: bool) {
+    if vectorized {
+        relax_tiled_with(d, n, tile, &relax_row);
+    } else {
+        relax_tiled_with(d, n, tile, &relax_scalar);
+    }
+}
+
+/// Checks, whether ```relax_row``` uses a vectorized kernel on this processor.
+pub(crate) fn is_vectorized() -> bool {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Benchmarks ```relax_compact``` with each of the given tile sizes, with the portable and, if it's available, the vectorized kernel,
+/// on a copy of the submatrix of the first nodes of the row-major 16 bit distances ```d``` of ```n``` nodes, and returns the fastest combination.
+/// A tile size of at least the node count is the naive traversal.
+pub(crate) fn tune(d: &[u16], n: usize, candidates: &[usize]) -> (usize, bool) {
+    let p = n.min(TUNE_NODES);
+    let mut sub = Vec::with_capacity(p * p);
+    for i in 0..p {
+        sub.extend_from_slice(&d[i * n..i * n + p]);
+    }
+
+    let kernels: &[bool] = if is_vectorized() {
+        &[false, true]
+    } else {
+        &[false]
+    };
+
+    let mut best = (Duration::MAX, candidates[0], false);
+    for &vectorized in kernels {
+        for &tile in candidates {
+            let mut run = sub.clone();
+            let start = Instant::now();
+            relax_compact(&mut run, p, tile.max(1), vectorized);
+            best = best.min((start.elapsed(), tile, vectorized));
+        }
+    }
+
+    (best.1, best.2)
+}
+
+/// Relaxes every distance in ```row``` by the path over ```k```, where ```to_k``` is the distance to ```k```,
+/// and ```from_k``` are the distances from ```k``` to the same nodes as in ```row```.
+/// A sum, which doesn't fit, is clamped to ```OVERFLOW```. As every path built on top of it is at least as long, it stays marked.
+#[inline]
+pub(crate) fn relax_row(row: &mut [u16], from_k: &[u16], to_k: u16) {
+    debug_assert_eq!(row.len(), from_k.len());
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // This is safe, because the processor supports AVX2.
+            unsafe { avx2::relax_row(row, from_k, to_k) };
+            return;
+        }
+    }
+
+    relax_scalar(row, from_k, to_k);
+}
+
+/// The portable version of ```relax_row```.
+fn relax_scalar(row: &mut [u16], from_k: &[u16], to_k: u16) {
+    for (d, &from_k) in row.iter_mut().zip(from_k) {
+        if from_k == INFINITY {
+            continue;
+        }
+
+        let over_k = (to_k as u32 + from_k as u32).min(OVERFLOW as u32) as u16;
+        if over_k < *d {
+            *d = over_k;
+        }
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    use super::relax_scalar;
+    use super::INFINITY;
+    use super::OVERFLOW;
+
+    /// The number of distances in a vector register.
+    const LANES: usize = 16;
+
+    /// Does the same as ```relax_scalar```, but on ```LANES``` distances at once.
+    /// The distances after the last full vector are relaxed by ```relax_scalar```.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn relax_row(row: &mut [u16], from_k: &[u16], to_k: u16) {
+        let to_k_v = _mm256_set1_epi16(to_k as i16);
+        let infinity = _mm256_set1_epi16(INFINITY as i16);
+        let overflow = _mm256_set1_epi16(OVERFLOW as i16);
+
+        let full = row.len() / LANES * LANES;
+        for p in (0..full).step_by(LANES) {
+            let d_ptr = row.as_mut_ptr().add(p) as *mut __m256i;
+            let d = _mm256_loadu_si256(d_ptr);
+            let f = _mm256_loadu_si256(from_k.as_ptr().add(p) as *const __m256i);
+
+            // The saturating sum is clamped to OVERFLOW, unless the distance from k is INFINITY,
+            // whose lanes are set to INFINITY by the mask, so they never replace a distance.
+            let sum = _mm256_min_epu16(_mm256_adds_epu16(to_k_v, f), overflow);
+            let missing = _mm256_cmpeq_epi16(f, infinity);
+            let over_k = _mm256_or_si256(sum, missing);
+
+            _mm256_storeu_si256(d_ptr, _mm256_min_epu16(d, over_k));
+        }
+
+        relax_scalar(&mut row[full..], &from_k[full..], to_k);
+    }
+}
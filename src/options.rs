@@ -0,0 +1,263 @@
+//! Tuning parameters of the algorithm.
+
+use std::fmt;
+use std::mem::size_of;
+use std::sync::Arc;
+
+#[cfg(feature = "rayon")]
+use rayon::ThreadPool;
+
+use kernel::Kernel;
+#[cfg(feature = "petgraph")]
+use minplus;
+use EventLog;
+use Progress;
+use Snapshots;
+
+/// The size of the cache, which a single tile of the matrix should fit in. This is a typical L1 data cache size.
+const TILE_BYTES: usize = 32 * 1024;
+
+/// Options to tune the algorithm for the machine it's running on. The defaults are chosen automatically.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// The number of nodes per side of the square tiles, in which the matrix is traversed.
+    /// If this is ```None```, the tile size is chosen so that a tile fits into a typical L1 cache.
+    pub tile_size: Option<usize>,
+    /// The threads, which the parallel functions of this crate run on. The sequential functions ignore this.
+    pub threads: Threads,
+    /// If this is set, the parallel functions allocate the dense buffers anew and let every thread initialize the rows it works on.
+    /// Operating systems place a page on the NUMA node of the thread, which touches it first, so this avoids remote memory accesses on multi-socket machines.
+    pub first_touch: bool,
+    /// If this is set and no tile size is given, the naive and the blocked traversal with several tile sizes are benchmarked on a small part of the matrix before the run,
+    /// and the fastest one is used for the whole run. This costs a few milliseconds, which pays off for large graphs.
+    /// ```FloydWarshall::run_compact``` also benchmarks the vectorized kernel of the feature ```simd``` against the portable one, if the processor supports it.
+    pub auto_tune: bool,
+    /// A callback, which is called for every relaxation, that makes a path shorter, in the order in which they happen.
+    /// Relaxing with a callback is considerably slower, so this is meant for tracing and debugging. The parallel functions ignore this.
+    pub observer: Option<Observer>,
+    /// A recorder, which receives the distances after every iteration. Like the observer, this slows the run down and is ignored by the parallel functions.
+    pub snapshots: Option<Snapshots>,
+    /// A log, which the events of the run are written to. Like the observer, this slows the run down and is ignored by the parallel functions.
+    pub event_log: Option<EventLog>,
+    /// If this is set, ties between equally long paths are broken at random with this seed, instead of keeping the path found first.
+    /// Every tie is decided with a probability proportional to the number of shortest paths on both sides, so the chosen paths spread the load
+    /// over the alternative routes, and the same seed always gives the same paths. Like the observer, this slows the run down and is ignored by the parallel functions.
+    pub random_ties: Option<u64>,
+    /// A callback, which is told the progress after every iteration of the dynamic program.
+    /// This only splits the run into single iterations, so it costs little. The parallel functions ignore it.
+    pub progress: Option<Progress>,
+}
+
+/// A relaxation of the dynamic program, which made the path from ```i``` to ```j``` shorter by going over ```k```.
+/// In an undirected graph, only pairs with ```i < j``` are relaxed, because the matrix is symmetric.
+/// In a directed graph, every ordered pair with ```i != j``` is relaxed on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Relaxation {
+    /// The intermediate node.
+    pub k: usize,
+    /// The start of the path, which is the smaller node of the pair in an undirected graph.
+    pub i: usize,
+    /// The end of the path, which is the larger node of the pair in an undirected graph.
+    pub j: usize,
+    /// The length of the path before, or ```None```, if there was no path yet.
+    pub old: Option<usize>,
+    /// The length of the new path over ```k```.
+    pub new: usize,
+}
+
+/// A callback, which observes the relaxations of a run. See ```Options::observer```.
+#[derive(Clone)]
+pub struct Observer(Arc<dyn Fn(Relaxation) + Send + Sync>);
+
+impl Observer {
+    /// Wraps the given callback.
+    pub fn new<F>(f: F) -> Observer
+    where
+        F: Fn(Relaxation) + Send + Sync + 'static,
+    {
+        Observer(Arc::new(f))
+    }
+
+    /// Calls the callback for the given relaxation.
+    #[inline]
+    pub(crate) fn notify(&self, r: Relaxation) {
+        (self.0)(r)
+    }
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Observer")
+    }
+}
+
+impl PartialEq for Observer {
+    fn eq(&self, other: &Observer) -> bool {
+        // Two callbacks are only the same, if they are the same object.
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Observer {}
+
+/// Selects the threads, on which the parallel functions run.
+#[derive(Clone, Debug, Default)]
+pub enum Threads {
+    /// Run on the global thread pool of rayon.
+    #[default]
+    Global,
+    /// Run on a new thread pool with the given number of threads, which is built for every call.
+    Count(usize),
+    /// Run on the given thread pool.
+    #[cfg(feature = "rayon")]
+    Pool(Arc<ThreadPool>),
+}
+
+impl PartialEq for Threads {
+    fn eq(&self, other: &Threads) -> bool {
+        match (self, other) {
+            (Threads::Global, Threads::Global) => true,
+            (Threads::Count(a), Threads::Count(b)) => a == b,
+            // Two pools are only the same, if they are the same object.
+            #[cfg(feature = "rayon")]
+            (Threads::Pool(a), Threads::Pool(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Threads {}
+
+impl Options {
+    /// Returns the tile sizes, which are benchmarked for a graph with ```n``` nodes and a matrix with ```pair_bytes``` bytes per pair, if ```auto_tune``` is set.
+    /// The first one is the naive traversal, the others are blocked traversals around the automatically chosen tile size.
+    pub(crate) fn tune_candidates(&self, n: usize, pair_bytes: usize) -> Vec<usize> {
+        let tile = Options::default().tile_size_for(pair_bytes);
+        vec![n.max(1), tile, (tile / 2).max(1), tile * 2]
+    }
+
+    /// Returns the tile size to use for the nodes ```ids``` (in ascending order) in the loaded ```kernel```.
+    /// If ```auto_tune``` is set and no tile size is given, the kernel is benchmarked for this.
+    pub(crate) fn choose_tile_size(&self, kernel: &Kernel, ids: &[usize]) -> usize {
+        if self.auto_tune && self.tile_size.is_none() {
+            kernel.tune(
+                ids,
+                &self.tune_candidates(ids.len(), 2 * size_of::<usize>()),
+            )
+        } else {
+            self.tile_size()
+        }
+    }
+
+    /// Returns the tile size to use for the row-major 16 bit distances ```d``` of ```n``` nodes, and whether to relax them with the vectorized kernel.
+    /// If ```auto_tune``` is set and no tile size is given, both kernels are benchmarked for this. Otherwise, the vectorized kernel is used, where it's available.
+    #[cfg(feature = "petgraph")]
+    pub(crate) fn choose_compact(&self, d: &[u16], n: usize) -> (usize, bool) {
+        if self.auto_tune && self.tile_size.is_none() {
+            minplus::tune(d, n, &self.tune_candidates(n, size_of::<u16>()))
+        } else {
+            (self.tile_size_for(size_of::<u16>()), true)
+        }
+    }
+
+    /// Prepares the recordings of a run on the loaded ```kernel```, whose first ```done``` iterations are already finished, before the first call of ```relax```.
+    pub(crate) fn start(&self, kernel: &Kernel, done: usize) {
+        if let Some(ref snapshots) = self.snapshots {
+            snapshots.start(kernel);
+        }
+        if let Some(ref log) = self.event_log {
+            log.start(kernel);
+        }
+        if let Some(ref progress) = self.progress {
+            progress.start(kernel, done);
+        }
+    }
+
+    /// Runs the relaxation for the intermediate nodes in ```order``` on the loaded ```kernel```,
+    /// either with the given tile size or, if anything records the run or ties are broken at random, with the observed kernel one node at a time.
+    pub(crate) fn relax(&self, kernel: &mut Kernel, order: &[usize], ids: &[usize], tile: usize) {
+        let observed = self.observer.is_some()
+            || self.snapshots.is_some()
+            || self.event_log.is_some()
+            || self.random_ties.is_some();
+
+        if !observed && self.progress.is_none() {
+            kernel.relax(order, ids, tile);
+            return;
+        }
+
+        for &k in order {
+            if observed {
+                self.relax_observed(kernel, k, ids);
+            } else {
+                kernel.relax(&[k], ids, tile);
+            }
+
+            if let Some(ref progress) = self.progress {
+                progress.advance();
+            }
+        }
+    }
+
+    /// Runs the relaxation for the intermediate node ```k``` with the observed kernel and passes the relaxations to everything, that records the run.
+    fn relax_observed(&self, kernel: &mut Kernel, k: usize, ids: &[usize]) {
+        if let Some(ref log) = self.event_log {
+            log.iteration(k);
+        }
+
+        let mut changes = Vec::new();
+        let mut report = |r: Relaxation| {
+            if let Some(ref observer) = self.observer {
+                observer.notify(r);
+            }
+            if let Some(ref log) = self.event_log {
+                log.relax(&r);
+            }
+            if self.snapshots.is_some() {
+                changes.push((r.i, r.j, r.new));
+            }
+        };
+
+        match self.random_ties {
+            Some(seed) => kernel.relax_random(&[k], ids, seed, &mut report),
+            None => kernel.relax_observed(&[k], ids, &mut report),
+        }
+
+        if let Some(ref snapshots) = self.snapshots {
+            snapshots.push(k, changes);
+        }
+    }
+
+    /// Returns the tile size to use for the kernel, which stores a length and an intermediate node per pair.
+    pub(crate) fn tile_size(&self) -> usize {
+        self.tile_size_for(2 * size_of::<usize>())
+    }
+
+    /// Returns the tile size to use for a matrix, which stores ```pair_bytes``` bytes per pair.
+    pub(crate) fn tile_size_for(&self, pair_bytes: usize) -> usize {
+        match self.tile_size {
+            Some(t) => t.max(1),
+            None => ((TILE_BYTES / pair_bytes) as f64).sqrt().max(1.0) as usize,
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl Options {
+    /// Runs ```f``` on the threads selected by these options, so that all parallel iterators in it use them.
+    pub(crate) fn install<R, F>(&self, f: F) -> R
+    where
+        R: Send,
+        F: FnOnce() -> R + Send,
+    {
+        match self.threads {
+            Threads::Global => f(),
+            Threads::Count(n) => ::rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .expect("the thread pool couldn't be built")
+                .install(f),
+            Threads::Pool(ref pool) => pool.install(f),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! A read-only query service on a computed matrix, which can be shared between threads.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use PathMatrix;
+
+/// The reconstructed paths of a ```DistOracle```, which are evicted in the order of their insertion.
+#[derive(Debug)]
+struct Paths {
+    capacity: usize,
+    paths: HashMap<(usize, usize), Arc<[usize]>>,
+    order: VecDeque<(usize, usize)>,
+}
+
+#[derive(Debug)]
+struct Inner<T, K> {
+    m: PathMatrix<T>,
+    index: Option<HashMap<K, usize>>,
+    paths: Option<Mutex<Paths>>,
+}
+
+/// An oracle for distances and paths, which owns a computed ```PathMatrix``` and only answers queries on it.
+/// A clone of the oracle shares the matrix, the label map and the cache of paths, so it's cheap,
+/// and the oracle can be handed to every worker thread of a server.
+///
+/// The nodes can also be addressed by a label, if the oracle was created with a label map.
+/// If several nodes have the same label, the one with the smallest index is used.
+#[derive(Debug)]
+pub struct DistOracle<T, K = T>(Arc<Inner<T, K>>);
+
+impl<T, K> Clone for DistOracle<T, K> {
+    fn clone(&self) -> DistOracle<T, K> {
+        DistOracle(self.0.clone())
+    }
+}
+
+impl<T> DistOracle<T> {
+    /// Creates an oracle for the given matrix, which doesn't know any labels.
+    pub fn new(m: PathMatrix<T>) -> DistOracle<T> {
+        DistOracle(Arc::new(Inner {
+            m,
+            index: None,
+            paths: None,
+        }))
+    }
+
+    /// Creates an oracle for the given matrix, which uses the node weights as labels.
+    ///
+    /// # Panics
+    ///
+    /// The node weights have to be known, so this panics for a matrix with nodes, which wasn't computed from a graph.
+    pub fn with_labels(m: PathMatrix<T>) -> DistOracle<T>
+    where
+        T: Clone + Hash + Eq,
+    {
+        DistOracle::with_labels_by(m, |w| w.clone())
+    }
+}
+
+impl<T, K> DistOracle<T, K> {
+    /// Creates an oracle for the given matrix, which labels every node by applying ```key``` to its weight.
+    ///
+    /// # Panics
+    ///
+    /// The node weights have to be known, so this panics for a matrix with nodes, which wasn't computed from a graph.
+    pub fn with_labels_by<F>(m: PathMatrix<T>, mut key: F) -> DistOracle<T, K>
+    where
+        K: Hash + Eq,
+        F: FnMut(&T) -> K,
+    {
+        let mut index = HashMap::new();
+        for i in 0..m.node_count() {
+            let w = m.node_weight(i).expect("the node weights are unknown");
+            index.entry(key(w)).or_insert(i);
+        }
+
+        DistOracle(Arc::new(Inner {
+            m,
+            index: Some(index),
+            paths: None,
+        }))
+    }
+
+    /// Keeps up to ```capacity``` reconstructed paths in a cache, which is shared by all clones of the oracle.
+    /// If the cache is full, the oldest path is evicted.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if ```capacity``` is 0, or if the oracle was cloned already.
+    pub fn cache_paths(mut self, capacity: usize) -> DistOracle<T, K> {
+        assert!(capacity > 0, "the capacity must not be 0");
+        let inner = Arc::get_mut(&mut self.0).expect("the oracle was cloned already");
+        inner.paths = Some(Mutex::new(Paths {
+            capacity,
+            paths: HashMap::new(),
+            order: VecDeque::new(),
+        }));
+        self
+    }
+
+    /// Returns the matrix, on which the queries are answered.
+    #[inline]
+    pub fn matrix(&self) -> &PathMatrix<T> {
+        &self.0.m
+    }
+
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.0.m.node_count()
+    }
+
+    /// Returns the distance between ```i``` and ```j```, or ```None```, if there is no path between them.
+    #[inline]
+    pub fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.0.m.distance(i, j)
+    }
+
+    /// Returns the nodes on the shortest path from ```i``` to ```j``` in that order, including both endpoints.
+    /// This is ```None```, if there is no path between them.
+    /// If the oracle caches paths, the path is reconstructed only, if it isn't in the cache yet.
+    pub fn path(&self, i: usize, j: usize) -> Option<Arc<[usize]>> {
+        self.distance(i, j)?;
+
+        let cache = match self.0.paths {
+            Some(ref cache) => cache,
+            None => return Some(self.reconstruct(i, j)),
+        };
+
+        if let Some(path) = cache.lock().unwrap().paths.get(&(i, j)) {
+            return Some(path.clone());
+        }
+
+        // The lock isn't held during the reconstruction, so other threads aren't blocked by a long path.
+        let path = self.reconstruct(i, j);
+        let mut cache = cache.lock().unwrap();
+        if !cache.paths.contains_key(&(i, j)) {
+            if cache.paths.len() == cache.capacity {
+                let oldest = cache.order.pop_front().unwrap();
+                cache.paths.remove(&oldest);
+            }
+            cache.paths.insert((i, j), path.clone());
+            cache.order.push_back((i, j));
+        }
+        Some(path)
+    }
+
+    /// Returns all nodes ```(j, distance)```, which are reachable from ```i``` within the distance ```d```, in ascending order of ```j```.
+    /// Node ```i``` itself is not included.
+    pub fn within(&self, i: usize, d: usize) -> Vec<(usize, usize)> {
+        self.0.m.reachable_within(i, d).collect()
+    }
+
+    /// Returns the index of the node with the given label, or ```None```, if there is no such node or the oracle doesn't know any labels.
+    pub fn index_of<Q>(&self, label: &Q) -> Option<usize>
+    where
+        K: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.index.as_ref()?.get(label).cloned()
+    }
+
+    /// Returns the distance between the nodes with the labels ```a``` and ```b```.
+    /// This is ```None```, if one of the labels is unknown or there is no path between the nodes.
+    pub fn distance_by_label<Q>(&self, a: &Q, b: &Q) -> Option<usize>
+    where
+        K: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.distance(self.index_of(a)?, self.index_of(b)?)
+    }
+
+    /// Returns the nodes on the shortest path between the nodes with the labels ```a``` and ```b``` like ```path```.
+    /// This is ```None```, if one of the labels is unknown or there is no path between the nodes.
+    pub fn path_by_label<Q>(&self, a: &Q, b: &Q) -> Option<Arc<[usize]>>
+    where
+        K: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.path(self.index_of(a)?, self.index_of(b)?)
+    }
+
+    /// Returns the nodes within the distance ```d``` of the node with the given label like ```within```.
+    /// This is empty, if the label is unknown.
+    pub fn within_by_label<Q>(&self, label: &Q, d: usize) -> Vec<(usize, usize)>
+    where
+        K: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.index_of(label) {
+            Some(i) => self.within(i, d),
+            None => Vec::new(),
+        }
+    }
+
+    fn reconstruct(&self, i: usize, j: usize) -> Arc<[usize]> {
+        self.0.m.path_iter(i, j).collect::<Vec<_>>().into()
+    }
+}
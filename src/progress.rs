@@ -0,0 +1,93 @@
+//! Reporting the progress of a run, for example to a progress bar.
+
+use std::fmt;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+#[cfg(feature = "indicatif")]
+use indicatif::ProgressBar;
+#[cfg(feature = "indicatif")]
+use indicatif::ProgressStyle;
+
+use kernel::Kernel;
+
+/// The callback together with the counters of the current run.
+struct Shared {
+    f: Box<dyn Fn(usize, usize) + Send + Sync>,
+    done: AtomicUsize,
+    total: AtomicUsize,
+}
+
+/// A callback, which is told the progress of a run after every iteration of the dynamic program, and which is passed to the run by ```Options::progress```.
+/// It's called with the number of finished iterations and the number of all iterations, which is the number of nodes.
+/// This is a handle, so a clone of it still refers to the same callback.
+#[derive(Clone)]
+pub struct Progress(Arc<Shared>);
+
+impl Progress {
+    /// Wraps the given callback.
+    pub fn new<F>(f: F) -> Progress
+    where
+        F: Fn(usize, usize) + Send + Sync + 'static,
+    {
+        Progress(Arc::new(Shared {
+            f: Box::new(f),
+            done: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+        }))
+    }
+
+    /// Reports the progress to the given progress bar of ```indicatif```. Its length is set to the number of iterations at the start of the run,
+    /// so it can show the rate of iterations and the remaining time, and it's finished after the last iteration.
+    #[cfg(feature = "indicatif")]
+    pub fn bar(bar: ProgressBar) -> Progress {
+        Progress::new(move |done, total| {
+            bar.set_length(total as u64);
+            bar.set_position(done as u64);
+            if done == total {
+                bar.finish();
+            }
+        })
+    }
+
+    /// Creates a new progress bar of ```indicatif```, which shows the finished iterations and the estimated remaining time, and reports the progress to it like ```bar```.
+    #[cfg(feature = "indicatif")]
+    pub fn default_bar() -> Progress {
+        let style = ProgressStyle::with_template(
+            "{bar:40} {pos}/{len} iterations ({per_sec}, {eta} remaining)",
+        )
+        .unwrap();
+        Progress::bar(ProgressBar::new(0).with_style(style))
+    }
+
+    /// Starts counting the iterations of a run on the loaded ```kernel```, whose first ```done``` iterations are already finished,
+    /// like after resuming from a checkpoint.
+    pub(crate) fn start(&self, kernel: &Kernel, done: usize) {
+        let total = kernel.node_count();
+        self.0.total.store(total, Ordering::Relaxed);
+        self.0.done.store(done, Ordering::Relaxed);
+        (self.0.f)(done, total);
+    }
+
+    /// Counts a finished iteration and calls the callback.
+    pub(crate) fn advance(&self) {
+        let done = self.0.done.fetch_add(1, Ordering::Relaxed) + 1;
+        (self.0.f)(done, self.0.total.load(Ordering::Relaxed));
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Progress")
+    }
+}
+
+impl PartialEq for Progress {
+    fn eq(&self, other: &Progress) -> bool {
+        // Two callbacks are only the same, if they are the same object.
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Progress {}
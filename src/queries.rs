@@ -0,0 +1,417 @@
+//! Convenience queries on a finished ```PathMatrix```.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[cfg(feature = "petgraph")]
+use petgraph::graph::NodeIndex;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::Data;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::EdgeRef;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::GraphBase;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::IntoEdges;
+
+#[cfg(feature = "petgraph")]
+use analysis::path_edges;
+use PathMatrix;
+
+/// A summary of the detour ratios of all pairs, when they are routed over a common waypoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetourSummary {
+    /// The number of pairs, which have a finite detour ratio.
+    pub pairs: usize,
+    /// The mean detour ratio over these pairs.
+    pub mean: f64,
+    /// The largest detour ratio over these pairs.
+    pub max: f64,
+}
+
+/// Summary statistics over the finite distances of all pairs of distinct nodes.
+/// The percentiles use the nearest-rank method, so each of them is one of the distances.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceStats {
+    /// The number of pairs, which are connected by a path.
+    pub pairs: usize,
+    /// The smallest distance.
+    pub min: usize,
+    /// The largest distance.
+    pub max: usize,
+    /// The mean distance.
+    pub mean: f64,
+    /// The 25th percentile.
+    pub p25: usize,
+    /// The median, which is the lower one of the two middle distances for an even number of pairs.
+    pub median: usize,
+    /// The 75th percentile.
+    pub p75: usize,
+    /// The 90th percentile.
+    pub p90: usize,
+    /// The 99th percentile.
+    pub p99: usize,
+}
+
+impl<T> PathMatrix<T> {
+    /// Returns the ```k``` nodes closest to ```i``` together with their distances, ordered by ascending distance.
+    /// Node ```i``` itself and unreachable nodes are never part of the result, so it may contain less than ```k``` entries.
+    /// Ties are broken by the smaller node index.
+    pub fn k_nearest(&self, i: usize, k: usize) -> Vec<(usize, usize)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut v: Vec<(usize, usize)> = (0..self.node_count())
+            .filter(|&j| j != i && self.does_path_exist(i, j))
+            .map(|j| (j, self.get_path_len(i, j)))
+            .collect();
+
+        // Only the k smallest entries have to be sorted, so partition the row around the k-th element first.
+        if v.len() > k {
+            v.select_nth_unstable_by_key(k - 1, |&(j, d)| (d, j));
+            v.truncate(k);
+        }
+
+        v.sort_unstable_by_key(|&(j, d)| (d, j));
+        v
+    }
+
+    /// Returns the node reachable from ```i``` with the largest distance to it, together with that distance.
+    /// If no other node is reachable from ```i```, ```None``` is returned. Ties are broken by the smaller node index.
+    pub fn farthest(&self, i: usize) -> Option<(usize, usize)> {
+        (0..self.node_count())
+            .filter(|&j| j != i && self.does_path_exist(i, j))
+            .map(|j| (j, self.get_path_len(i, j)))
+            .fold(None, |best, (j, d)| match best {
+                Some((_, bd)) if bd >= d => best,
+                _ => Some((j, d)),
+            })
+    }
+
+    /// Returns the node closest to ```i```, which satisfies the predicate ```f```, together with its distance.
+    /// The predicate is called with the index and the weight of a node. Node ```i``` itself is a candidate with distance 0.
+    /// If no reachable node satisfies the predicate, ```None``` is returned. Ties are broken by the smaller node index.
+    ///
+    /// # Panics
+    ///
+    /// The node weights have to be known, so this panics for a matrix with nodes, which wasn't computed from a graph.
+    pub fn nearest_matching<F>(&self, i: usize, mut f: F) -> Option<(usize, usize)>
+    where
+        F: FnMut(usize, &T) -> bool,
+    {
+        let mut best: Option<(usize, usize)> = None;
+        for j in 0..self.node_count() {
+            let d = match self.distance(i, j) {
+                Some(d) => d,
+                None => continue,
+            };
+
+            // The predicate may be expensive, so it is only evaluated for nodes, which would improve the result.
+            if best.is_none_or(|(_, bd)| d < bd) {
+                let w = self.node_weight(j).expect("the node weights are unknown");
+                if f(j, w) {
+                    best = Some((j, d));
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns the distance from ```i``` to the closest node in ```set```.
+    /// This is ```None```, if the set is empty or none of its nodes is reachable from ```i```.
+    pub fn distance_to_set(&self, i: usize, set: &[usize]) -> Option<usize> {
+        set.iter().filter_map(|&j| self.distance(i, j)).min()
+    }
+
+    /// Returns the smallest distance between a node in ```a``` and a node in ```b```.
+    /// This is ```None```, if one of the sets is empty or no node of ```b``` is reachable from ```a```.
+    pub fn distance_between_sets(&self, a: &[usize], b: &[usize]) -> Option<usize> {
+        a.iter().filter_map(|&i| self.distance_to_set(i, b)).min()
+    }
+
+    /// Returns the Hausdorff distance between the node sets ```a``` and ```b```,
+    /// which is the largest distance from a node of one set to the closest node of the other set.
+    /// This is ```None```, if one of the sets is empty or some node can't reach any node of the other set.
+    pub fn hausdorff(&self, a: &[usize], b: &[usize]) -> Option<usize> {
+        let directed = |from: &[usize], to: &[usize]| {
+            from.iter()
+                .map(|&i| self.distance_to_set(i, to))
+                .try_fold(0, |max, d| d.map(|d| max.max(d)))
+        };
+
+        if a.is_empty() || b.is_empty() {
+            return None;
+        }
+        Some(directed(a, b)?.max(directed(b, a)?))
+    }
+
+    /// Returns an iterator over all pairs ```(i, j)``` with ```i < j```, which are connected by a path.
+    /// A directed matrix has a path of its own for every ordered pair, so all of them with ```i != j``` are visited instead.
+    pub(crate) fn connected_pairs<'a>(&'a self) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let n = self.node_count();
+        let directed = self.is_directed();
+        (0..n)
+            .flat_map(move |i| {
+                let first = if directed { 0 } else { i + 1 };
+                (first..n).filter(move |&j| j != i).map(move |j| (i, j))
+            })
+            .filter(move |&(i, j)| self.does_path_exist(i, j))
+    }
+
+    /// Returns an iterator over all pairs ```(i, j)``` with ```i < j``` (or ```i != j``` in a directed matrix), whose distance is exactly ```d```.
+    /// The pairs are visited in the order of the storage, so this doesn't allocate.
+    pub fn pairs_at_distance<'a>(&'a self, d: usize) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.connected_pairs().filter(move |&(i, j)| self.get_path_len(i, j) == d)
+    }
+
+    /// Returns an iterator over all pairs ```(i, j, distance)``` with ```i < j``` (or ```i != j``` in a directed matrix), whose distance is at most ```d```.
+    /// The pairs are grouped by ```i```, but in an undirected matrix a source only lists the nodes with a larger index; use ```reachable_within``` to get all of them.
+    pub fn pairs_within<'a>(&'a self, d: usize) -> impl Iterator<Item = (usize, usize, usize)> + 'a {
+        self.connected_pairs()
+            .map(move |(i, j)| (i, j, self.get_path_len(i, j)))
+            .filter(move |&(_, _, len)| len <= d)
+    }
+
+    /// Returns an iterator over all nodes ```(j, distance)```, which are reachable from ```i``` within the distance ```d```, in ascending order of ```j```.
+    /// Node ```i``` itself is not included.
+    pub fn reachable_within<'a>(&'a self, i: usize, d: usize) -> impl Iterator<Item = (usize, usize)> + 'a {
+        (0..self.node_count())
+            .filter(move |&j| j != i)
+            .filter_map(move |j| self.distance(i, j).map(|len| (j, len)))
+            .filter(move |&(_, len)| len <= d)
+    }
+
+    /// Returns the ```k``` pairs ```(i, j, distance)``` with ```i < j``` (or ```i != j``` in a directed matrix) and the largest finite distances, ordered by descending distance.
+    /// Ties are broken by the smaller pair. Only ```k``` pairs are kept in a heap at any time, so this doesn't sort all pairs.
+    pub fn top_k_pairs_by_distance(&self, k: usize) -> Vec<(usize, usize, usize)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // The top of this heap is the pair, which is the first one to be dropped.
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (i, j) in self.connected_pairs() {
+            let key = Reverse((self.get_path_len(i, j), Reverse((i, j))));
+            if heap.len() < k {
+                heap.push(key);
+            } else if heap.peek().is_some_and(|top| key < *top) {
+                heap.pop();
+                heap.push(key);
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((d, Reverse((i, j))))| (i, j, d))
+            .collect()
+    }
+
+    /// Computes summary statistics over the distances of all pairs ```(i, j)``` with ```i < j``` (or ```i != j``` in a directed matrix), which are connected by a path.
+    /// If there is no such pair, ```None``` is returned.
+    pub fn distance_stats(&self) -> Option<DistanceStats> {
+        let mut d: Vec<usize> = self
+            .connected_pairs()
+            .map(|(i, j)| self.get_path_len(i, j))
+            .collect();
+        if d.is_empty() {
+            return None;
+        }
+        d.sort_unstable();
+
+        let n = d.len();
+        let sum: f64 = d.iter().map(|&x| x as f64).sum();
+        let rank = |p: usize| d[(p * n).div_ceil(100).max(1) - 1];
+
+        Some(DistanceStats {
+            pairs: n,
+            min: d[0],
+            max: d[n - 1],
+            mean: sum / n as f64,
+            p25: rank(25),
+            median: rank(50),
+            p75: rank(75),
+            p90: rank(90),
+            p99: rank(99),
+        })
+    }
+
+    /// Does the stored shortest path between ```i``` and ```j``` have ```k``` as an intermediate node?
+    fn path_uses_node(&self, i: usize, j: usize, k: usize) -> bool {
+        match self.entry(i, j).via() {
+            None => false,
+            Some(v) => v == k || self.path_uses_node(i, v, k) || self.path_uses_node(v, j, k),
+        }
+    }
+
+    /// Returns all pairs ```(i, j)``` with ```i < j``` (or ```i != j``` in a directed matrix), whose stored shortest path has ```k``` as an intermediate node.
+    /// Pairs, where ```k``` is one of the endpoints, are not included.
+    pub fn pairs_through(&self, k: usize) -> Vec<(usize, usize)> {
+        self.connected_pairs()
+            .filter(|&(i, j)| self.path_uses_node(i, j, k))
+            .collect()
+    }
+
+    /// Returns the number of pairs, whose stored shortest path has ```k``` as an intermediate node.
+    /// This is the same as ```pairs_through(k).len()```, but doesn't allocate.
+    pub fn count_pairs_through(&self, k: usize) -> usize {
+        self.connected_pairs()
+            .filter(|&(i, j)| self.path_uses_node(i, j, k))
+            .count()
+    }
+
+    /// Does the stored shortest path between ```i``` and ```j``` use the edge between ```u``` and ```v```?
+    fn path_uses_edge(&self, i: usize, j: usize, u: usize, v: usize) -> bool {
+        match self.entry(i, j).via() {
+            // A path without intermediate node is a single edge of the original graph.
+            None => (i == u && j == v) || (!self.is_directed() && i == v && j == u),
+            Some(k) => self.path_uses_edge(i, k, u, v) || self.path_uses_edge(k, j, u, v),
+        }
+    }
+
+    /// Returns all pairs ```(i, j)``` with ```i < j``` (or ```i != j``` in a directed matrix), whose stored shortest path uses the edge between ```u``` and ```v```.
+    /// If the edge itself is the shortest path between ```u``` and ```v```, the pair ```(u, v)``` is included as well.
+    /// In a directed matrix, only the edge from ```u``` to ```v``` counts.
+    pub fn pairs_using_edge(&self, u: usize, v: usize) -> Vec<(usize, usize)> {
+        self.connected_pairs()
+            .filter(|&(i, j)| self.path_uses_edge(i, j, u, v))
+            .collect()
+    }
+
+    /// Returns the number of pairs, whose stored shortest path uses the edge between ```u``` and ```v```.
+    pub fn count_pairs_using_edge(&self, u: usize, v: usize) -> usize {
+        self.connected_pairs()
+            .filter(|&(i, j)| self.path_uses_edge(i, j, u, v))
+            .count()
+    }
+
+    /// Returns the length of the shortest path from ```i``` to ```j```, which visits the waypoint ```w```.
+    /// This is ```None```, if ```w``` isn't reachable from ```i``` or ```j``` isn't reachable from ```w```.
+    pub fn distance_via(&self, i: usize, w: usize, j: usize) -> Option<usize> {
+        let d1 = self.distance(i, w)?;
+        let d2 = self.distance(w, j)?;
+        Some(d1.saturating_add(d2))
+    }
+
+    /// Returns the length of the shortest path from ```i``` to ```j``` together with all nodes on it, in the order from ```i``` to ```j```.
+    /// Other than ```get_path```, the nodes include both endpoints. If ```i == j```, the path consists of that single node.
+    /// This is ```None```, if there is no path between them.
+    ///
+    /// # Panics
+    ///
+    /// The weights of the nodes have to be known, so this panics for a matrix, which wasn't computed from a graph.
+    pub fn shortest_path(&self, i: usize, j: usize) -> Option<(usize, Vec<&T>)> {
+        let len = self.distance(i, j)?;
+        let v = self
+            .path_iter(i, j)
+            .map(|n| self.node_weight(n).expect("the node weights are unknown"))
+            .collect();
+
+        Some((len, v))
+    }
+
+    /// Returns all nodes on the shortest path from ```i``` to ```j``` including both endpoints, like ```shortest_path``` without the length.
+    /// This tells adjacent nodes, whose path is ```[i, j]```, apart from a missing path, which is ```None```.
+    ///
+    /// # Panics
+    ///
+    /// This panics for a matrix, which wasn't computed from a graph, like ```shortest_path```.
+    pub fn full_path(&self, i: usize, j: usize) -> Option<Vec<&T>> {
+        self.shortest_path(i, j).map(|(_, v)| v)
+    }
+
+    /// Returns the edges of the shortest path from ```i``` to ```j``` in the given graph, in the order from ```i``` to ```j```.
+    /// The graph has to be the one this matrix was computed for. If there are parallel edges, one with the stored length is returned.
+    /// This is ```None```, if there is no path between them.
+    #[cfg(feature = "petgraph")]
+    pub fn get_path_edges<G>(&self, i: usize, j: usize, g: G) -> Option<Vec<G::EdgeId>>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdges,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        self.distance(i, j)?;
+
+        let mut pairs = Vec::new();
+        if i != j {
+            path_edges(self, i, j, &mut pairs);
+        }
+
+        let edges = pairs.into_iter().map(|(u, v)| {
+            let len = self.get_path_len(u, v);
+            g.edges(NodeIndex::new(u))
+                .find(|e| {
+                    let (s, t) = (e.source().index(), e.target().index());
+                    let w: usize = e.weight().clone().into();
+                    ((s, t) == (u, v) || (!self.is_directed() && (s, t) == (v, u))) && w == len
+                })
+                .expect("the graph doesn't belong to this matrix")
+                .id()
+        });
+
+        Some(edges.collect())
+    }
+
+    /// Returns the intermediate nodes of the shortest path from ```i``` to ```j```, which visits the waypoint ```w```, in the order from ```i``` to ```j```.
+    /// The waypoint itself is included, unless it's one of the endpoints.
+    /// This is ```None```, if there is no such path.
+    ///
+    /// # Panics
+    ///
+    /// If the waypoint is an intermediate node, its weight has to be known, so this panics for a matrix, which wasn't computed from a graph.
+    pub fn path_via(&self, i: usize, w: usize, j: usize) -> Option<Vec<&T>>
+    where
+        T: Clone,
+    {
+        self.distance_via(i, w, j)?;
+
+        let mut v: Vec<&T> = self.path_from(i, w).collect();
+        if i != w && w != j {
+            v.push(self.node_weight(w).expect("the node weights are unknown"));
+        }
+        v.extend(self.path_from(w, j));
+
+        Some(v)
+    }
+
+    /// Returns the ratio of the length of the shortest path from ```i``` to ```j``` over the waypoint ```via``` to the length of the shortest path from ```i``` to ```j```.
+    /// This is ```None```, if one of the paths doesn't exist or the shortest path has length 0.
+    pub fn detour_ratio(&self, i: usize, j: usize, via: usize) -> Option<f64> {
+        let direct = self.distance(i, j)?;
+        let detour = self.distance_via(i, via, j)?;
+
+        if direct == 0 {
+            None
+        } else {
+            Some(detour as f64 / direct as f64)
+        }
+    }
+
+    /// Summarizes the detour ratios over the waypoint ```via``` of all pairs, which don't contain ```via``` itself.
+    /// Pairs without a finite detour ratio are skipped. If no pair remains, ```None``` is returned.
+    pub fn detour_summary(&self, via: usize) -> Option<DetourSummary> {
+        let mut pairs = 0;
+        let mut sum = 0.0;
+        let mut max: f64 = 0.0;
+
+        for (i, j) in self.connected_pairs() {
+            if i == via || j == via {
+                continue;
+            }
+            if let Some(r) = self.detour_ratio(i, j, via) {
+                pairs += 1;
+                sum += r;
+                max = max.max(r);
+            }
+        }
+
+        if pairs == 0 {
+            None
+        } else {
+            Some(DetourSummary {
+                pairs,
+                mean: sum / pairs as f64,
+                max,
+            })
+        }
+    }
+}
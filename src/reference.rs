@@ -0,0 +1,34 @@
+//! A slow, but simple reference implementation, which downstream crates can check the results of this crate against in their own tests.
+
+use std::hash::Hash;
+
+use petgraph::algo::dijkstra;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::IntoEdges;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeIndexable;
+use petgraph::visit::Visitable;
+
+/// Computes the distances between every two nodes by running Dijkstra's algorithm of petgraph from every node, without any optimization.
+/// The result is indexed by node index, so ```d[i][j]``` is the distance from node ```i``` to node ```j```, or ```None```, if there is no path.
+/// This also works for directed graphs.
+pub fn apsp_bruteforce<G>(g: G) -> Vec<Vec<Option<usize>>>
+where
+    G: Data + NodeCount + NodeIndexable + IntoNodeIdentifiers + IntoEdges + Visitable,
+    G::NodeId: Eq + Hash,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let mut d = vec![vec![None; n]; n];
+
+    for source in g.node_identifiers() {
+        let row = &mut d[g.to_index(source)];
+        for (target, len) in dijkstra(g, source, None, |e| e.weight().clone().into()) {
+            row[g.to_index(target)] = Some(len);
+        }
+    }
+
+    d
+}
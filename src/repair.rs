@@ -0,0 +1,326 @@
+//! Repairing a computed ```PathMatrix``` after its graph has changed, without recomputing the unaffected pairs.
+
+use std::collections::HashMap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+use petgraph::visit::NodeRef;
+
+use dijkstra::dijkstra;
+use revalidate::edge_weights;
+use PathMatrix;
+
+const INFINITY: usize = usize::MAX;
+
+/// The structural difference between two versions of a graph, whose nodes are identified by their index.
+/// The edges are compared per pair of nodes, where parallel edges count with their smallest weight, and loops are ignored.
+/// All edges are given with the smaller node first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    /// The nodes of the new graph, whose index doesn't exist in the old graph.
+    pub added_nodes: Vec<usize>,
+    /// The nodes of the old graph, whose index doesn't exist in the new graph.
+    pub removed_nodes: Vec<usize>,
+    /// The edges ```(u, v, weight)``` of the new graph, whose nodes aren't connected in the old graph.
+    pub added_edges: Vec<(usize, usize, usize)>,
+    /// The edges ```(u, v, weight)``` of the old graph, whose nodes aren't connected in the new graph.
+    pub removed_edges: Vec<(usize, usize, usize)>,
+    /// The edges ```(u, v, old weight, new weight)```, whose weight has changed.
+    pub changed_edges: Vec<(usize, usize, usize, usize)>,
+}
+
+impl GraphDiff {
+    /// If both graphs have the same nodes and edges, this returns true.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_edges.is_empty()
+    }
+}
+
+/// Maps every unordered pair of distinct nodes, which is connected by an edge, to the smallest weight of such an edge.
+fn pair_weights<G>(g: G) -> HashMap<(usize, usize), usize>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut weights = HashMap::new();
+    for ((u, v), w) in edge_weights(g) {
+        if u != v {
+            let entry = weights.entry((u.min(v), u.max(v))).or_insert(w);
+            *entry = (*entry).min(w);
+        }
+    }
+    weights
+}
+
+/// Computes the structural difference between the old and the new version of a graph.
+pub fn graph_diff<G>(old: G, new: G) -> GraphDiff
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let old_weights = pair_weights(old);
+    let new_weights = pair_weights(new);
+
+    let mut diff = GraphDiff {
+        added_nodes: (old.node_count()..new.node_count()).collect(),
+        removed_nodes: (new.node_count()..old.node_count()).collect(),
+        ..GraphDiff::default()
+    };
+
+    for (&(u, v), &w) in &new_weights {
+        match old_weights.get(&(u, v)) {
+            None => diff.added_edges.push((u, v, w)),
+            Some(&o) if o != w => diff.changed_edges.push((u, v, o, w)),
+            Some(_) => {}
+        }
+    }
+    for (&(u, v), &o) in &old_weights {
+        if !new_weights.contains_key(&(u, v)) {
+            diff.removed_edges.push((u, v, o));
+        }
+    }
+
+    diff.added_edges.sort_unstable();
+    diff.removed_edges.sort_unstable();
+    diff.changed_edges.sort_unstable();
+    diff
+}
+
+/// Builds an adjacency list of ```n``` nodes from the given pair weights.
+fn adjacency(n: usize, weights: &HashMap<(usize, usize), usize>) -> Vec<Vec<(usize, usize)>> {
+    let mut adj = vec![Vec::new(); n];
+    for (&(u, v), &w) in weights {
+        adj[u].push((v, w));
+        adj[v].push((u, w));
+    }
+    adj
+}
+
+/// Chooses one endpoint of every pair as the source, from which the pair is recomputed.
+/// Nodes, which are part of many pairs, are preferred, so few sources cover all pairs.
+fn cover(n: usize, pairs: &[(usize, usize)]) -> Vec<bool> {
+    let mut count = vec![0; n];
+    for &(i, j) in pairs {
+        count[i] += 1;
+        count[j] += 1;
+    }
+
+    let mut chosen = vec![false; n];
+    for &(i, j) in pairs {
+        if !chosen[i] && !chosen[j] {
+            if count[i] >= count[j] {
+                chosen[i] = true;
+            } else {
+                chosen[j] = true;
+            }
+        }
+    }
+    chosen
+}
+
+impl<T: Clone> PathMatrix<T> {
+    /// Updates this matrix, which was computed for the graph ```old```, to the graph ```new```, and returns the pairs ```(i, j)``` with ```i < j```,
+    /// whose distance or stored path has changed. Pairs with an added node are returned, if they are connected.
+    /// The nodes of both graphs are identified by their index, see ```graph_diff```.
+    ///
+    /// Only the pairs, whose stored path runs over a removed or more expensive edge, are recomputed by Dijkstra's algorithm
+    /// from a few of their endpoints. Afterwards, every added or cheaper edge is inserted in quadratic time.
+    /// If only a small part of the graph has changed, this is much faster than running the algorithm again.
+    /// If the new graph has an edge of weight 0, every pair is recomputed that way, which still skips the cubic dynamic program.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the old graph has a different number of nodes than the matrix, or if the new graph is directed.
+    pub fn repair<G>(&mut self, old: G, new: G) -> Vec<(usize, usize)>
+    where
+        G: Data<NodeWeight = T>
+            + GraphBase<NodeId = NodeIndex>
+            + NodeCount
+            + IntoNodeReferences
+            + IntoEdgeReferences
+            + GraphProp,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        assert_eq!(
+            self.node_count(),
+            old.node_count(),
+            "the old graph doesn't belong to the matrix"
+        );
+        assert!(
+            !new.is_directed(),
+            "repairing a directed graph isn't supported"
+        );
+
+        let n = new.node_count();
+        let common = n.min(self.node_count());
+        let old_weights = pair_weights(old);
+        let new_weights = pair_weights(new);
+
+        // The intermediate graph only contains the edges of both graphs, each with the larger of its weights,
+        // so it's reached from the old graph by only removing edges and making them more expensive.
+        let mid_weights: HashMap<(usize, usize), usize> = new_weights
+            .iter()
+            .filter_map(|(&p, &w)| old_weights.get(&p).map(|&o| (p, o.max(w))))
+            .collect();
+        let mut inserted: Vec<(usize, usize, usize)> = new_weights
+            .iter()
+            .filter(|&(p, &w)| old_weights.get(p).is_none_or(|&o| w < o))
+            .map(|(&(u, v), &w)| (u, v, w))
+            .collect();
+        inserted.sort_unstable();
+
+        // Every stored path, which still has its length in the intermediate graph, is still a shortest path in it.
+        let mut memo = vec![None; self.m_len()];
+        let mut dist = vec![INFINITY; n * n];
+        let mut stale = Vec::new();
+        for i in 0..n {
+            dist[i * n + i] = 0;
+        }
+        for i in 0..common {
+            for j in i + 1..common {
+                if !self.does_path_exist(i, j) {
+                    continue;
+                }
+
+                let len = self.get_path_len(i, j);
+                if self.current_weight(&mid_weights, &mut memo, i, j) == Some(len) {
+                    dist[i * n + j] = len;
+                    dist[j * n + i] = len;
+                } else {
+                    stale.push((i, j));
+                }
+            }
+        }
+
+        let mut row = vec![0; n];
+        let mut hops = vec![0; n];
+        let mut pred = vec![0; n];
+
+        let mid = adjacency(n, &mid_weights);
+        for (s, _) in cover(n, &stale).into_iter().enumerate().filter(|&(_, c)| c) {
+            dijkstra(&mid, s, &mut row, &mut hops, &mut pred);
+            for (t, &d) in row.iter().enumerate() {
+                dist[s * n + t] = d;
+                dist[t * n + s] = d;
+            }
+        }
+
+        // Inserting an edge (u, v) shortens a path, if it runs over the edge afterwards.
+        for &(u, v, w) in &inserted {
+            let du = dist[u * n..(u + 1) * n].to_vec();
+            let dv = dist[v * n..(v + 1) * n].to_vec();
+            for i in 0..n {
+                for j in 0..n {
+                    let over_uv = du[i].saturating_add(w).saturating_add(dv[j]);
+                    let over_vu = dv[i].saturating_add(w).saturating_add(du[j]);
+                    let d = &mut dist[i * n + j];
+                    *d = (*d).min(over_uv).min(over_vu);
+                }
+            }
+        }
+
+        let mut changed = stale;
+        for i in 0..n {
+            for j in i + 1..n {
+                let d = dist[i * n + j];
+                let before = if j < common && self.does_path_exist(i, j) {
+                    self.get_path_len(i, j)
+                } else {
+                    INFINITY
+                };
+                if d != before {
+                    changed.push((i, j));
+                }
+            }
+        }
+        changed.sort_unstable();
+        changed.dedup();
+
+        let mut m = PathMatrix::new(n);
+        m.set_node_weights(new.node_references().map(|n| n.weight().clone()));
+        if n > 0 {
+            m.set_path_len(0, 0, 0);
+        }
+
+        // Over edges of weight 0, paths of the same length can run through each other, so a new path can't be combined with the old ones,
+        // or reconstructing a path could run in circles. Then every pair is recomputed.
+        let rebuild = new_weights.values().any(|&w| w == 0);
+
+        // The unchanged pairs keep their paths, whose parts are unchanged pairs as well.
+        let mut is_changed = vec![false; m.m_len()];
+        for &(i, j) in &changed {
+            is_changed[m.idx(i, j)] = true;
+        }
+        for i in 0..common {
+            for j in i + 1..common {
+                if !rebuild && !is_changed[m.idx(i, j)] && self.does_path_exist(i, j) {
+                    m.set_path_len(i, j, self.get_path_len(i, j));
+                    if let Some(k) = self.entry(i, j).via() {
+                        m.get_path_mut(i, j).set_via(k);
+                    }
+                }
+            }
+        }
+
+        // A changed pair is split at the last intermediate node of a shortest path with the fewest edges.
+        // Both parts have fewer edges, so the splits always end at single edges.
+        let new_adj = adjacency(n, &new_weights);
+        let recomputed: Vec<(usize, usize)> = if rebuild {
+            (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .filter(|&(i, j)| dist[i * n + j] != INFINITY)
+                .collect()
+        } else {
+            changed
+                .iter()
+                .cloned()
+                .filter(|&(i, j)| dist[i * n + j] != INFINITY)
+                .collect()
+        };
+        let sources = cover(n, &recomputed);
+        let mut by_source = vec![Vec::new(); n];
+        for &(i, j) in &recomputed {
+            if sources[i] {
+                by_source[i].push(j);
+            } else {
+                by_source[j].push(i);
+            }
+        }
+
+        for (s, targets) in by_source.iter().enumerate().filter(|(_, t)| !t.is_empty()) {
+            dijkstra(&new_adj, s, &mut row, &mut hops, &mut pred);
+            for &t in targets {
+                debug_assert_eq!(row[t], dist[s * n + t]);
+                m.set_path_len(s, t, row[t]);
+                if hops[t] > 1 {
+                    m.get_path_mut(s, t).set_via(pred[t]);
+                }
+            }
+        }
+
+        // A recomputed pair, whose distance hasn't changed, may still have a new path.
+        if rebuild {
+            for i in 0..common {
+                for j in i + 1..common {
+                    if m.does_path_exist(i, j) && self.entry(i, j).via() != m.entry(i, j).via() {
+                        changed.push((i, j));
+                    }
+                }
+            }
+            changed.sort_unstable();
+            changed.dedup();
+        }
+
+        *self = m;
+        changed
+    }
+}
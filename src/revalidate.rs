@@ -0,0 +1,141 @@
+//! Checks of a computed ```PathMatrix``` against a graph, which may have changed after the computation.
+
+use std::collections::HashMap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoEdges;
+
+use Path;
+use PathMatrix;
+
+/// Maps every ordered pair of nodes, which is connected by an edge from the first to the second node, to the smallest weight of such an edge.
+/// An edge of an undirected graph is only stored in the order of its endpoints in the graph, see ```edge_weight```.
+pub(crate) fn edge_weights<G>(g: G) -> HashMap<(usize, usize), usize>
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + IntoEdgeReferences,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let mut weights = HashMap::new();
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+
+        let entry = weights.entry((u, v)).or_insert(w);
+        *entry = (*entry).min(w);
+    }
+    weights
+}
+
+impl<T> PathMatrix<T> {
+    /// Returns the smallest weight of an edge from ```u``` to ```v``` in the map of ```edge_weights```.
+    /// The edges of an undirected graph are looked up in both orders.
+    pub(crate) fn edge_weight(
+        &self,
+        weights: &HashMap<(usize, usize), usize>,
+        u: usize,
+        v: usize,
+    ) -> Option<usize> {
+        let forward = weights.get(&(u, v)).cloned();
+        if self.is_directed() {
+            return forward;
+        }
+        match (forward, weights.get(&(v, u)).cloned()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the total weight of the stored path between ```i``` and ```j``` in the graph given by ```weights```,
+    /// or ```None```, if one of its edges doesn't exist anymore. Already computed totals are kept in ```memo```.
+    pub(crate) fn current_weight(
+        &self,
+        weights: &HashMap<(usize, usize), usize>,
+        memo: &mut [Option<Option<usize>>],
+        i: usize,
+        j: usize,
+    ) -> Option<usize> {
+        let idx = self.idx(i, j);
+        if let Some(w) = memo[idx] {
+            return w;
+        }
+
+        let w = match self.entry(i, j).via() {
+            None => self.edge_weight(weights, i, j),
+            Some(k) => {
+                let a = self.current_weight(weights, memo, i, k);
+                let b = self.current_weight(weights, memo, k, j);
+                a.and_then(|a| b.map(|b| a.saturating_add(b)))
+            }
+        };
+
+        memo[idx] = Some(w);
+        w
+    }
+
+    /// Recomputes the total weight of every stored path in the given graph, and returns all pairs ```(i, j)``` with ```i < j``` (or ```i != j``` in a directed matrix),
+    /// whose path uses an edge, which doesn't exist anymore, or whose weight doesn't equal the stored length anymore.
+    /// Only the stored paths are checked, so a new shorter path in the graph doesn't make a pair stale.
+    pub fn revalidate<G>(&self, g: G) -> Vec<(usize, usize)>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        let weights = edge_weights(g);
+        let mut memo = vec![None; self.m_len()];
+
+        let pairs: Vec<(usize, usize)> = self.connected_pairs().collect();
+        pairs
+            .into_iter()
+            .filter(|&(i, j)| {
+                self.current_weight(&weights, &mut memo, i, j) != Some(self.get_path_len(i, j))
+            })
+            .collect()
+    }
+}
+
+impl<T> Path<T> {
+    /// Recomputes the total weight of this path in the given graph, where this is the stored path of ```m``` between ```i``` and ```j```,
+    /// and checks, whether all of its edges still exist and the weight still equals the stored length.
+    /// Only the edges of this path are looked up in the graph, so unlike ```PathMatrix::revalidate```, this doesn't visit the whole graph or matrix.
+    /// A missing path and ```i == j``` are always valid.
+    pub fn validate<G>(&self, m: &PathMatrix<T>, i: usize, j: usize, g: G) -> bool
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdges,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        if i == j || !self.exists() {
+            return true;
+        }
+        self.current_weight_in(m, i, j, g) == Some(self.len())
+    }
+
+    /// Returns the total weight of this path between ```i``` and ```j``` in the given graph,
+    /// or ```None```, if one of its edges doesn't exist anymore.
+    fn current_weight_in<G>(&self, m: &PathMatrix<T>, i: usize, j: usize, g: G) -> Option<usize>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + IntoEdges,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        match self.via() {
+            // Of parallel edges, the lightest one is the one the path takes.
+            None => g
+                .edges(NodeIndex::new(i))
+                .filter(|e| {
+                    let (s, t) = (e.source().index(), e.target().index());
+                    (s, t) == (i, j) || (!m.is_directed() && (s, t) == (j, i))
+                })
+                .map(|e| e.weight().clone().into())
+                .min(),
+            Some(k) => {
+                let a = m.entry(i, k).current_weight_in(m, i, k, g)?;
+                let b = m.entry(k, j).current_weight_in(m, k, j, g)?;
+                Some(a.saturating_add(b))
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Forwarding tables, which tell every node over which neighbour it reaches every other node.
+
+#[cfg(feature = "export")]
+use std::io;
+#[cfg(feature = "export")]
+use std::io::Write;
+
+#[cfg(feature = "petgraph")]
+use petgraph::graph::NodeIndex;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::Data;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::EdgeRef;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::GraphBase;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::GraphProp;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::IntoEdgeReferences;
+#[cfg(feature = "petgraph")]
+use petgraph::visit::NodeCount;
+
+use DistanceMatrix;
+#[cfg(feature = "petgraph")]
+use Distances;
+use PathMatrix;
+
+const INFINITY: usize = DistanceMatrix::INFINITY;
+
+/// The stored next hop of a pair, which has no path.
+const NO_HOP: usize = usize::MAX;
+
+/// An entry of the forwarding table of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Route {
+    /// The node, which is reached by this route.
+    pub destination: usize,
+    /// The neighbour, to which the node forwards everything for ```destination```.
+    pub next_hop: usize,
+    /// The length of the shortest path to ```destination```.
+    pub distance: usize,
+}
+
+/// The forwarding tables of all nodes, as returned by ```PathMatrix::routing_tables```.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoutingTables {
+    tables: Vec<Vec<Route>>,
+}
+
+impl RoutingTables {
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Returns the forwarding table of node ```i```, ordered by destination.
+    /// It has an entry for every other node, which ```i``` can reach.
+    /// The tables of ```PathMatrix::routing_tables_ecmp``` have an entry for every equal-cost next hop instead, ordered by the next hop.
+    pub fn table(&self, i: usize) -> &[Route] {
+        &self.tables[i]
+    }
+
+    /// Writes all tables as CSV with the header ```node,destination,next_hop,distance```.
+    #[cfg(feature = "export")]
+    pub fn write_csv<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "node,destination,next_hop,distance")?;
+        for (i, table) in self.tables.iter().enumerate() {
+            for r in table {
+                writeln!(w, "{},{},{},{}", i, r.destination, r.next_hop, r.distance)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes all tables as a JSON array, which holds an object ```{"node":i,"routes":[...]}``` for every node.
+    /// Each route is an object ```{"destination":j,"next_hop":h,"distance":d}```.
+    #[cfg(feature = "export")]
+    pub fn write_json<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(b"[")?;
+        for (i, table) in self.tables.iter().enumerate() {
+            if i > 0 {
+                w.write_all(b",")?;
+            }
+            write!(w, r#"{{"node":{},"routes":["#, i)?;
+            for (n, r) in table.iter().enumerate() {
+                if n > 0 {
+                    w.write_all(b",")?;
+                }
+                write!(
+                    w,
+                    r#"{{"destination":{},"next_hop":{},"distance":{}}}"#,
+                    r.destination, r.next_hop, r.distance
+                )?;
+            }
+            w.write_all(b"]}")?;
+        }
+        w.write_all(b"]")
+    }
+}
+
+impl<T> PathMatrix<T> {
+    /// Returns the first node after ```i``` on the shortest path from ```i``` to ```j```,
+    /// or ```None```, if ```i == j``` or there is no path between them.
+    /// This follows the via table, so no path has to be reconstructed.
+    pub fn next_hop(&self, i: usize, mut j: usize) -> Option<usize> {
+        if i == j || !self.does_path_exist(i, j) {
+            return None;
+        }
+
+        // The path from i to j starts with the path from i to its intermediate node, so the first hop is found by shrinking the path.
+        while let Some(k) = self.via(i, j) {
+            j = k;
+        }
+        Some(j)
+    }
+
+    /// Returns the nodes on the shortest path from ```i``` to ```j``` one by one, starting with ```i``` and ending with ```j```.
+    /// They are found lazily by walking the intermediate nodes, so every node is visited once and, other than ```get_path_iter```, no path is reconstructed or cached.
+    /// If there is no path between them, this is empty.
+    pub fn path_iter<'a>(&'a self, i: usize, j: usize) -> impl Iterator<Item = usize> + 'a {
+        let mut first = if i == j || self.does_path_exist(i, j) {
+            Some(i)
+        } else {
+            None
+        };
+        // The parts of the path, whose end still has to be visited. A part over an intermediate node is split, until it's a single edge.
+        let mut parts = if first.is_some() && i != j {
+            vec![(i, j)]
+        } else {
+            Vec::new()
+        };
+
+        ::std::iter::from_fn(move || {
+            if let Some(x) = first.take() {
+                return Some(x);
+            }
+
+            let (a, mut b) = parts.pop()?;
+            while let Some(k) = self.via(a, b) {
+                parts.push((k, b));
+                b = k;
+            }
+            Some(b)
+        })
+    }
+
+    /// Returns the neighbours of ```i```, whose edge to ```i``` is a shortest path, ordered by their index.
+    /// Only these can be the first hop of a shortest path from ```i```.
+    pub(crate) fn shortest_edges(&self, i: usize) -> Vec<usize> {
+        (0..self.node_count())
+            .filter(|&h| h != i && self.does_path_exist(i, h) && self.via(i, h).is_none())
+            .collect()
+    }
+
+    /// Returns every first node after ```i``` on some shortest path from ```i``` to ```j```, ordered by their index.
+    /// Other than ```next_hop```, this includes all equal-cost alternatives, so it's empty, if ```i == j``` or there is no path between them.
+    pub fn next_hops(&self, i: usize, j: usize) -> Vec<usize> {
+        self.equal_cost_hops(&self.shortest_edges(i), i, j)
+    }
+
+    /// Returns the nodes of ```neighbours```, over which a shortest path from ```i``` to ```j``` starts.
+    fn equal_cost_hops(&self, neighbours: &[usize], i: usize, j: usize) -> Vec<usize> {
+        if i == j || !self.does_path_exist(i, j) {
+            return Vec::new();
+        }
+
+        let d = self.get_path_len(i, j);
+        neighbours
+            .iter()
+            .cloned()
+            .filter(|&h| match self.distance(h, j) {
+                Some(rest) => self.get_path_len(i, h).checked_add(rest) == Some(d),
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Builds the forwarding table of every node, with one entry per reachable destination.
+    pub fn routing_tables(&self) -> RoutingTables {
+        let n = self.node_count();
+        let tables = (0..n)
+            .map(|i| {
+                (0..n)
+                    .filter_map(|j| {
+                        self.next_hop(i, j).map(|next_hop| Route {
+                            destination: j,
+                            next_hop,
+                            distance: self.get_path_len(i, j),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        RoutingTables { tables }
+    }
+
+    /// Builds the forwarding table of every node for equal-cost multi-path routing.
+    /// Every reachable destination has one entry per neighbour, over which a shortest path to it starts.
+    pub fn routing_tables_ecmp(&self) -> RoutingTables {
+        let n = self.node_count();
+        let tables = (0..n)
+            .map(|i| {
+                let neighbours = self.shortest_edges(i);
+                let mut table = Vec::new();
+                for j in 0..n {
+                    for next_hop in self.equal_cost_hops(&neighbours, i, j) {
+                        table.push(Route {
+                            destination: j,
+                            next_hop,
+                            distance: self.get_path_len(i, j),
+                        });
+                    }
+                }
+                table
+            })
+            .collect();
+
+        RoutingTables { tables }
+    }
+}
+
+/// This matrix is a solution to the APSP problem, which contains the length and the first hop of the shortest path for every pair of nodes, but not the paths.
+/// It's computed by ```floyd_warshall_next_hops``` and needs ```16 * V * V``` bytes. The whole path is found by following the next hops towards the destination.
+#[cfg(feature = "petgraph")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NextHopMatrix {
+    dist: DistanceMatrix,
+    next: Box<[usize]>,
+}
+
+#[cfg(feature = "petgraph")]
+impl NextHopMatrix {
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.dist.node_count()
+    }
+
+    /// Returns the first node after ```i``` on the shortest path from ```i``` to ```j```,
+    /// or ```None```, if ```i == j``` or there is no path between them.
+    #[inline]
+    pub fn next_hop(&self, i: usize, j: usize) -> Option<NodeIndex> {
+        match self.next[i * self.node_count() + j] {
+            NO_HOP => None,
+            h => Some(NodeIndex::new(h)),
+        }
+    }
+
+    /// Returns the length of the shortest path from ```i``` to ```j```, or ```None```, if there is no path between them.
+    #[inline]
+    pub fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.dist.get(i, j)
+    }
+
+    /// Builds the forwarding table of every node, with one entry per reachable destination, like ```PathMatrix::routing_tables```.
+    pub fn routing_tables(&self) -> RoutingTables {
+        let n = self.node_count();
+        let tables = (0..n)
+            .map(|i| {
+                (0..n)
+                    .filter_map(|j| {
+                        self.next_hop(i, j).map(|h| Route {
+                            destination: j,
+                            next_hop: h.index(),
+                            distance: self.dist.get(i, j).expect("a next hop without a path"),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        RoutingTables { tables }
+    }
+}
+
+#[cfg(feature = "petgraph")]
+impl Distances for NextHopMatrix {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.dist.node_count()
+    }
+
+    #[inline]
+    fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.dist.get(i, j)
+    }
+}
+
+/// This function computes the distance and the first hop of the shortest path between every two nodes in the graph, but doesn't store the paths.
+/// This is enough for routing: Every node only has to know the neighbour, to which it forwards everything for a destination.
+/// The node weights aren't needed.
+#[cfg(feature = "petgraph")]
+pub fn floyd_warshall_next_hops<G>(g: G) -> NextHopMatrix
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    let n = g.node_count();
+    let mut d = vec![INFINITY; n * n];
+    let mut next = vec![NO_HOP; n * n];
+    for i in 0..n {
+        d[i * n + i] = 0;
+    }
+
+    for e in g.edge_references() {
+        let u = e.source().index();
+        let v = e.target().index();
+        let w: usize = e.weight().clone().into();
+        if u != v {
+            d[u * n + v] = w;
+            next[u * n + v] = v;
+            if !g.is_directed() {
+                d[v * n + u] = w;
+                next[v * n + u] = u;
+            }
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let to_k = d[i * n + k];
+            if to_k == INFINITY {
+                continue;
+            }
+
+            for j in 0..n {
+                // If the path from k is missing, this saturates to INFINITY, so it never wins.
+                let over_k = to_k.saturating_add(d[k * n + j]);
+                if over_k < d[i * n + j] {
+                    d[i * n + j] = over_k;
+                    // The path over k starts like the path to k.
+                    next[i * n + j] = next[i * n + k];
+                }
+            }
+        }
+    }
+
+    NextHopMatrix {
+        dist: DistanceMatrix::from_vec(n, d),
+        next: next.into(),
+    }
+}
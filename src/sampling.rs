@@ -0,0 +1,107 @@
+//! Estimates of global statistics, which only look at a random sample of the distances.
+
+use rng::SplitMix64;
+use Distances;
+
+/// The quantile of the standard normal distribution, which belongs to a two-sided confidence level of 95%.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// An estimate of a statistic together with an interval, which contains the exact value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Estimate {
+    /// The estimated value.
+    pub value: f64,
+    /// The lower end of the interval.
+    pub lower: f64,
+    /// The upper end of the interval. This may be infinite, if the sample doesn't bound the value.
+    pub upper: f64,
+    /// The number of samples, which the estimate is based on.
+    pub samples: usize,
+}
+
+/// Estimates the mean distance over all connected pairs of distinct nodes from ```samples``` random pairs.
+/// Pairs without a path are drawn, but don't count as samples. The interval is the 95% confidence interval
+/// of the normal approximation, so it contains the exact mean only with that probability.
+/// The result is reproducible for the same ```seed```. If no connected pair is drawn, ```None``` is returned.
+pub fn estimate_mean_distance<D: Distances>(d: &D, samples: usize, seed: u64) -> Option<Estimate> {
+    let n = d.node_count();
+    if n < 2 {
+        return None;
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    let mut values = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        // Draw j from the other n - 1 nodes, so the pair never degenerates to a single node.
+        let i = rng.below(n);
+        let mut j = rng.below(n - 1);
+        if j >= i {
+            j += 1;
+        }
+
+        if let Some(len) = d.distance(i, j) {
+            values.push(len as f64);
+        }
+    }
+
+    if values.is_empty() {
+        return None;
+    }
+
+    let k = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / k;
+    let margin = if values.len() > 1 {
+        let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (k - 1.0);
+        Z_95 * (variance / k).sqrt()
+    } else {
+        f64::INFINITY
+    };
+
+    Some(Estimate {
+        value: mean,
+        lower: (mean - margin).max(0.0),
+        upper: mean + margin,
+        samples: values.len(),
+    })
+}
+
+/// Estimates the diameter, which is the largest finite distance between two nodes, from the eccentricities of ```sources``` random nodes.
+/// This reads one row of the matrix per source. The estimate is the largest eccentricity found, which is a certain lower bound.
+/// If a sampled source reaches all nodes, twice its eccentricity is a certain upper bound by the triangle inequality,
+/// otherwise the upper bound is infinite. The result is reproducible for the same ```seed```.
+/// If the graph has no nodes or no source is drawn, ```None``` is returned.
+pub fn estimate_diameter<D: Distances>(d: &D, sources: usize, seed: u64) -> Option<Estimate> {
+    let n = d.node_count();
+    if n == 0 || sources == 0 {
+        return None;
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    let mut lower = 0;
+    let mut upper = None;
+    for _ in 0..sources {
+        let i = rng.below(n);
+
+        let mut eccentricity = 0;
+        let mut reaches_all = true;
+        for j in 0..n {
+            match d.distance(i, j) {
+                Some(len) => eccentricity = eccentricity.max(len),
+                None => reaches_all = false,
+            }
+        }
+
+        lower = lower.max(eccentricity);
+        if reaches_all {
+            let bound = eccentricity.saturating_mul(2);
+            upper = Some(upper.map_or(bound, |upper: usize| upper.min(bound)));
+        }
+    }
+
+    Some(Estimate {
+        value: lower as f64,
+        lower: lower as f64,
+        upper: upper.map_or(f64::INFINITY, |upper| upper as f64),
+        samples: sources,
+    })
+}
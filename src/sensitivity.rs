@@ -0,0 +1,226 @@
+//! Precomputed structures, which answer distance queries in a graph, where a single element has failed.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use analysis::intermediate_nodes;
+use analysis::path_edges;
+use analysis::Adjacency;
+use PathMatrix;
+
+/// A list of (failed element, distance avoiding that element) entries, sorted by the element.
+type Detours = Vec<(usize, Option<usize>)>;
+
+/// For every pair of nodes, this contains the distances avoiding each failed element on the stored path of that pair.
+/// Elements are either node indices or edge positions, depending on how the table was built.
+pub(crate) struct DetourTable {
+    detours: Box<[Detours]>,
+}
+
+impl DetourTable {
+    /// Builds the table. ```elements``` appends the failable elements on the stored path between two nodes to the given vector,
+    /// and ```nodes``` tells, whether these elements are nodes (or edges otherwise).
+    pub(crate) fn build<T, F>(
+        m: &PathMatrix<T>,
+        adj: &Adjacency,
+        nodes: bool,
+        mut elements: F,
+    ) -> DetourTable
+    where
+        F: FnMut(usize, usize, &mut Vec<usize>),
+    {
+        let n = m.node_count();
+        let mut detours = vec![Vec::new(); 1 + n * n.saturating_sub(1) / 2];
+        let mut on_path = Vec::new();
+        let mut dist = vec![None; n];
+
+        for i in 0..n {
+            // Collect, which targets are affected by the failure of which element.
+            let mut affected: HashMap<usize, Vec<usize>> = HashMap::new();
+            for j in i + 1..n {
+                if !m.does_path_exist(i, j) {
+                    continue;
+                }
+
+                on_path.clear();
+                elements(i, j, &mut on_path);
+                for &e in &on_path {
+                    affected.entry(e).or_default().push(j);
+                }
+            }
+
+            // A single search per failed element recomputes all affected targets at once.
+            for (e, targets) in affected {
+                let skip_node = if nodes { Some(e) } else { None };
+                let skip_edge = if nodes { None } else { Some(e) };
+
+                adj.detours(m, i, skip_node, skip_edge, &mut dist);
+
+                for j in targets {
+                    detours[m.idx(i, j)].push((e, dist[j]));
+                }
+            }
+        }
+
+        for d in detours.iter_mut() {
+            d.sort_unstable_by_key(|&(e, _)| e);
+            d.shrink_to_fit();
+        }
+
+        DetourTable {
+            detours: detours.into(),
+        }
+    }
+
+    /// Returns the distance between ```i``` and ```j``` avoiding the element ```e```,
+    /// or ```None```, if ```e``` isn't on the stored path between them and the distance therefore doesn't change.
+    pub(crate) fn get<T>(
+        &self,
+        m: &PathMatrix<T>,
+        i: usize,
+        j: usize,
+        e: usize,
+    ) -> Option<Option<usize>> {
+        let d = &self.detours[m.idx(i, j)];
+        d.binary_search_by_key(&e, |&(e, _)| e).ok().map(|p| d[p].1)
+    }
+}
+
+/// This structure answers, how long the shortest path between two nodes is, if a single edge of the graph has failed.
+/// All replacement distances are precomputed, so that a query doesn't need to search the graph again.
+pub struct ReplacementPaths<'a, T: 'a, E> {
+    m: &'a PathMatrix<T>,
+    edges: HashMap<E, usize>,
+    table: DetourTable,
+}
+
+impl<'a, T, E> ReplacementPaths<'a, T, E>
+where
+    E: Copy + Eq + Hash,
+{
+    /// Precomputes the replacement distances for the given graph and its computed ```PathMatrix```.
+    /// This runs one single-source search per source node and edge on one of its stored paths.
+    ///
+    /// # Panics
+    ///
+    /// The searches treat every edge as undirected, so this panics for the matrix of a directed graph.
+    pub fn new<G>(g: G, m: &'a PathMatrix<T>) -> ReplacementPaths<'a, T, E>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex, EdgeId = E> + NodeCount + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        assert!(
+            !m.is_directed(),
+            "replacement paths of a directed graph aren't supported"
+        );
+        let adj = Adjacency::new(g);
+        let stored = adj.stored_edges(m);
+
+        let mut path = Vec::new();
+        let table = DetourTable::build(m, &adj, false, |i, j, out| {
+            path.clear();
+            path_edges(m, i, j, &mut path);
+            out.extend(path.iter().filter_map(|e| stored.get(e)));
+        });
+
+        let edges = g
+            .edge_references()
+            .enumerate()
+            .map(|(pos, e)| (e.id(), pos))
+            .collect();
+
+        ReplacementPaths { m, edges, table }
+    }
+
+    /// Returns the length of the shortest path between ```i``` and ```j```, which doesn't use ```failed_edge```.
+    /// If there is no such path, ```None``` is returned.
+    pub fn replacement_distance(&self, i: usize, j: usize, failed_edge: E) -> Option<usize> {
+        if i == j {
+            return Some(0);
+        }
+        if !self.m.does_path_exist(i, j) {
+            return None;
+        }
+
+        let detour = self
+            .edges
+            .get(&failed_edge)
+            .and_then(|&pos| self.table.get(self.m, i, j, pos));
+
+        match detour {
+            Some(d) => d,
+            None => Some(self.m.get_path_len(i, j)),
+        }
+    }
+}
+
+/// A distance oracle for a graph, where a single node or a single edge may have failed.
+///
+/// For every pair and every element on its stored shortest path, the distance avoiding that element is precomputed.
+/// The failure of any other element doesn't change the distance, so a query is a binary search over the elements of a single path.
+/// The memory usage is proportional to the total length of all stored paths.
+pub struct SensitivityOracle<'a, T: 'a, E> {
+    edges: ReplacementPaths<'a, T, E>,
+    nodes: DetourTable,
+}
+
+impl<'a, T, E> SensitivityOracle<'a, T, E>
+where
+    E: Copy + Eq + Hash,
+{
+    /// Precomputes the oracle for the given graph and its computed ```PathMatrix```.
+    ///
+    /// # Panics
+    ///
+    /// Like ```ReplacementPaths::new```, this panics for the matrix of a directed graph.
+    pub fn new<G>(g: G, m: &'a PathMatrix<T>) -> SensitivityOracle<'a, T, E>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex, EdgeId = E> + NodeCount + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        assert!(
+            !m.is_directed(),
+            "a sensitivity oracle of a directed graph isn't supported"
+        );
+        let adj = Adjacency::new(g);
+        let nodes = DetourTable::build(m, &adj, true, |i, j, out| intermediate_nodes(m, i, j, out));
+        let edges = ReplacementPaths::new(g, m);
+
+        SensitivityOracle { edges, nodes }
+    }
+
+    /// Returns the length of the shortest path between ```i``` and ```j```, which doesn't use ```failed_edge```.
+    /// If there is no such path, ```None``` is returned.
+    #[inline]
+    pub fn distance_avoiding_edge(&self, i: usize, j: usize, failed_edge: E) -> Option<usize> {
+        self.edges.replacement_distance(i, j, failed_edge)
+    }
+
+    /// Returns the length of the shortest path between ```i``` and ```j```, which doesn't visit ```failed_node```.
+    /// If there is no such path (which includes the case that ```failed_node``` is ```i``` or ```j```), ```None``` is returned.
+    pub fn distance_avoiding_node(&self, i: usize, j: usize, failed_node: usize) -> Option<usize> {
+        let m = self.edges.m;
+
+        if i == failed_node || j == failed_node {
+            return None;
+        }
+        if i == j {
+            return Some(0);
+        }
+        if !m.does_path_exist(i, j) {
+            return None;
+        }
+
+        match self.nodes.get(m, i, j, failed_node) {
+            Some(d) => d,
+            None => Some(m.get_path_len(i, j)),
+        }
+    }
+}
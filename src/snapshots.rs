@@ -0,0 +1,121 @@
+//! Snapshots of the distances after every iteration of the dynamic program, for example to animate a run step by step.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use kernel::Kernel;
+
+/// An iteration of the dynamic program over the intermediate node ```k```, together with the pairs ```(i, j, distance)```, which it made shorter.
+#[derive(Clone, Debug, Default)]
+struct Step {
+    k: usize,
+    changes: Vec<(usize, usize, usize)>,
+}
+
+/// The recorded run. Only the initial distances are stored in full, every iteration only stores the pairs it changed.
+#[derive(Clone, Debug, Default)]
+struct Log {
+    n: usize,
+    directed: bool,
+    initial: Vec<usize>,
+    steps: Vec<Step>,
+}
+
+/// A recorder for the distances after every iteration of a run, which is passed to the run by ```Options::snapshots```.
+/// This is a handle, so a clone of it still refers to the same recording, which can be read after the run.
+///
+/// The snapshots are delta-encoded: Besides the initial distances, only the changes of every iteration are stored,
+/// and a snapshot is rebuilt from them on request.
+#[derive(Clone, Default)]
+pub struct Snapshots(Arc<Mutex<Log>>);
+
+impl Snapshots {
+    /// Creates an empty recorder.
+    pub fn new() -> Snapshots {
+        Snapshots::default()
+    }
+
+    fn log(&self) -> MutexGuard<'_, Log> {
+        self.0.lock().unwrap()
+    }
+
+    /// Starts a new recording with the distances in the loaded ```kernel``` as the initial ones. A previous recording is discarded.
+    pub(crate) fn start(&self, kernel: &Kernel) {
+        let mut log = self.log();
+        log.n = kernel.node_count();
+        log.directed = kernel.is_directed();
+        log.initial = kernel.distances();
+        log.steps.clear();
+    }
+
+    /// Appends an iteration over the intermediate node ```k```, which changed the given pairs.
+    pub(crate) fn push(&self, k: usize, changes: Vec<(usize, usize, usize)>) {
+        self.log().steps.push(Step { k, changes });
+    }
+
+    /// Returns the number of nodes of the recorded run.
+    pub fn node_count(&self) -> usize {
+        self.log().n
+    }
+
+    /// Returns the number of recorded iterations. The snapshots are numbered from 0 (before the first iteration) to this number.
+    pub fn len(&self) -> usize {
+        self.log().steps.len()
+    }
+
+    /// If no iteration was recorded, this returns true.
+    pub fn is_empty(&self) -> bool {
+        self.log().steps.is_empty()
+    }
+
+    /// Returns the intermediate node of the iteration, which led from snapshot ```s``` to snapshot ```s + 1```.
+    pub fn intermediate_node(&self, s: usize) -> usize {
+        self.log().steps[s].k
+    }
+
+    /// Returns the pairs ```(i, j, distance)``` with ```i < j``` (or ```i != j``` in a directed graph), which the iteration from snapshot ```s``` to snapshot ```s + 1``` made shorter.
+    pub fn changes(&self, s: usize) -> Vec<(usize, usize, usize)> {
+        self.log().steps[s].changes.clone()
+    }
+
+    /// Returns the distances of snapshot ```s``` as a row-major ```n * n``` vector, where ```None``` means that no path was known yet.
+    /// Snapshot 0 holds the edge weights, and snapshot ```len()``` the final distances.
+    pub fn distances(&self, s: usize) -> Vec<Option<usize>> {
+        let log = self.log();
+        let n = log.n;
+        assert!(s <= log.steps.len(), "there is no snapshot {}", s);
+
+        let mut d = log.initial.clone();
+        for &(i, j, len) in log.steps[..s].iter().flat_map(|step| &step.changes) {
+            d[i * n + j] = len;
+            // The change of a pair of an undirected graph holds for both orders.
+            if !log.directed {
+                d[j * n + i] = len;
+            }
+        }
+
+        d.into_iter()
+            .map(|len| if len == usize::MAX { None } else { Some(len) })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Snapshots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshots")
+            .field("nodes", &self.node_count())
+            .field("iterations", &self.len())
+            .finish()
+    }
+}
+
+impl PartialEq for Snapshots {
+    fn eq(&self, other: &Snapshots) -> bool {
+        // Two recorders are only the same, if they are the same object.
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Snapshots {}
@@ -0,0 +1,162 @@
+//! Approximate distances with a bounded multiplicative error, computed on a sparse spanner of the graph.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use Distances;
+
+/// The length of a missing path.
+const INFINITY: usize = usize::MAX;
+
+/// Distances, which are at least the exact distances and at most ```1 + epsilon``` times as large.
+/// Pairs without a path in the graph have no path here either.
+#[derive(Clone, Debug)]
+pub struct ApproximateDistances {
+    d: Box<[usize]>,
+    n: usize,
+    spanner_edges: usize,
+}
+
+impl ApproximateDistances {
+    /// Returns the number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of edges of the spanner, which the distances were computed on.
+    #[inline]
+    pub fn spanner_edge_count(&self) -> usize {
+        self.spanner_edges
+    }
+
+    /// Returns the approximate distance between ```i``` and ```j```, or ```None```, if there is no path between them.
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> Option<usize> {
+        match self.d[i * self.n + j] {
+            INFINITY => None,
+            d => Some(d),
+        }
+    }
+}
+
+impl Distances for ApproximateDistances {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn distance(&self, i: usize, j: usize) -> Option<usize> {
+        self.get(i, j)
+    }
+}
+
+/// Runs Dijkstra's algorithm from ```source``` on ```adj```, but doesn't settle nodes farther away than ```bound```.
+/// ```dist``` has to be ```INFINITY``` everywhere, and every entry, which was set, is recorded in ```touched```.
+fn dijkstra(
+    adj: &[Vec<(usize, usize)>],
+    source: usize,
+    bound: usize,
+    dist: &mut [usize],
+    touched: &mut Vec<usize>,
+) {
+    let mut heap = BinaryHeap::new();
+    dist[source] = 0;
+    touched.push(source);
+    heap.push(Reverse((0, source)));
+
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if d > dist[u] {
+            continue;
+        }
+
+        for &(v, w) in &adj[u] {
+            let nd = d.saturating_add(w);
+            if nd <= bound && nd < dist[v] {
+                if dist[v] == INFINITY {
+                    touched.push(v);
+                }
+                dist[v] = nd;
+                heap.push(Reverse((nd, v)));
+            }
+        }
+    }
+}
+
+/// This function computes approximate distances between every two nodes, which are at most ```1 + epsilon``` times the exact ones.
+///
+/// First, a greedy spanner is built: The edges are visited by ascending weight, and an edge is only kept,
+/// if the kept edges don't already connect its endpoints within ```1 + epsilon``` times its weight.
+/// Then the distances are computed by a single-source search from every node on the spanner.
+/// For sparse spanners, this takes far less than the **O(V^(3))** time of the exact computation, and a larger ```epsilon``` gives a sparser spanner.
+///
+/// # Panics
+///
+/// The graph has to be undirected, and ```epsilon``` must not be negative.
+pub fn approximate_distances<G>(g: G, epsilon: f64) -> ApproximateDistances
+where
+    G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences + GraphProp,
+    G::EdgeWeight: Clone + Into<usize>,
+{
+    assert!(!g.is_directed(), "the graph has to be undirected");
+    assert!(epsilon >= 0.0, "epsilon must not be negative");
+
+    let n = g.node_count();
+    let stretch = 1.0 + epsilon;
+
+    let mut edges: Vec<(usize, usize, usize)> = g
+        .edge_references()
+        .map(|e| {
+            (
+                e.weight().clone().into(),
+                e.source().index(),
+                e.target().index(),
+            )
+        })
+        .filter(|&(_, u, v)| u != v)
+        .collect();
+    edges.sort_unstable();
+
+    let mut adj = vec![Vec::new(); n];
+    let mut dist = vec![INFINITY; n];
+    let mut touched = Vec::new();
+    let mut spanner_edges = 0;
+
+    for (w, u, v) in edges {
+        // The bound is rounded down, because all distances are integers.
+        let bound = (w as f64 * stretch).min(INFINITY as f64) as usize;
+        dijkstra(&adj, u, bound, &mut dist, &mut touched);
+        let covered = dist[v] <= bound;
+
+        for x in touched.drain(..) {
+            dist[x] = INFINITY;
+        }
+
+        if !covered {
+            adj[u].push((v, w));
+            adj[v].push((u, w));
+            spanner_edges += 1;
+        }
+    }
+
+    let mut d = vec![INFINITY; n * n].into_boxed_slice();
+    for (i, row) in d.chunks_mut(n.max(1)).enumerate().take(n) {
+        dijkstra(&adj, i, INFINITY, row, &mut touched);
+        touched.clear();
+    }
+
+    ApproximateDistances {
+        d,
+        n,
+        spanner_edges,
+    }
+}
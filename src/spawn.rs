@@ -0,0 +1,181 @@
+//! Computations on a background thread, which can be joined, polled or awaited, and cancelled.
+
+use std::future::Future;
+use std::panic;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::thread;
+use std::thread::JoinHandle;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use cancel::run_until;
+use Options;
+use PathMatrix;
+
+/// The error of a computation, which was cancelled before it finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// The progress of a computation, which is shared between a ```Computation``` and its thread.
+struct State<T> {
+    finished: bool,
+    /// The result, once the thread has finished. The thread finishes without a result only, if it panicked.
+    result: Option<Result<PathMatrix<T>, Cancelled>>,
+    /// The waker of the last poll, which is woken up, once the thread has finished.
+    waker: Option<Waker>,
+}
+
+/// The state, which is shared between a ```Computation``` and its thread.
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    cancel: AtomicBool,
+}
+
+/// Marks the computation as finished, when the thread ends, even if it panics, so a pending poll is always woken up.
+struct Finish<'a, T: 'a>(&'a Shared<T>);
+
+impl<'a, T> Drop for Finish<'a, T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.finished = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A handle to a computation on a background thread, which is started by ```floyd_warshall_spawn```.
+///
+/// The result can be waited for by ```join```, checked by ```is_finished```, or awaited, because this is a ```Future```.
+/// The computation can be stopped by ```cancel```. It checks for cancellation before every iteration of the dynamic program.
+/// Dropping the handle doesn't stop the computation.
+pub struct Computation<T> {
+    shared: Arc<Shared<T>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<T> Computation<T> {
+    /// Asks the computation to stop. It returns ```Cancelled``` after the current iteration, unless it has already finished.
+    pub fn cancel(&self) {
+        self.shared.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// If the computation has finished, either with a result or by cancellation, this returns true.
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().unwrap().finished
+    }
+
+    /// Waits for the computation to finish and returns its result.
+    ///
+    /// # Panics
+    ///
+    /// If the computation panicked, the panic is propagated to the caller.
+    /// The result is returned only once, so this panics, if it was already awaited or polled until it was ready, like polling it again would.
+    pub fn join(mut self) -> Result<PathMatrix<T>, Cancelled> {
+        self.join_thread();
+        let result = self.shared.state.lock().unwrap().result.take();
+        result.expect("the computation has already returned its result")
+    }
+
+    /// Waits for the thread to end and propagates its panic, if it panicked.
+    fn join_thread(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            if let Err(e) = thread.join() {
+                panic::resume_unwind(e);
+            }
+        }
+    }
+}
+
+impl<T> Future for Computation<T> {
+    type Output = Result<PathMatrix<T>, Cancelled>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = {
+            let mut state = self.shared.state.lock().unwrap();
+            if !state.finished {
+                state.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            state.result.take()
+        };
+
+        // The thread has finished. Without a result, it panicked, and joining it propagates the panic.
+        self.join_thread();
+        Poll::Ready(result.expect("the computation has already returned its result"))
+    }
+}
+
+/// This function starts computing the same matrix as ```floyd_warshall``` on a new thread and returns a handle to it immediately.
+/// The graph is moved to the thread, so it has to be owned, for example a ```Graph``` instead of a reference to one.
+pub fn floyd_warshall_spawn<G, N, E>(g: G) -> Computation<N>
+where
+    G: Send + 'static,
+    for<'a> &'a G: Data<NodeWeight = N, EdgeWeight = E>
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    N: Clone + Send + 'static,
+    E: Clone + Into<usize>,
+{
+    floyd_warshall_spawn_with_options(g, Options::default())
+}
+
+/// This function does the same as ```floyd_warshall_spawn```, but allows tuning the algorithm by the given ```Options```.
+pub fn floyd_warshall_spawn_with_options<G, N, E>(g: G, options: Options) -> Computation<N>
+where
+    G: Send + 'static,
+    for<'a> &'a G: Data<NodeWeight = N, EdgeWeight = E>
+        + GraphBase<NodeId = NodeIndex>
+        + NodeCount
+        + IntoNodeIdentifiers<NodeId = NodeIndex>
+        + IntoNodeReferences
+        + IntoEdgeReferences
+        + GraphProp,
+    N: Clone + Send + 'static,
+    E: Clone + Into<usize>,
+{
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            finished: false,
+            result: None,
+            waker: None,
+        }),
+        cancel: AtomicBool::new(false),
+    });
+
+    let thread = {
+        let shared = shared.clone();
+        thread::spawn(move || {
+            let _finish = Finish(&shared);
+            let result = run_until(&g, &options, || shared.cancel.load(Ordering::Relaxed))
+                .map_err(|_| Cancelled);
+            shared.state.lock().unwrap().result = Some(result);
+        })
+    };
+
+    Computation {
+        shared,
+        thread: Some(thread),
+    }
+}
@@ -0,0 +1,70 @@
+//! A small description of a graph, which fuzzers and property tests can generate.
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "arbitrary")]
+use arbitrary::Unstructured;
+
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+use petgraph::Undirected;
+
+/// The largest number of nodes of a generated ```GraphSpec```.
+#[cfg(feature = "arbitrary")]
+const MAX_NODES: usize = 32;
+
+/// The largest number of edges of a generated ```GraphSpec```.
+#[cfg(feature = "arbitrary")]
+const MAX_EDGES: usize = 128;
+
+/// The largest edge weight of a generated ```GraphSpec```. This is small enough, that no distance can overflow.
+#[cfg(feature = "arbitrary")]
+const MAX_WEIGHT: usize = 1 << 16;
+
+/// A description of an undirected graph by its number of nodes and a list of ```(a, b, weight)``` edges.
+/// With the feature ```arbitrary```, this implements ```arbitrary::Arbitrary```, so fuzzers can generate it directly.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphSpec {
+    /// The number of nodes.
+    pub nodes: usize,
+    /// The edges as pairs of node indices together with their weights.
+    pub edges: Vec<(usize, usize, usize)>,
+}
+
+impl GraphSpec {
+    /// Builds the described graph, whose node weights are the node indices.
+    /// The graph is always a valid input for ```floyd_warshall```: Edges, whose endpoints aren't nodes, and loops are left out,
+    /// and of several edges between the same pair of nodes, only the weight of the last one is kept.
+    pub fn to_graph(&self) -> Graph<usize, usize, Undirected> {
+        let mut g = Graph::with_capacity(self.nodes, self.edges.len());
+        for i in 0..self.nodes {
+            g.add_node(i);
+        }
+
+        for &(a, b, w) in &self.edges {
+            if a != b && a < self.nodes && b < self.nodes {
+                g.update_edge(NodeIndex::new(a), NodeIndex::new(b), w);
+            }
+        }
+        g
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for GraphSpec {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<GraphSpec> {
+        let nodes = u.int_in_range(0..=MAX_NODES)?;
+        let mut edges = Vec::new();
+
+        if nodes > 0 {
+            for _ in 0..u.int_in_range(0..=MAX_EDGES)? {
+                let a = u.choose_index(nodes)?;
+                let b = u.choose_index(nodes)?;
+                let w = u.int_in_range(0..=MAX_WEIGHT)?;
+                edges.push((a, b, w));
+            }
+        }
+
+        Ok(GraphSpec { nodes, edges })
+    }
+}
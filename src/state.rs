@@ -0,0 +1,151 @@
+//! A run of the algorithm, which can be paused, persisted and resumed.
+
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use kernel::Kernel;
+use Options;
+use PathMatrix;
+
+/// The state of a run of the algorithm between two iterations of the dynamic program: The current distances and intermediate nodes,
+/// together with the position of the next intermediate node. A run is advanced by ```step``` and turned into its result by ```finish```.
+///
+/// With the feature ```serde```, the state can be serialized, so a long run can be persisted and resumed later, even on a different machine.
+/// A deserialized state is checked to be consistent, so a damaged one is rejected with an error instead of panicking later.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "StateData<T>"))]
+pub struct FloydWarshallState<T> {
+    weights: Vec<T>,
+    order: Vec<usize>,
+    next: usize,
+    kernel: Kernel,
+}
+
+/// The fields of a ```FloydWarshallState```, as they are deserialized before they are checked.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct StateData<T> {
+    weights: Vec<T>,
+    order: Vec<usize>,
+    next: usize,
+    kernel: Kernel,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<StateData<T>> for FloydWarshallState<T> {
+    type Error = String;
+
+    fn try_from(data: StateData<T>) -> Result<FloydWarshallState<T>, String> {
+        let n = data.kernel.node_count();
+        if !data.kernel.is_consistent() || data.weights.len() != n {
+            return Err("the buffers don't fit to the number of nodes".to_string());
+        }
+        if data.order.iter().any(|&k| k >= n) || data.next > data.order.len() {
+            return Err("the order of the intermediate nodes is invalid".to_string());
+        }
+
+        Ok(FloydWarshallState {
+            weights: data.weights,
+            order: data.order,
+            next: data.next,
+            kernel: data.kernel,
+        })
+    }
+}
+
+impl<T: Clone> FloydWarshallState<T> {
+    /// Starts a run on the given graph, before its first iteration.
+    pub fn new<G>(g: G) -> FloydWarshallState<T>
+    where
+        G: Data<NodeWeight = T>
+            + GraphBase<NodeId = NodeIndex>
+            + NodeCount
+            + IntoNodeIdentifiers<NodeId = NodeIndex>
+            + IntoNodeReferences
+            + IntoEdgeReferences
+            + GraphProp,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        let mut m = PathMatrix::new(g.node_count());
+        let order = ::prepare(g, &mut m);
+
+        let mut kernel = Kernel::default();
+        kernel.load(&m);
+
+        let weights = (0..m.node_count())
+            .map(|i| m.node_weight(i).unwrap().clone())
+            .collect();
+
+        FloydWarshallState {
+            weights,
+            order,
+            next: 0,
+            kernel,
+        }
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn node_count(&self) -> usize {
+        self.kernel.node_count()
+    }
+
+    /// Returns the number of iterations of the whole run, which is the number of nodes.
+    pub fn iterations(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns the number of iterations, which are done already.
+    pub fn completed(&self) -> usize {
+        self.next
+    }
+
+    /// If all iterations are done, this returns true.
+    pub fn is_finished(&self) -> bool {
+        self.next == self.order.len()
+    }
+
+    /// Runs up to ```iterations``` further iterations of the dynamic program. Returns true, if the run is finished afterwards.
+    pub fn step(&mut self, iterations: usize) -> bool {
+        let end = self.next.saturating_add(iterations).min(self.order.len());
+        if end > self.next {
+            let mut ids = self.order.clone();
+            ids.sort_unstable();
+            let tile = Options::default().choose_tile_size(&self.kernel, &ids);
+
+            self.kernel.relax(&self.order[self.next..end], &ids, tile);
+            self.next = end;
+        }
+
+        self.is_finished()
+    }
+
+    /// Runs the remaining iterations and returns the same matrix, which ```floyd_warshall``` computes for the graph.
+    pub fn finish(mut self) -> PathMatrix<T> {
+        let remaining = self.order.len() - self.next;
+        self.step(remaining);
+
+        let mut m = if self.kernel.is_directed() {
+            PathMatrix::new_directed(self.node_count())
+        } else {
+            PathMatrix::new(self.node_count())
+        };
+        m.set_node_weights(self.weights);
+        self.kernel.store(&mut m);
+        m
+    }
+}
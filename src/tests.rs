@@ -1,4 +1,27 @@
 use super::floyd_warshall;
+use petgraph::EdgeType;
+use petgraph::Graph;
+
+/// Builds a graph with ```n``` nodes, whose weights are their indices, and up to ```e``` random edges of the weights ```1..=10```.
+/// ```Ty``` chooses, whether the graph is directed. The same seed draws the same edges for both kinds.
+/// Self-loops and parallel edges are skipped, the tests, which need them, add them explicitly.
+fn random_graph<Ty: EdgeType>(seed: u64, n: usize, e: usize) -> Graph<usize, usize, Ty> {
+    use petgraph::graph::NodeIndex;
+    use rng::SplitMix64;
+
+    let mut rng = SplitMix64::new(seed);
+    let mut graph = Graph::with_capacity(n, e);
+    for i in 0..n {
+        graph.add_node(i);
+    }
+    for _ in 0..e {
+        let (u, v, w) = (rng.below(n), rng.below(n), 1 + rng.below(10));
+        if u != v {
+            graph.update_edge(NodeIndex::new(u), NodeIndex::new(v), w);
+        }
+    }
+    graph
+}
 
 #[test]
 fn test_no_intermediate() {
@@ -128,4 +151,3894 @@ fn test_random() {
             println!("There is no path from {} to {}.", i, j)
         }
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_k_nearest() {
+    use petgraph::Graph;
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    graph.add_node(4);
+
+    graph.extend_with_edges([(a, b, 3usize), (a, c, 1), (c, d, 1)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.k_nearest(0, 2), vec![(2, 1), (3, 2)]);
+    assert_eq!(m.k_nearest(0, 10), vec![(2, 1), (3, 2), (1, 3)]);
+    assert_eq!(m.k_nearest(0, 0), vec![]);
+    assert_eq!(m.k_nearest(4, 3), vec![]);
+}
+
+#[test]
+fn test_farthest() {
+    use petgraph::Graph;
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    graph.add_node(4);
+
+    graph.extend_with_edges([(a, b, 2usize), (a, c, 1), (c, d, 1)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.farthest(0), Some((1, 2)));
+    assert_eq!(m.farthest(1), Some((3, 4)));
+    assert_eq!(m.farthest(4), None);
+}
+
+#[test]
+fn test_pairs_through() {
+    use petgraph::Graph;
+    let mut graph = Graph::new_undirected();
+
+    // A path a - b - c - d with a shortcut a - d, which is longer than going over b and c.
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (a, d, 5)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.pairs_through(1), vec![(0, 2), (0, 3)]);
+    assert_eq!(m.pairs_through(2), vec![(0, 3), (1, 3)]);
+    assert_eq!(m.count_pairs_through(0), 0);
+    assert_eq!(m.count_pairs_through(3), 0);
+}
+
+#[test]
+fn test_pairs_using_edge() {
+    use petgraph::Graph;
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (a, d, 5)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(
+        m.pairs_using_edge(1, 2),
+        vec![(0, 2), (0, 3), (1, 2), (1, 3)]
+    );
+    assert_eq!(m.pairs_using_edge(3, 2), vec![(0, 3), (1, 3), (2, 3)]);
+    assert_eq!(m.count_pairs_using_edge(0, 3), 0);
+}
+
+#[test]
+fn test_vitality() {
+    use edge_vitality;
+    use node_vitality;
+    use petgraph::Graph;
+    use Vitality;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (a, d, 5), (d, e, 1)]);
+
+    let m = floyd_warshall(&graph);
+
+    let nodes = node_vitality(&graph, &m);
+    assert_eq!(nodes[0], Vitality::default());
+    assert_eq!(
+        nodes[1],
+        Vitality {
+            sum_increase: 8,
+            max_increase: 4,
+            disconnected_pairs: 0,
+        }
+    );
+    assert_eq!(
+        nodes[3],
+        Vitality {
+            sum_increase: 0,
+            max_increase: 0,
+            disconnected_pairs: 3,
+        }
+    );
+
+    let edges = edge_vitality(&graph, &m);
+    assert_eq!(edges.len(), 5);
+    assert_eq!(
+        edges[1].1,
+        Vitality {
+            sum_increase: 22,
+            max_increase: 6,
+            disconnected_pairs: 0,
+        }
+    );
+    assert_eq!(edges[3].1, Vitality::default());
+    assert_eq!(edges[4].1.disconnected_pairs, 4);
+}
+
+#[test]
+fn test_vitality_lower_sources() {
+    use edge_vitality;
+    use node_vitality;
+    use petgraph::Graph;
+    use Vitality;
+
+    // The stored path from 1 to 0 runs over 3 and 2, so removing one of them changes a pair, whose source has the higher index.
+    let edges = [(0, 2, 1usize), (2, 3, 1), (3, 1, 1), (0, 1, 10)];
+    let build = |skip_node: Option<usize>, skip_edge: Option<usize>| {
+        let mut graph = Graph::new_undirected();
+        let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+        for (pos, &(u, v, w)) in edges.iter().enumerate() {
+            if Some(pos) != skip_edge && Some(u) != skip_node && Some(v) != skip_node {
+                graph.add_edge(nodes[u], nodes[v], w);
+            }
+        }
+        graph
+    };
+
+    // The degradation, which is recomputed from scratch on the graph without the element.
+    let expected = |skip_node: Option<usize>, skip_edge: Option<usize>| {
+        let before = floyd_warshall(&build(None, None));
+        let after = floyd_warshall(&build(skip_node, skip_edge));
+        let mut v = Vitality::default();
+        for i in 0..4 {
+            for j in i + 1..4 {
+                if Some(i) == skip_node || Some(j) == skip_node || !before.does_path_exist(i, j) {
+                    continue;
+                }
+                match after.distance(i, j) {
+                    Some(d) => {
+                        let increase = d - before.get_path_len(i, j);
+                        v.sum_increase += increase;
+                        v.max_increase = v.max_increase.max(increase);
+                    }
+                    None => v.disconnected_pairs += 1,
+                }
+            }
+        }
+        v
+    };
+
+    let graph = build(None, None);
+    let m = floyd_warshall(&graph);
+
+    let nodes = node_vitality(&graph, &m);
+    assert_eq!(nodes[2].sum_increase, 16);
+    assert_eq!(nodes[3].sum_increase, 16);
+    for (k, v) in nodes.iter().enumerate() {
+        assert_eq!(*v, expected(Some(k), None));
+    }
+
+    let vitality = edge_vitality(&graph, &m);
+    assert_eq!(vitality[0].1.sum_increase, 27);
+    for (pos, (_, v)) in vitality.iter().enumerate() {
+        assert_eq!(*v, expected(None, Some(pos)));
+    }
+}
+
+#[test]
+fn test_replacement_distance() {
+    use petgraph::Graph;
+    use ReplacementPaths;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+
+    let ab = graph.add_edge(a, b, 1usize);
+    let bc = graph.add_edge(b, c, 1);
+    graph.add_edge(c, d, 1);
+    let ad = graph.add_edge(a, d, 5);
+    let de = graph.add_edge(d, e, 1);
+
+    let m = floyd_warshall(&graph);
+    let r = ReplacementPaths::new(&graph, &m);
+
+    assert_eq!(r.replacement_distance(0, 2, bc), Some(6));
+    assert_eq!(r.replacement_distance(1, 2, bc), Some(7));
+    assert_eq!(r.replacement_distance(0, 4, ab), Some(6));
+    assert_eq!(r.replacement_distance(0, 4, ad), Some(4));
+    assert_eq!(r.replacement_distance(2, 4, de), None);
+    assert_eq!(r.replacement_distance(3, 3, de), Some(0));
+}
+
+#[test]
+fn test_replacement_distance_lower_sources() {
+    use petgraph::Graph;
+    use ReplacementPaths;
+
+    // The stored path from 1 to 0 runs over 3 and 2, so the failure of the edge 0 - 2 changes pairs in both orientations.
+    let edges = [(0, 2, 1usize), (2, 3, 1), (3, 1, 1), (0, 1, 10)];
+    let build = |skip_edge: Option<usize>| {
+        let mut graph = Graph::new_undirected();
+        let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+        let ids: Vec<_> = edges
+            .iter()
+            .enumerate()
+            .filter(|&(pos, _)| Some(pos) != skip_edge)
+            .map(|(_, &(u, v, w))| graph.add_edge(nodes[u], nodes[v], w))
+            .collect();
+        (graph, ids)
+    };
+
+    let (graph, ids) = build(None);
+    let m = floyd_warshall(&graph);
+    let r = ReplacementPaths::new(&graph, &m);
+
+    assert_eq!(r.replacement_distance(0, 1, ids[0]), Some(10));
+    assert_eq!(r.replacement_distance(3, 0, ids[0]), Some(11));
+    for (pos, &e) in ids.iter().enumerate() {
+        let after = floyd_warshall(&build(Some(pos)).0);
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(r.replacement_distance(i, j, e), after.distance(i, j));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sensitivity_oracle() {
+    use petgraph::Graph;
+    use SensitivityOracle;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+
+    graph.add_edge(a, b, 1usize);
+    let bc = graph.add_edge(b, c, 1);
+    graph.add_edge(c, d, 1);
+    graph.add_edge(a, d, 5);
+    graph.add_edge(d, e, 1);
+
+    let m = floyd_warshall(&graph);
+    let o = SensitivityOracle::new(&graph, &m);
+
+    assert_eq!(o.distance_avoiding_edge(1, 3, bc), Some(6));
+    assert_eq!(o.distance_avoiding_node(0, 2, 1), Some(6));
+    assert_eq!(o.distance_avoiding_node(1, 3, 2), Some(6));
+    assert_eq!(o.distance_avoiding_node(1, 3, 4), Some(2));
+    assert_eq!(o.distance_avoiding_node(0, 4, 3), None);
+    assert_eq!(o.distance_avoiding_node(0, 4, 4), None);
+}
+
+#[test]
+fn test_sensitivity_oracle_recomputed() {
+    use petgraph::Graph;
+    use SensitivityOracle;
+
+    // The stored path from 1 to 0 runs over 3 and 2, so a failure changes pairs, whose source has the higher index.
+    let edges = [(0, 2, 1usize), (2, 3, 1), (3, 1, 1), (0, 1, 10)];
+    let build = |skip_node: Option<usize>, skip_edge: Option<usize>| {
+        let mut graph = Graph::new_undirected();
+        let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+        let mut ids = Vec::new();
+        for (pos, &(u, v, w)) in edges.iter().enumerate() {
+            if Some(pos) != skip_edge && Some(u) != skip_node && Some(v) != skip_node {
+                ids.push(graph.add_edge(nodes[u], nodes[v], w));
+            }
+        }
+        (graph, ids)
+    };
+
+    let (graph, ids) = build(None, None);
+    let m = floyd_warshall(&graph);
+    let o = SensitivityOracle::new(&graph, &m);
+
+    assert_eq!(o.distance_avoiding_edge(0, 1, ids[0]), Some(10));
+    assert_eq!(o.distance_avoiding_node(1, 0, 3), Some(10));
+    for k in 0..4 {
+        let after = floyd_warshall(&build(Some(k), None).0);
+        for i in (0..4).filter(|&i| i != k) {
+            for j in (0..4).filter(|&j| j != k) {
+                assert_eq!(o.distance_avoiding_node(i, j, k), after.distance(i, j));
+            }
+        }
+    }
+    for (pos, &e) in ids.iter().enumerate() {
+        let after = floyd_warshall(&build(None, Some(pos)).0);
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(o.distance_avoiding_edge(i, j, e), after.distance(i, j));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_via() {
+    use petgraph::Graph;
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+    graph.add_node(5);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (d, e, 1)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.distance_via(4, 2, 0), Some(4));
+    assert_eq!(m.distance_via(1, 4, 0), Some(7));
+    assert_eq!(m.distance_via(0, 5, 1), None);
+
+    assert_eq!(m.path_via(4, 2, 0), Some(vec![&3, &2, &1]));
+    assert_eq!(m.path_via(1, 4, 0), Some(vec![&2, &3, &4, &3, &2, &1]));
+    assert_eq!(m.path_via(0, 0, 3), Some(vec![&1, &2]));
+    assert_eq!(m.path_via(0, 5, 1), None);
+}
+
+#[test]
+fn test_detour_ratio() {
+    use petgraph::Graph;
+    use DetourSummary;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 2)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.detour_ratio(0, 2, 1), Some(1.0));
+    assert_eq!(m.detour_ratio(0, 1, 3), Some(7.0));
+    assert_eq!(m.detour_ratio(0, 0, 3), None);
+
+    // The pairs (0, 2), (0, 3) and (2, 3) have the ratios 1, 1 and 2 over node 1.
+    assert_eq!(
+        m.detour_summary(1),
+        Some(DetourSummary {
+            pairs: 3,
+            mean: 4.0 / 3.0,
+            max: 2.0,
+        })
+    );
+}
+
+#[test]
+fn test_verify_metric() {
+    use petgraph::Graph;
+    use MetricViolation;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    graph.add_node(3);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (a, c, 3)]);
+
+    let mut m = floyd_warshall(&graph);
+    assert_eq!(m.verify_metric(), Ok(()));
+
+    m.set_path_len(0, 2, 5);
+    assert_eq!(m.verify_metric(), Err(MetricViolation::Triangle(0, 1, 2)));
+
+    // A self-loop doesn't change the distance of a node to itself.
+    graph.add_edge(b, b, 4);
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.verify_metric(), Ok(()));
+    assert_eq!(m.distance(1, 1), Some(0));
+}
+
+#[test]
+fn test_hyperbolicity() {
+    use petgraph::Graph;
+
+    // A tree is 0-hyperbolic.
+    let mut tree = Graph::new_undirected();
+    let a = tree.add_node(0);
+    let b = tree.add_node(1);
+    let c = tree.add_node(2);
+    let d = tree.add_node(3);
+    let e = tree.add_node(4);
+    tree.extend_with_edges([(a, b, 1usize), (b, c, 2), (b, d, 1), (d, e, 3)]);
+
+    let m = floyd_warshall(&tree);
+    assert_eq!(m.hyperbolicity(), Some(0.0));
+    assert_eq!(m.hyperbolicity_sampled(100, 1), Some(0.0));
+
+    // A cycle of four nodes with unit weights is 1-hyperbolic.
+    let mut cycle = Graph::new_undirected();
+    let a = cycle.add_node(0);
+    let b = cycle.add_node(1);
+    let c = cycle.add_node(2);
+    let d = cycle.add_node(3);
+    cycle.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (d, a, 1)]);
+
+    let m = floyd_warshall(&cycle);
+    assert_eq!(m.hyperbolicity(), Some(1.0));
+    assert!(m.hyperbolicity_sampled(1000, 7).unwrap() <= 1.0);
+
+    // In a star with huge weights, the sums for the quadruple of the leaves overflow, so only the quadruples with the center count.
+    let w = usize::MAX / 3;
+    let mut star = Graph::new_undirected();
+    let center = star.add_node(0);
+    for i in 1..5 {
+        let leaf = star.add_node(i);
+        star.add_edge(center, leaf, w);
+    }
+
+    let m = floyd_warshall(&star);
+    assert_eq!(m.distance(1, 2), Some(2 * w));
+    assert_eq!(m.hyperbolicity(), Some(0.0));
+    assert_eq!(m.hyperbolicity_sampled(1000, 3), Some(0.0));
+}
+
+#[test]
+fn test_k_center() {
+    use petgraph::Graph;
+    use KCenters;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+    let f = graph.add_node(5);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 4), (d, e, 1), (e, f, 1)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(
+        m.k_center(1),
+        KCenters {
+            centers: vec![0],
+            radius: Some(8),
+        }
+    );
+    assert_eq!(
+        m.k_center(2),
+        KCenters {
+            centers: vec![0, 5],
+            radius: Some(2),
+        }
+    );
+    assert_eq!(m.k_center(6).radius, Some(0));
+    assert_eq!(m.k_center(0).radius, None);
+
+    // Nodes joined by edges of weight 0 are covered by the same center, but are still distinct centers.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 0usize), (nodes[1], nodes[2], 0), (nodes[2], nodes[3], 4)]);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(
+        m.k_center(4),
+        KCenters {
+            centers: vec![0, 3, 1, 2],
+            radius: Some(0),
+        }
+    );
+}
+
+#[test]
+fn test_one_median() {
+    use petgraph::Graph;
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.one_median(), Some((1, 4)));
+    assert_eq!(m.weighted_one_median(&[0, 0, 1, 10]), Some((3, 1)));
+    assert_eq!(m.weighted_one_median(&[0, 0, 0, 0]), Some((0, 0)));
+
+    graph.add_node(4);
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.one_median(), None);
+    assert_eq!(m.weighted_one_median(&[1, 1, 1, 1, 0]), Some((1, 4)));
+}
+
+#[test]
+#[cfg(feature = "clustering")]
+fn test_k_medoids() {
+    use k_medoids;
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+
+    // Two triangles, which are connected by a long edge.
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+    let f = graph.add_node(5);
+
+    graph.extend_with_edges([
+        (a, b, 1usize),
+        (b, c, 1),
+        (a, c, 1),
+        (c, d, 10),
+        (d, e, 1),
+        (e, f, 1),
+        (d, f, 1),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    let r = k_medoids(&m, 2);
+
+    let mut medoids = r.medoids.clone();
+    medoids.sort();
+    assert_eq!(medoids, vec![2, 3]);
+    assert_eq!(r.cost, 4);
+    assert_eq!(r.assignment[0], r.assignment[1]);
+    assert_eq!(r.assignment[4], r.assignment[5]);
+    assert_ne!(r.assignment[0], r.assignment[5]);
+}
+
+#[test]
+#[cfg(feature = "clustering")]
+fn test_single_linkage() {
+    use petgraph::Graph;
+    use single_linkage;
+    use Merge;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    graph.add_node(4);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 3), (c, d, 2)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(
+        single_linkage(&m),
+        vec![
+            Merge {
+                left: 0,
+                right: 1,
+                distance: 1,
+                size: 2,
+            },
+            Merge {
+                left: 2,
+                right: 3,
+                distance: 2,
+                size: 2,
+            },
+            Merge {
+                left: 5,
+                right: 6,
+                distance: 3,
+                size: 4,
+            },
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "layout")]
+fn test_layout() {
+    use mds_layout;
+    use petgraph::Graph;
+    use stress_layout;
+
+    // A right triangle with the sides 3, 4 and 5 can be drawn exactly.
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    graph.extend_with_edges([(a, b, 3usize), (b, c, 4), (a, c, 5)]);
+
+    let m = floyd_warshall(&graph);
+
+    for pos in [mds_layout(&m), stress_layout(&m, 10)] {
+        for i in 0..3 {
+            for j in 0..3 {
+                let dx = pos[i].0 - pos[j].0;
+                let dy = pos[i].1 - pos[j].1;
+                let d = (dx * dx + dy * dy).sqrt();
+                assert!((d - m.get_path_len(i, j) as f64).abs() < 1e-6);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_steiner_tree() {
+    use petgraph::Graph;
+    use steiner_tree;
+
+    // A star with the center e, whose spokes are cheaper than the outer cycle.
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let e = graph.add_node(4);
+    graph.add_node(5);
+
+    graph.add_edge(a, b, 5usize);
+    graph.add_edge(b, c, 5);
+    graph.add_edge(c, a, 5);
+    let ea = graph.add_edge(e, a, 2);
+    let eb = graph.add_edge(e, b, 2);
+    let ec = graph.add_edge(e, c, 2);
+    graph.add_edge(e, d, 2);
+
+    let m = floyd_warshall(&graph);
+
+    let mut t = steiner_tree(&graph, &m, &[0, 1, 2]).unwrap();
+    t.edges.sort();
+    assert_eq!(t.edges, vec![ea, eb, ec]);
+    assert_eq!(t.weight, 6);
+
+    // Repeated terminals are only connected once.
+    let mut t = steiner_tree(&graph, &m, &[0, 2, 0, 1]).unwrap();
+    t.edges.sort();
+    assert_eq!(t.edges, vec![ea, eb, ec]);
+    assert_eq!(t.weight, 6);
+
+    let t = steiner_tree(&graph, &m, &[3, 3]).unwrap();
+    assert!(t.edges.is_empty());
+    assert_eq!(t.weight, 0);
+
+    assert!(steiner_tree(&graph, &m, &[0, 5]).is_none());
+}
+
+#[test]
+fn test_tsp() {
+    use petgraph::Graph;
+    use tsp_apx;
+    use tsp_nearest_neighbor;
+
+    // A cycle of four nodes with a long diagonal.
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    graph.add_node(4);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (d, a, 1), (a, c, 5)]);
+
+    let m = floyd_warshall(&graph);
+
+    let t = tsp_apx(&m, &[0, 1, 2, 3]).unwrap();
+    assert_eq!(t.order[0], 0);
+    assert_eq!(t.order.len(), 4);
+    assert!(t.length <= 8);
+
+    let t = tsp_nearest_neighbor(&m, &[0, 2, 1, 3]).unwrap();
+    assert_eq!(t.order, vec![0, 1, 2, 3]);
+    assert_eq!(t.length, 4);
+
+    assert!(tsp_apx(&m, &[0, 4]).is_none());
+    assert!(tsp_nearest_neighbor(&m, &[0, 4]).is_none());
+}
+
+#[test]
+fn test_compare() {
+    use compare;
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 1)]);
+    let before = floyd_warshall(&graph);
+
+    graph.extend_with_edges([(a, c, 1usize), (c, d, 1)]);
+    let after = floyd_warshall(&graph);
+
+    let r = compare(&before, &after);
+    assert_eq!(r.compared_pairs, 3);
+    assert_eq!(r.connectivity_changes, 3);
+    assert_eq!(r.frobenius, 1.0);
+    assert_eq!(r.max_difference, 1);
+    assert_eq!(r.rank_correlation, None);
+
+    let r = compare(&after, &after);
+    assert_eq!(r.frobenius, 0.0);
+    assert_eq!(r.rank_correlation, Some(1.0));
+}
+
+#[test]
+fn test_workspace() {
+    use petgraph::Graph;
+    use Workspace;
+
+    let mut large = Graph::new_undirected();
+    let a = large.add_node(0);
+    let b = large.add_node(1);
+    let c = large.add_node(2);
+    let d = large.add_node(3);
+    let e = large.add_node(4);
+    large.extend_with_edges([(a, b, 1usize), (b, c, 1), (c, d, 1), (d, e, 1)]);
+
+    let mut small = Graph::new_undirected();
+    let a = small.add_node(0);
+    let b = small.add_node(1);
+    let c = small.add_node(2);
+    small.extend_with_edges([(a, b, 1usize), (b, c, 1), (a, c, 3)]);
+
+    let mut w = Workspace::new();
+
+    let m = w.run(&large);
+    assert_eq!(m.node_count(), 5);
+    assert_eq!(m.get_path_len(0, 4), 4);
+    assert_eq!(m.get_path(0, 4).get_slice(), &[1, 2, 3]);
+
+    let m = w.run(&small);
+    assert_eq!(m.node_count(), 3);
+    assert_eq!(m.get_path_len(0, 2), 2);
+    assert_eq!(m.get_path(0, 2).get_slice(), &[1]);
+    assert_eq!(m.get_path(0, 1).get_slice(), &[] as &[i32]);
+    assert_eq!(m.k_nearest(0, 5), vec![(1, 1), (2, 2)]);
+
+    let m = w.run(&large);
+    assert_eq!(m.get_path_len(1, 4), 3);
+    assert_eq!(m.get_path(1, 4).get_slice(), &[2, 3]);
+}
+
+#[test]
+fn test_tile_sizes() {
+    use floyd_warshall_with_options;
+    use petgraph::Undirected;
+    use Options;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(42, 30, 60);
+
+    let expected = floyd_warshall(&graph);
+
+    for tile_size in [Some(1), Some(2), Some(7), Some(100), None] {
+        let m = floyd_warshall_with_options(
+            &graph,
+            &Options {
+                tile_size,
+                ..Options::default()
+            },
+        );
+        for i in 0..30 {
+            for j in 0..30 {
+                assert_eq!(m.does_path_exist(i, j), expected.does_path_exist(i, j));
+                if m.does_path_exist(i, j) {
+                    assert_eq!(m.get_path_len(i, j), expected.get_path_len(i, j));
+                }
+            }
+        }
+    }
+
+    let tuned = floyd_warshall_with_options(
+        &graph,
+        &Options {
+            auto_tune: true,
+            ..Options::default()
+        },
+    );
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(tuned.distance(i, j), expected.distance(i, j));
+            assert_eq!(tuned.get_path(i, j).get_slice(), expected.get_path(i, j).get_slice());
+        }
+    }
+}
+
+#[test]
+fn test_materialized_paths() {
+    use petgraph::Undirected;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(7, 25, 50);
+    let mut w = vec![vec![None; 25]; 25];
+    for e in graph.raw_edges() {
+        let (u, v) = (e.source().index(), e.target().index());
+        w[u][v] = Some(e.weight);
+        w[v][u] = Some(e.weight);
+    }
+
+    let m = floyd_warshall(&graph);
+
+    // Every materialized path has to consist of edges of the graph, which sum up to its length.
+    for i in 0..25 {
+        for j in i + 1..25 {
+            if !m.does_path_exist(i, j) {
+                continue;
+            }
+
+            let mut hops = vec![i];
+            hops.extend(m.get_path_iter(i, j).cloned());
+            hops.push(j);
+
+            let len: usize = hops.windows(2).map(|e| w[e[0]][e[1]].unwrap()).sum();
+            assert_eq!(len, m.get_path_len(i, j));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        use floyd_warshall_par;
+
+        let par = floyd_warshall_par(&graph);
+        for i in 0..25 {
+            for j in 0..25 {
+                assert_eq!(par.does_path_exist(i, j), m.does_path_exist(i, j));
+                assert_eq!(par.get_path(i, j).get_slice(), m.get_path(i, j).get_slice());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_isolated_nodes() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..8).map(|i| graph.add_node(i)).collect();
+
+    // Two components with a leaf each, and the nodes 3 and 7 are isolated.
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 2usize),
+        (nodes[1], nodes[2], 3),
+        (nodes[4], nodes[5], 1),
+        (nodes[5], nodes[6], 1),
+    ]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.get_path_len(0, 2), 5);
+    assert_eq!(m.get_path(0, 2).get_slice(), &[1]);
+    assert_eq!(m.get_path_len(4, 6), 2);
+    assert_eq!(m.get_path(6, 4).get_slice(), &[5]);
+
+    assert!(!m.does_path_exist(0, 4));
+    assert!(!m.does_path_exist(2, 6));
+    for i in 0..8 {
+        if i != 3 {
+            assert!(!m.does_path_exist(i, 3));
+        }
+        if i != 7 {
+            assert!(!m.does_path_exist(i, 7));
+        }
+    }
+}
+
+#[test]
+fn test_batch() {
+    use floyd_warshall_batch;
+    use petgraph::Undirected;
+
+    let graphs: Vec<Graph<usize, usize, Undirected>> =
+        (0..20).map(|s| random_graph(3 + s as u64, 5 + s, 2 * (5 + s))).collect();
+
+    let refs: Vec<_> = graphs.iter().collect();
+    let batch = floyd_warshall_batch(refs.iter().cloned());
+    assert_eq!(batch.len(), graphs.len());
+
+    #[cfg(feature = "rayon")]
+    let par = ::floyd_warshall_batch_par(&refs);
+
+    for (b, g) in batch.iter().zip(&graphs) {
+        let m = floyd_warshall(g);
+        let n = g.node_count();
+        assert_eq!(b.node_count(), n);
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(b.distance(i, j), m.distance(i, j));
+                assert_eq!(b.get_path(i, j).get_slice(), m.get_path(i, j).get_slice());
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    for (p, b) in par.iter().zip(&batch) {
+        for i in 0..b.node_count() {
+            for j in 0..b.node_count() {
+                assert_eq!(p.distance(i, j), b.distance(i, j));
+                assert_eq!(p.get_path(i, j).get_slice(), b.get_path(i, j).get_slice());
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_threads() {
+    use floyd_warshall_batch_par_with_options;
+    use floyd_warshall_par_with_options;
+    use petgraph::Graph;
+    use rayon::ThreadPoolBuilder;
+    use std::sync::Arc;
+    use Options;
+    use Threads;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..10).map(|i| graph.add_node(i)).collect();
+    for i in 0..9 {
+        graph.add_edge(nodes[i], nodes[i + 1], 1usize);
+    }
+
+    let pool = Arc::new(ThreadPoolBuilder::new().num_threads(2).build().unwrap());
+    let threads = [Threads::Global, Threads::Count(3), Threads::Pool(pool.clone())];
+    assert_eq!(Threads::Pool(pool.clone()), Threads::Pool(pool));
+    assert_ne!(Threads::Count(2), Threads::Global);
+
+    for (threads, first_touch) in threads.iter().cloned().zip([false, true, false]) {
+        let options = Options {
+            threads,
+            first_touch,
+            ..Options::default()
+        };
+
+        let m = floyd_warshall_par_with_options(&graph, &options);
+        assert_eq!(m.get_path_len(0, 9), 9);
+        assert_eq!(m.get_path(9, 0).get_slice(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let batch = floyd_warshall_batch_par_with_options(&[&graph, &graph], &options);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1].get_path_len(2, 7), 5);
+    }
+}
+
+#[test]
+fn test_compact() {
+    use floyd_warshall_compact;
+    use petgraph::graph::NodeIndex;
+    use petgraph::Undirected;
+    use DistanceOverflow;
+
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(11, 30, 50).map(|_, &i| i, |_, &w| 97 * w);
+    // A self-loop doesn't change the distance 0 on the diagonal.
+    graph.add_edge(NodeIndex::new(4), NodeIndex::new(4), 5);
+
+    let expected = floyd_warshall(&graph);
+    let compact = floyd_warshall_compact(&graph).unwrap();
+    assert_eq!(compact.node_count(), 30);
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(compact.get(i, j).map(usize::from), expected.distance(i, j));
+        }
+    }
+
+    // Every edge fits, but the path over both of them doesn't.
+    let mut long = Graph::new_undirected();
+    let a = long.add_node(0);
+    let b = long.add_node(1);
+    let c = long.add_node(2);
+    long.extend_with_edges([(a, b, 40000usize), (b, c, 40000)]);
+    assert_eq!(floyd_warshall_compact(&long).unwrap_err(), DistanceOverflow::Path(0, 2));
+
+    // A shortcut keeps the distance in range.
+    long.add_edge(a, c, 100);
+    let compact = floyd_warshall_compact(&long).unwrap();
+    assert_eq!(compact.get(0, 2), Some(100));
+    assert_eq!(compact.get(1, 2), Some(40000));
+
+    long.add_edge(a, b, 70000);
+    assert_eq!(floyd_warshall_compact(&long).unwrap_err(), DistanceOverflow::Edge(0, 1));
+
+    // A directed graph keeps both directions apart.
+    let mut directed: Graph<usize, usize> = random_graph(11, 30, 80).map(|_, &i| i, |_, &w| 97 * w);
+    directed.add_edge(NodeIndex::new(4), NodeIndex::new(4), 5);
+
+    let expected = floyd_warshall(&directed);
+    let compact = floyd_warshall_compact(&directed).unwrap();
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(compact.get(i, j).map(usize::from), expected.distance(i, j));
+        }
+    }
+
+    // Only the way back from b to a overflows.
+    let mut long = Graph::new();
+    let a = long.add_node(0);
+    let b = long.add_node(1);
+    let c = long.add_node(2);
+    long.extend_with_edges([(a, b, 100usize), (b, c, 40000), (c, a, 40000)]);
+    assert_eq!(floyd_warshall_compact(&long).unwrap_err(), DistanceOverflow::Path(1, 0));
+
+    long.add_edge(b, a, 100);
+    let compact = floyd_warshall_compact(&long).unwrap();
+    assert_eq!(compact.get(0, 2), Some(40100));
+    assert_eq!(compact.get(2, 0), Some(40000));
+}
+
+#[test]
+fn test_transitive_closure() {
+    use petgraph::graph::NodeIndex;
+    use petgraph::Undirected;
+    use transitive_closure;
+
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(5, 100, 60);
+    // A self-loop doesn't make any other node reachable.
+    graph.add_edge(NodeIndex::new(7), NodeIndex::new(7), 1);
+
+    let m = floyd_warshall(&graph);
+    let r = transitive_closure(&graph);
+    assert_eq!(r.node_count(), 100);
+    for i in 0..100 {
+        for j in 0..100 {
+            assert_eq!(r.is_reachable(i, j), m.distance(i, j).is_some());
+        }
+        let reachable: Vec<usize> = r.reachable_from(i).collect();
+        assert_eq!(reachable.len(), r.count_reachable(i));
+        assert!(reachable.iter().all(|&j| r.is_reachable(i, j)));
+    }
+
+    // In a directed graph, the edges are only followed forward.
+    let mut directed = Graph::new();
+    let a = directed.add_node(0);
+    let b = directed.add_node(1);
+    let c = directed.add_node(2);
+    directed.extend_with_edges([(a, b, 1usize), (b, c, 1)]);
+
+    let r = transitive_closure(&directed);
+    assert_eq!(r.reachable_from(0).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(r.reachable_from(1).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(r.reachable_from(2).collect::<Vec<_>>(), vec![2]);
+}
+
+#[test]
+fn test_checkpoint() {
+    use floyd_warshall_checkpointed;
+    use petgraph::Graph;
+    use resume_from_checkpoint;
+    use petgraph::Undirected;
+    use std::fs;
+    use Checkpoint;
+    use Options;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(13, 20, 40);
+
+    let path = ::std::env::temp_dir()
+        .join(format!("floyd-warshall-{}.ckpt", ::std::process::id()));
+    let checkpoint = Checkpoint {
+        path: path.clone(),
+        every: 7,
+    };
+    let options = Options::default();
+
+    let expected = floyd_warshall(&graph);
+    let check = |m: &::PathMatrix<usize>| {
+        for i in 0..20 {
+            for j in 0..20 {
+                assert_eq!(m.distance(i, j), expected.distance(i, j));
+                assert_eq!(m.get_path(i, j).get_slice(), expected.get_path(i, j).get_slice());
+            }
+        }
+    };
+
+    let m = floyd_warshall_checkpointed(&graph, &options, &checkpoint).unwrap();
+    check(&m);
+
+    // The last checkpoint was written before the final chunk, so resuming runs that chunk again.
+    let m = resume_from_checkpoint(&graph, &options, &checkpoint).unwrap();
+    check(&m);
+
+    let mut small = Graph::<usize, usize, _>::new_undirected();
+    small.add_node(0);
+    let err = resume_from_checkpoint(&small, &options, &checkpoint).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::InvalidData);
+
+    // A damaged header is rejected before the buffers are read.
+    let header = |n: u64, next: u64| {
+        let mut bytes = b"FWKERNL2".to_vec();
+        bytes.extend_from_slice(&n.to_le_bytes());
+        bytes.extend_from_slice(&next.to_le_bytes());
+        bytes.push(0);
+        bytes
+    };
+    for &(n, next) in &[(u64::MAX, 0), (1 << 33, 0), (20, 21)] {
+        fs::write(&path, header(n, next)).unwrap();
+        let err = resume_from_checkpoint(&graph, &options, &checkpoint).unwrap_err();
+        assert_eq!(err.kind(), ::std::io::ErrorKind::InvalidData);
+    }
+
+    // So is an intermediate node, which doesn't exist.
+    let mut bytes = header(20, 0);
+    for _ in 0..2 * 20 * 20 {
+        bytes.extend_from_slice(&20u64.to_le_bytes());
+    }
+    fs::write(&path, bytes).unwrap();
+    let err = resume_from_checkpoint(&graph, &options, &checkpoint).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::InvalidData);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_checkpoint_every_iteration() {
+    use floyd_warshall_checkpointed;
+    use petgraph::Graph;
+    use resume_from_checkpoint;
+    use petgraph::Directed;
+    use std::fs;
+    use Checkpoint;
+    use Options;
+
+    let graph: Graph<usize, usize, Directed> = random_graph(83, 20, 50);
+
+    let path = ::std::env::temp_dir().join(format!("floyd-warshall-resume-{}.ckpt", ::std::process::id()));
+    let checkpoint = Checkpoint::new(path.clone());
+    assert_eq!(checkpoint.every, 1);
+
+    // A checkpoint is written after every iteration but the last one, so resuming only runs the last iteration again.
+    let expected = floyd_warshall_checkpointed(&graph, &Options::default(), &checkpoint).unwrap();
+    let m = resume_from_checkpoint(&graph, &Options::default(), &checkpoint).unwrap();
+    assert!(m.is_directed());
+    for i in 0..20 {
+        for j in 0..20 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+        }
+    }
+
+    fs::remove_file(&path).unwrap();
+    assert!(resume_from_checkpoint(&graph, &Options::default(), &checkpoint).is_err());
+}
+
+#[test]
+fn test_shortest_path() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+    let c = graph.add_node("c");
+    let d = graph.add_node("d");
+    graph.add_node("e");
+    graph.extend_with_edges([(a, b, 1usize), (b, c, 2), (c, d, 3), (a, d, 10)]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.shortest_path(0, 3), Some((6, vec![&"a", &"b", &"c", &"d"])));
+    assert_eq!(m.shortest_path(3, 0), Some((6, vec![&"d", &"c", &"b", &"a"])));
+    assert_eq!(m.shortest_path(1, 2), Some((2, vec![&"b", &"c"])));
+    assert_eq!(m.shortest_path(2, 2), Some((0, vec![&"c"])));
+    assert_eq!(m.shortest_path(0, 4), None);
+}
+
+#[test]
+fn test_indexed() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+    let c = graph.add_node("c");
+    let d = graph.add_node("d");
+    graph.extend_with_edges([(a, b, 2usize), (b, c, 2), (a, c, 5)]);
+
+    let m = floyd_warshall(&graph);
+    let v = m.indexed();
+
+    assert_eq!(v.node_weight(c), Some(&"c"));
+    assert_eq!(v.distance(a, c), Some(4));
+    assert_eq!(v.distance(a, d), None);
+    assert!(v.does_path_exist(c, a));
+    assert_eq!(v.get_path_len(b, c), 2);
+    assert_eq!(v.get_path(c, a).get_slice(), &["b"]);
+    assert_eq!(v.shortest_path(c, a), Some((4, vec![&"c", &"b", &"a"])));
+    assert_eq!(v.k_nearest(a, 5), vec![(b, 2), (c, 4)]);
+    assert_eq!(v.farthest(b), Some((a, 2)));
+}
+
+#[test]
+fn test_labels() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let berlin = graph.add_node(("Berlin", 3_600_000));
+    let leipzig = graph.add_node(("Leipzig", 600_000));
+    let munich = graph.add_node(("Munich", 1_500_000));
+    graph.add_node(("Helgoland", 1_300));
+    graph.extend_with_edges([(berlin, leipzig, 190usize), (leipzig, munich, 430)]);
+
+    let m = floyd_warshall(&graph);
+
+    let by_name = m.labels_by(|&(name, _)| name.to_string());
+    assert_eq!(by_name.index_of("Munich"), Some(2));
+    assert_eq!(by_name.distance_by_label("Berlin", "Munich"), Some(620));
+    assert_eq!(by_name.distance_by_label("Berlin", "Helgoland"), None);
+    assert_eq!(by_name.distance_by_label("Berlin", "Paris"), None);
+
+    let (len, path) = by_name.shortest_path_by_label("Munich", "Berlin").unwrap();
+    assert_eq!(len, 620);
+    let names: Vec<_> = path.iter().map(|w| w.0).collect();
+    assert_eq!(names, vec!["Munich", "Leipzig", "Berlin"]);
+
+    let by_weight = m.labels();
+    assert_eq!(
+        by_weight.distance_by_label(&("Leipzig", 600_000), &("Munich", 1_500_000)),
+        Some(430)
+    );
+}
+
+#[test]
+fn test_path_matrix_ref() {
+    use petgraph::visit::EdgeRef;
+    use petgraph::Graph;
+    use PathMatrixRef;
+
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+    let c = graph.add_node("c");
+    let d = graph.add_node("d");
+    graph.add_edge(a, b, 1usize);
+    let slow = graph.add_edge(b, c, 5);
+    let fast = graph.add_edge(b, c, 2);
+    graph.add_edge(c, d, 1);
+    graph.add_edge(a, d, 10);
+
+    let r = PathMatrixRef::new(&graph);
+    assert_eq!(r.matrix().get_path_len(0, 3), 4);
+
+    let nodes: Vec<_> = r.path_nodes(3, 0).unwrap().iter().map(|n| n.0).collect();
+    assert_eq!(nodes, vec![d, c, b, a]);
+    assert_eq!(*r.node(2).1, "c");
+
+    let edges = r.path_edges(0, 3).unwrap();
+    assert_eq!(edges.len(), 3);
+    assert_eq!(edges[1].id(), fast);
+    assert_ne!(edges[1].id(), slow);
+    assert_eq!(edges.iter().map(|e| *e.weight()).sum::<usize>(), 4);
+
+    assert_eq!(r.path_nodes(1, 1).unwrap().len(), 1);
+    assert!(r.path_edges(1, 1).unwrap().is_empty());
+}
+
+#[test]
+fn test_get_path_edges() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    let ab = graph.add_edge(a, b, 1usize);
+    graph.add_edge(c, b, 5);
+    let cb = graph.add_edge(c, b, 2);
+    let dc = graph.add_edge(d, c, 1);
+    graph.add_edge(a, d, 10);
+    graph.add_node(4);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.get_path_edges(0, 3, &graph), Some(vec![ab, cb, dc]));
+    assert_eq!(m.get_path_edges(3, 0, &graph), Some(vec![dc, cb, ab]));
+    assert_eq!(m.get_path_edges(1, 2, &graph), Some(vec![cb]));
+    assert_eq!(m.get_path_edges(2, 2, &graph), Some(vec![]));
+    assert_eq!(m.get_path_edges(0, 4, &graph), None);
+}
+
+#[test]
+fn test_revalidate() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    let d = graph.add_node(3);
+    graph.add_edge(a, b, 1usize);
+    let bc = graph.add_edge(b, c, 1);
+    let cd = graph.add_edge(c, d, 1);
+    graph.add_edge(a, d, 5);
+
+    let m = floyd_warshall(&graph);
+    assert!(m.revalidate(&graph).is_empty());
+    assert!(m.get_path(0, 3).validate(&m, 0, 3, &graph));
+
+    // Changing a weight makes every path over that edge stale.
+    graph[bc] = 2;
+    assert_eq!(m.revalidate(&graph), vec![(0, 2), (0, 3), (1, 2), (1, 3)]);
+    assert!(!m.get_path(3, 0).validate(&m, 3, 0, &graph));
+    assert!(m.get_path(2, 3).validate(&m, 2, 3, &graph));
+
+    // So does removing an edge.
+    graph[bc] = 1;
+    graph.remove_edge(cd);
+    assert_eq!(m.revalidate(&graph), vec![(0, 3), (1, 3), (2, 3)]);
+    assert!(!m.get_path(0, 3).validate(&m, 0, 3, &graph));
+
+    // A heavier parallel edge doesn't change the weight of a path.
+    graph.add_edge(c, b, 7);
+    assert!(m.get_path(2, 0).validate(&m, 2, 0, &graph));
+    assert!(m.get_path(1, 1).validate(&m, 1, 1, &graph));
+}
+
+#[test]
+fn test_pairs_at_distance() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..6).map(|i| graph.add_node(i)).collect();
+    for i in 0..4 {
+        graph.add_edge(nodes[i], nodes[i + 1], 1usize);
+    }
+
+    let m = floyd_warshall(&graph);
+
+    let pairs: Vec<_> = m.pairs_at_distance(2).collect();
+    assert_eq!(pairs, vec![(0, 2), (1, 3), (2, 4)]);
+    assert_eq!(m.pairs_at_distance(4).collect::<Vec<_>>(), vec![(0, 4)]);
+    assert_eq!(m.pairs_at_distance(5).count(), 0);
+    assert_eq!(m.pairs_at_distance(0).count(), 0);
+}
+
+#[test]
+fn test_top_k_pairs_by_distance() {
+    use petgraph::Undirected;
+
+    // The weights 1..=5 make many distances tie.
+    let graph: Graph<usize, usize, Undirected> = random_graph(17, 25, 40).map(|_, &i| i, |_, &w| w.div_ceil(2));
+
+    let m = floyd_warshall(&graph);
+
+    let mut all: Vec<_> = (0..25)
+        .flat_map(|i| (i + 1..25).map(move |j| (i, j)))
+        .filter_map(|(i, j)| m.distance(i, j).map(|d| (i, j, d)))
+        .collect();
+    all.sort_by_key(|&(i, j, d)| (::std::cmp::Reverse(d), i, j));
+
+    for k in [0, 1, 5, 17, all.len(), all.len() + 10] {
+        let expected: Vec<_> = all.iter().take(k).cloned().collect();
+        assert_eq!(m.top_k_pairs_by_distance(k), expected);
+    }
+}
+
+#[test]
+fn test_distance_stats() {
+    use petgraph::Graph;
+    use DistanceStats;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..6).map(|i| graph.add_node(i)).collect();
+    for i in 0..4 {
+        graph.add_edge(nodes[i], nodes[i + 1], 1usize);
+    }
+
+    let m = floyd_warshall(&graph);
+
+    // The path over 5 nodes has 4 pairs at distance 1, 3 at 2, 2 at 3 and 1 at 4.
+    assert_eq!(
+        m.distance_stats(),
+        Some(DistanceStats {
+            pairs: 10,
+            min: 1,
+            max: 4,
+            mean: 2.0,
+            p25: 1,
+            median: 2,
+            p75: 3,
+            p90: 3,
+            p99: 4,
+        })
+    );
+
+    let mut empty = Graph::<(), usize, _>::new_undirected();
+    empty.add_node(());
+    empty.add_node(());
+    assert_eq!(floyd_warshall(&empty).distance_stats(), None);
+}
+
+#[test]
+fn test_pairs_within() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 10usize),
+        (nodes[1], nodes[2], 15),
+        (nodes[2], nodes[3], 30),
+    ]);
+
+    let m = floyd_warshall(&graph);
+
+    let pairs: Vec<_> = m.pairs_within(30).collect();
+    assert_eq!(pairs, vec![(0, 1, 10), (0, 2, 25), (1, 2, 15), (2, 3, 30)]);
+    assert_eq!(m.pairs_within(9).count(), 0);
+
+    assert_eq!(m.reachable_within(2, 25).collect::<Vec<_>>(), vec![(0, 25), (1, 15)]);
+    assert_eq!(m.reachable_within(4, 100).count(), 0);
+}
+
+#[test]
+fn test_nearest_matching() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = ["home", "shop", "hospital", "school", "hospital"]
+        .iter()
+        .map(|&w| graph.add_node(w))
+        .collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 5),
+        (nodes[0], nodes[3], 2),
+        (nodes[3], nodes[4], 4),
+    ]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.nearest_matching(0, |_, &w| w == "hospital"), Some((2, 6)));
+    assert_eq!(m.nearest_matching(3, |_, &w| w == "hospital"), Some((4, 4)));
+    assert_eq!(m.nearest_matching(2, |_, &w| w == "hospital"), Some((2, 0)));
+    assert_eq!(m.nearest_matching(0, |j, _| j != 0), Some((1, 1)));
+    assert_eq!(m.nearest_matching(0, |_, &w| w == "park"), None);
+}
+
+#[test]
+fn test_set_distances() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..6).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 3usize),
+        (nodes[1], nodes[2], 4),
+        (nodes[2], nodes[3], 1),
+        (nodes[4], nodes[5], 2),
+    ]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.distance_to_set(0, &[2, 3]), Some(7));
+    assert_eq!(m.distance_to_set(0, &[0, 3]), Some(0));
+    assert_eq!(m.distance_to_set(0, &[4, 5]), None);
+    assert_eq!(m.distance_to_set(0, &[]), None);
+
+    assert_eq!(m.distance_between_sets(&[0, 4], &[3, 5]), Some(2));
+    assert_eq!(m.distance_between_sets(&[0, 1], &[2, 3]), Some(4));
+    assert_eq!(m.distance_between_sets(&[0, 1], &[4, 5]), None);
+    assert_eq!(m.distance_between_sets(&[], &[4, 5]), None);
+}
+
+#[test]
+fn test_hausdorff() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..6).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 2),
+        (nodes[2], nodes[3], 3),
+        (nodes[3], nodes[4], 4),
+    ]);
+
+    let m = floyd_warshall(&graph);
+
+    assert_eq!(m.hausdorff(&[0, 4], &[0, 4]), Some(0));
+    assert_eq!(m.hausdorff(&[0], &[1, 4]), Some(10));
+    assert_eq!(m.hausdorff(&[1, 4], &[0]), Some(10));
+    assert_eq!(m.hausdorff(&[0, 3], &[1, 2]), Some(3));
+    assert_eq!(m.hausdorff(&[0], &[1, 5]), None);
+    assert_eq!(m.hausdorff(&[], &[1]), None);
+}
+
+#[test]
+fn test_sampled_statistics() {
+    use estimate_diameter;
+    use estimate_mean_distance;
+    use petgraph::Graph;
+
+    // A path 0 - 1 - ... - 9 with unit weights has the mean distance 11/3 and the diameter 9.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..10).map(|i| graph.add_node(i)).collect();
+    for w in nodes.windows(2) {
+        graph.add_edge(w[0], w[1], 1usize);
+    }
+
+    let m = floyd_warshall(&graph);
+
+    let mean = estimate_mean_distance(&m, 2000, 7).unwrap();
+    assert_eq!(mean.samples, 2000);
+    assert!(mean.lower <= mean.value && mean.value <= mean.upper);
+    assert!((mean.value - 11.0 / 3.0).abs() < 0.5);
+    assert_eq!(estimate_mean_distance(&m, 2000, 7), Some(mean));
+
+    let diameter = estimate_diameter(&m, 10, 3).unwrap();
+    assert!(diameter.lower <= 9.0 && 9.0 <= diameter.upper);
+    assert_eq!(diameter.value, diameter.lower);
+    assert!(diameter.upper.is_finite());
+    assert_eq!(estimate_diameter(&m, 0, 3), None);
+
+    // Without any connected pair, there is nothing to estimate.
+    let mut graph = Graph::<usize, usize, _>::new_undirected();
+    graph.add_node(0);
+    graph.add_node(1);
+    let m = floyd_warshall(&graph);
+    assert_eq!(estimate_mean_distance(&m, 100, 1), None);
+    assert_eq!(estimate_diameter(&m, 5, 1).unwrap().upper, f64::INFINITY);
+}
+
+#[test]
+fn test_landmarks() {
+    use petgraph::Graph;
+    use DistanceBounds;
+    use Landmarks;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..7).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 2usize),
+        (nodes[1], nodes[2], 3),
+        (nodes[2], nodes[3], 1),
+        (nodes[1], nodes[4], 4),
+        (nodes[4], nodes[3], 1),
+        (nodes[5], nodes[6], 1),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    let l = Landmarks::new(&graph, &[0, 3]);
+
+    assert_eq!(l.landmarks(), &[0, 3]);
+    assert_eq!(l.landmark_distance(1, 4), Some(1));
+    assert_eq!(l.landmark_distance(0, 5), None);
+
+    for i in 0..5 {
+        for j in 0..5 {
+            let b = l.bounds(i, j).unwrap();
+            let d = m.distance(i, j).unwrap();
+            assert!(b.lower <= d && d <= b.upper.unwrap());
+        }
+    }
+
+    assert_eq!(l.bounds(0, 3), Some(DistanceBounds { lower: 6, upper: Some(6) }));
+    assert_eq!(l.estimate(2, 4), Some(2));
+    assert_eq!(l.bounds(0, 5), None);
+    assert_eq!(l.bounds(5, 6), Some(DistanceBounds { lower: 0, upper: None }));
+}
+
+#[test]
+fn test_hub_labels() {
+    use hub_labels;
+    use hub_labels_with_order;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use Distances;
+
+    // A star with a center 0, so the center is the only hub needed besides the nodes themselves.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..6).map(|i| graph.add_node(i)).collect();
+    for i in 1..5 {
+        graph.add_edge(nodes[0], nodes[i], i);
+    }
+
+    let m = floyd_warshall(&graph);
+    let h = hub_labels(&m);
+    assert_eq!(h.label(3).collect::<Vec<_>>(), vec![(0, 3), (3, 0)]);
+    assert_eq!(h.get(2, 4), Some(6));
+    assert_eq!(h.get(2, 5), None);
+    assert_eq!(h.get(5, 5), Some(0));
+    assert_eq!(h.total_label_size(), 10);
+
+    // The labels give the exact distances for every order, including a bad one.
+    // Edges of weight 0 are included, too.
+    let graph: Graph<usize, usize, Undirected> = random_graph(17, 30, 60).map(|_, &i| i, |_, &w| w - 1);
+
+    let m = floyd_warshall(&graph);
+    let order: Vec<usize> = (0..30).rev().collect();
+    for h in [hub_labels(&m), hub_labels_with_order(&m, &order)].iter() {
+        for i in 0..30 {
+            for j in 0..30 {
+                assert_eq!(h.distance(i, j), Distances::distance(&m, i, j));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_approximate_distances() {
+    use approximate_distances;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    // A dense graph with weights from 1 to 100, so that the spanner can drop edges, and an isolated node.
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(5, 40, 220).map(|_, &i| i, |_, &w| w * w);
+    graph.add_node(40);
+
+    let m = floyd_warshall(&graph);
+
+    let exact = approximate_distances(&graph, 0.0);
+    for i in 0..41 {
+        for j in 0..41 {
+            assert_eq!(exact.get(i, j), m.distance(i, j));
+        }
+    }
+
+    let approx = approximate_distances(&graph, 0.5);
+    assert!(approx.spanner_edge_count() < exact.spanner_edge_count());
+    for i in 0..41 {
+        for j in 0..41 {
+            match (approx.get(i, j), m.distance(i, j)) {
+                (Some(a), Some(d)) => assert!(d <= a && a as f64 <= 1.5 * d as f64),
+                (a, d) => assert_eq!(a, d),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_path_cache() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    let mut cache = m.path_cache(2);
+
+    assert_eq!(cache.path(0, 3), Some(&[0, 1, 2, 3][..]));
+    assert_eq!(cache.path(3, 1), Some(&[3, 2, 1][..]));
+    assert_eq!(cache.path(0, 3), Some(&[0, 1, 2, 3][..]));
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 2));
+
+    // The pair (3, 1) is the least recently used one, so it is evicted.
+    assert_eq!(cache.path(2, 2), Some(&[2][..]));
+    assert_eq!(cache.path(0, 3).map(|p| p.len()), Some(4));
+    assert_eq!(cache.path(3, 1).map(|p| p.len()), Some(3));
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (2, 4, 2));
+
+    // Unreachable pairs are neither cached nor counted.
+    assert_eq!(cache.path(0, 4), None);
+    assert_eq!((cache.hits(), cache.misses()), (2, 4));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_query_many() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 2usize),
+        (nodes[1], nodes[2], 3),
+        (nodes[2], nodes[3], 4),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    let pairs = [(3, 0), (1, 1), (0, 4), (2, 1), (0, 3)];
+
+    assert_eq!(
+        m.query_many(&pairs),
+        vec![Some(9), Some(0), None, Some(3), Some(9)]
+    );
+    assert_eq!(
+        m.query_many_paths(&pairs),
+        vec![
+            Some(vec![3, 2, 1, 0]),
+            Some(vec![1]),
+            None,
+            Some(vec![2, 1]),
+            Some(vec![0, 1, 2, 3]),
+        ]
+    );
+
+    #[cfg(feature = "rayon")]
+    {
+        assert_eq!(m.query_many_par(&pairs), m.query_many(&pairs));
+        assert_eq!(m.query_many_paths_par(&pairs), m.query_many_paths(&pairs));
+    }
+}
+
+#[test]
+fn test_spawn() {
+    use floyd_warshall_spawn;
+    use petgraph::Graph;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::Context;
+    use std::task::Poll;
+    use std::task::Wake;
+    use std::thread;
+    use std::thread::Thread;
+    use Cancelled;
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..200).map(|i| graph.add_node(i)).collect();
+    for w in nodes.windows(2) {
+        graph.add_edge(w[0], w[1], 1usize);
+    }
+    let expected = floyd_warshall(&graph);
+
+    let m = floyd_warshall_spawn(graph.clone()).join().unwrap();
+    assert_eq!(m.get_path_len(0, 199), expected.get_path_len(0, 199));
+
+    // Poll the computation like an executor, which parks the thread until it is woken up.
+    let mut computation = floyd_warshall_spawn(graph.clone());
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let m = loop {
+        match ::std::pin::Pin::new(&mut computation).poll(&mut cx) {
+            Poll::Ready(m) => break m.unwrap(),
+            Poll::Pending => thread::park(),
+        }
+    };
+    assert!(computation.is_finished());
+    assert_eq!(m.get_path_len(3, 150), 147);
+    // The result was taken by the poll, so joining afterwards panics instead of returning a wrong result.
+    let joined = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| computation.join()));
+    assert!(joined.is_err());
+
+    // The computation may finish before the cancellation is seen, but then its result is complete.
+    let computation = floyd_warshall_spawn(graph);
+    computation.cancel();
+    match computation.join() {
+        Err(Cancelled) => {}
+        Ok(m) => assert_eq!(m.get_path_len(0, 199), 199),
+    }
+}
+
+#[test]
+fn test_observer() {
+    use floyd_warshall_with_options;
+    use petgraph::Graph;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use Observer;
+    use Options;
+    use Relaxation;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[0], nodes[2], 5),
+        (nodes[2], nodes[3], 1),
+    ]);
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let options = Options {
+        observer: Some(Observer::new({
+            let log = log.clone();
+            move |r| log.lock().unwrap().push(r)
+        })),
+        ..Options::default()
+    };
+
+    let m = floyd_warshall_with_options(&graph, &options);
+    let log = log.lock().unwrap();
+
+    assert_eq!(
+        log[0],
+        Relaxation {
+            k: 1,
+            i: 0,
+            j: 2,
+            old: Some(5),
+            new: 2,
+        }
+    );
+
+    // Every relaxation improves the pair, and the last one of every pair is its final distance.
+    let mut last = HashMap::new();
+    for r in log.iter() {
+        assert!(r.i < r.j && r.old.is_none_or(|old| r.new < old));
+        last.insert((r.i, r.j), r.new);
+    }
+    for (&(i, j), &d) in &last {
+        assert_eq!(m.get_path_len(i, j), d);
+    }
+    assert_eq!(last.len(), 3);
+    assert_eq!(m.get_path_len(0, 3), floyd_warshall(&graph).get_path_len(0, 3));
+}
+
+#[test]
+fn test_via_table() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[0], nodes[3], 5),
+    ]);
+
+    let m = floyd_warshall(&graph);
+
+    // The path 0 - 1 - 2 - 3 was last improved over 2, after the path 0 - 1 - 2 had been found over 1.
+    assert_eq!(m.via(0, 3), Some(2));
+    assert_eq!(m.via(3, 0), Some(2));
+    assert_eq!(m.via(0, 2), Some(1));
+    assert_eq!(m.via(2, 3), None);
+    assert_eq!(m.via(1, 1), None);
+    assert_eq!(m.via(0, 4), None);
+    assert_eq!(m.get_path(0, 3).via(), Some(2));
+}
+
+#[test]
+fn test_snapshots() {
+    use floyd_warshall_with_options;
+    use petgraph::Graph;
+    use Options;
+    use Snapshots;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 2)]);
+
+    let snapshots = Snapshots::new();
+    let options = Options {
+        snapshots: Some(snapshots.clone()),
+        ..Options::default()
+    };
+    let m = floyd_warshall_with_options(&graph, &options);
+
+    assert_eq!(snapshots.node_count(), 3);
+    assert_eq!(snapshots.len(), 3);
+    assert_eq!(
+        snapshots.distances(0),
+        vec![Some(0), Some(1), None, Some(1), Some(0), Some(2), None, Some(2), Some(0)]
+    );
+
+    // Only the iteration over node 1 finds a new path.
+    assert_eq!(snapshots.intermediate_node(1), 1);
+    assert_eq!(snapshots.changes(0), vec![]);
+    assert_eq!(snapshots.changes(1), vec![(0, 2, 3)]);
+    assert_eq!(snapshots.distances(2)[2], Some(3));
+    assert_eq!(snapshots.distances(3)[6], Some(m.get_path_len(2, 0)));
+
+    // A second run replaces the recording.
+    floyd_warshall_with_options(&graph, &options);
+    assert_eq!(snapshots.len(), 3);
+}
+
+#[test]
+fn test_event_log() {
+    use floyd_warshall_with_options;
+    use petgraph::Graph;
+    use std::fs;
+    use std::fs::File;
+    use EventLog;
+    use Options;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 2)]);
+
+    let path = ::std::env::temp_dir()
+        .join(format!("floyd-warshall-{}.jsonl", ::std::process::id()));
+    let log = EventLog::new(File::create(&path).unwrap());
+    let options = Options {
+        event_log: Some(log.clone()),
+        ..Options::default()
+    };
+
+    floyd_warshall_with_options(&graph, &options);
+    log.finish().unwrap();
+
+    let lines = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        lines.lines().collect::<Vec<_>>(),
+        vec![
+            r#"{"event":"start","nodes":3}"#,
+            r#"{"event":"edge","i":0,"j":1,"len":1}"#,
+            r#"{"event":"edge","i":1,"j":2,"len":2}"#,
+            r#"{"event":"iteration","k":0}"#,
+            r#"{"event":"iteration","k":1}"#,
+            r#"{"event":"relax","k":1,"i":0,"j":2,"old":null,"new":3}"#,
+            r#"{"event":"iteration","k":2}"#,
+        ]
+    );
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "generators")]
+fn test_generators() {
+    use generators;
+
+    let g = generators::grid(3, 4, 1, 0);
+    assert_eq!((g.node_count(), g.edge_count()), (12, 17));
+    assert_eq!(floyd_warshall(&g).get_path_len(0, 11), 5);
+
+    let g = generators::complete(6, 10, 1);
+    assert_eq!(g.edge_count(), 15);
+    assert!(g.raw_edges().iter().all(|e| (1..=10).contains(&e.weight)));
+
+    let g = generators::scale_free(50, 2, 5, 2);
+    assert_eq!(g.edge_count(), 3 + 47 * 2);
+
+    // The same seed gives the same graph.
+    let a = generators::gnp(30, 0.2, 100, 3);
+    let b = generators::gnp(30, 0.2, 100, 3);
+    assert_eq!(a.edge_count(), b.edge_count());
+    assert!(a
+        .raw_edges()
+        .iter()
+        .zip(b.raw_edges())
+        .all(|(x, y)| (x.source(), x.target(), x.weight) == (y.source(), y.target(), y.weight)));
+    assert_eq!(generators::gnp(30, 0.0, 100, 3).edge_count(), 0);
+}
+
+#[test]
+#[cfg(feature = "reference")]
+fn test_reference() {
+    use petgraph::Undirected;
+    use reference::apsp_bruteforce;
+
+    // The matrix uses the last one of parallel edges, which random_graph doesn't add.
+    let graph: Graph<usize, usize, Undirected> = random_graph(11, 25, 50).map(|_, &i| i, |_, &w| 2 * (w - 1));
+
+    let m = floyd_warshall(&graph);
+    let d = apsp_bruteforce(&graph);
+    for (i, row) in d.iter().enumerate() {
+        for (j, &len) in row.iter().enumerate() {
+            assert_eq!(len, m.distance(i, j), "{} {}", i, j);
+        }
+    }
+}
+
+#[test]
+fn test_cross_check() {
+    use cross_check;
+    use cross_check_sampled;
+    use petgraph::Graph;
+    use Mismatch;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 2usize),
+        (nodes[1], nodes[2], 2),
+        (nodes[0], nodes[2], 5),
+        (nodes[2], nodes[3], 1),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(cross_check(&m, &graph), vec![]);
+    assert_eq!(cross_check_sampled(&m, &graph, 3, 1), vec![]);
+
+    // A matrix of a graph, which changed afterwards, is reported.
+    graph.add_edge(nodes[3], nodes[4], 1);
+    let mismatches = cross_check(&m, &graph);
+    assert_eq!(mismatches.len(), 8);
+    assert!(mismatches.contains(&Mismatch {
+        i: 4,
+        j: 0,
+        matrix: None,
+        dijkstra: Some(6),
+    }));
+}
+
+#[test]
+fn test_graph_spec() {
+    use GraphSpec;
+
+    let spec = GraphSpec {
+        nodes: 4,
+        edges: vec![(0, 1, 3), (1, 2, 1), (2, 2, 9), (2, 7, 1), (1, 0, 2)],
+    };
+    let g = spec.to_graph();
+    assert_eq!((g.node_count(), g.edge_count()), (4, 2));
+
+    let m = floyd_warshall(&g);
+    assert_eq!(m.get_path_len(0, 2), 3);
+    assert!(!m.does_path_exist(0, 3));
+
+    #[cfg(feature = "arbitrary")]
+    {
+        use arbitrary::Arbitrary;
+        use arbitrary::Unstructured;
+        use rng::SplitMix64;
+
+        // Every generated spec is a valid input.
+        let mut rng = SplitMix64::new(9);
+        for _ in 0..20 {
+            let bytes: Vec<u8> = (0..512).map(|_| rng.next_u64() as u8).collect();
+            let spec = GraphSpec::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let g = spec.to_graph();
+            assert_eq!(floyd_warshall(&g).node_count(), spec.nodes);
+        }
+    }
+}
+
+#[test]
+fn test_routing_tables() {
+    use petgraph::Graph;
+    use Route;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[0], nodes[3], 5),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.next_hop(0, 3), Some(1));
+    assert_eq!(m.next_hop(3, 0), Some(2));
+    assert_eq!(m.next_hop(2, 3), Some(3));
+    assert_eq!(m.next_hop(1, 1), None);
+    assert_eq!(m.next_hop(0, 4), None);
+
+    let tables = m.routing_tables();
+    assert_eq!(tables.node_count(), 5);
+    assert!(tables.table(4).is_empty());
+    assert_eq!(
+        tables.table(3),
+        &[
+            Route { destination: 0, next_hop: 2, distance: 3 },
+            Route { destination: 1, next_hop: 2, distance: 2 },
+            Route { destination: 2, next_hop: 2, distance: 1 },
+        ]
+    );
+
+    #[cfg(feature = "export")]
+    {
+        let mut csv = Vec::new();
+        tables.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().next(), Some("node,destination,next_hop,distance"));
+        assert_eq!(csv.lines().nth(1), Some("0,1,1,1"));
+        assert_eq!(csv.lines().count(), 1 + 4 * 3);
+
+        let mut json = Vec::new();
+        tables.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with(r#"[{"node":0,"routes":[{"destination":1,"next_hop":1,"distance":1},"#));
+        assert!(json.ends_with(r#"{"node":4,"routes":[]}]"#));
+    }
+}
+
+#[test]
+fn test_routing_tables_ecmp() {
+    use petgraph::Graph;
+    use Route;
+
+    // A square, where 0 reaches 2 over 1 and over 3 with the same cost, and a longer direct edge between 0 and 2.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[3], nodes[0], 1),
+        (nodes[0], nodes[2], 3),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.next_hops(0, 2), vec![1, 3]);
+    assert_eq!(m.next_hops(0, 1), vec![1]);
+    assert!(m.next_hops(0, 0).is_empty());
+    assert!(m.next_hops(0, 2).contains(&m.next_hop(0, 2).unwrap()));
+
+    let tables = m.routing_tables_ecmp();
+    assert_eq!(
+        tables.table(0),
+        &[
+            Route { destination: 1, next_hop: 1, distance: 1 },
+            Route { destination: 2, next_hop: 1, distance: 2 },
+            Route { destination: 2, next_hop: 3, distance: 2 },
+            Route { destination: 3, next_hop: 3, distance: 1 },
+        ]
+    );
+    assert_eq!(m.routing_tables().table(0).len(), 3);
+}
+
+#[test]
+fn test_intervals() {
+    use floyd_warshall_intervals;
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    // Three weights per edge, one for each time interval.
+    let graph: Graph<usize, usize, Undirected> = random_graph(3, 30, 80);
+    let mut graph = graph.map(|_, &i| i, |e, &w| [w, 1 + (w + e.index()) % 10, 11 - w]);
+    graph.add_edge(NodeIndex::new(5), NodeIndex::new(5), [1, 1, 1]);
+
+    let m = floyd_warshall_intervals(&graph, 3, |w, t| w[t]);
+    assert_eq!(m.len(), 3);
+
+    for (t, m) in m.iter().enumerate() {
+        let expected = floyd_warshall(&graph.map(|_, &n| n, |_, w| w[t]));
+        for i in 0..30 {
+            for j in 0..30 {
+                assert_eq!(m.distance(i, j), expected.distance(i, j));
+            }
+        }
+        assert_eq!(m.get_path(0, 29).get_slice(), expected.get_path(0, 29).get_slice());
+    }
+
+    assert!(floyd_warshall_intervals(&graph, 0, |w, t| w[t]).is_empty());
+
+    // A directed graph with a self-loop, whose paths differ in both directions.
+    let mut graph = Graph::new();
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    graph.extend_with_edges([
+        (a, b, [1usize, 5]),
+        (b, c, [1, 5]),
+        (c, a, [1, 1]),
+        (a, a, [3, 3]),
+    ]);
+
+    let m = floyd_warshall_intervals(&graph, 2, |w, t| w[t]);
+    assert_eq!(m[0].distance(0, 2), Some(2));
+    assert_eq!(m[0].distance(2, 1), Some(2));
+    assert_eq!(m[1].distance(0, 2), Some(10));
+    assert_eq!(m[1].distance(2, 0), Some(1));
+    for m in &m {
+        assert_eq!(m.distance(0, 0), Some(0));
+        assert_eq!(m.distance(1, 1), Some(0));
+    }
+}
+
+#[test]
+fn test_layered_graph() {
+    use LayeredGraph;
+
+    // Layer 0 is walking along 0 - 1 - 2, layer 1 is a fast transit line between 0 and 2, which costs 2 to enter or leave.
+    let mut g = LayeredGraph::new(4, 2);
+    g.add_edge(0, 0, 1, 5);
+    g.add_edge(0, 1, 2, 5);
+    g.add_edge(1, 0, 2, 1);
+    g.add_transfer((0, 0), (1, 0), 2);
+    g.add_transfer((0, 2), (1, 2), 2);
+    assert_eq!(g.graph().node_count(), 8);
+
+    let m = g.solve();
+    assert_eq!((m.node_count(), m.layer_count()), (4, 2));
+    assert_eq!(m.distance((0, 0), (0, 2)), Some(5));
+    assert_eq!(m.path((0, 0), (0, 2)), Some(vec![(1, 0), (1, 2)]));
+    assert_eq!(m.path((0, 2), (0, 0)), Some(vec![(1, 2), (1, 0)]));
+    assert_eq!(m.distance((0, 1), (1, 2)), Some(7));
+    assert_eq!(m.distance((1, 0), (0, 0)), Some(2));
+    assert_eq!(m.path((0, 0), (0, 3)), None);
+    assert_eq!(m.path((0, 3), (0, 3)), Some(vec![]));
+
+    let d = m.layer_distances(0, 1);
+    assert_eq!(d.len(), 16);
+    assert_eq!(d[2], Some(3));
+    assert_eq!(d[4 + 2], Some(7));
+    assert_eq!(d[4 + 1], None);
+}
+
+#[test]
+fn test_k_shortest_paths() {
+    use k_shortest_paths;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    // Enumerates the lengths of all loopless paths from u to j by a depth-first search.
+    fn all_lengths(
+        adj: &[Vec<(usize, usize)>],
+        u: usize,
+        j: usize,
+        len: usize,
+        seen: &mut Vec<bool>,
+        out: &mut Vec<usize>,
+    ) {
+        if u == j {
+            out.push(len);
+            return;
+        }
+        seen[u] = true;
+        for &(v, w) in &adj[u] {
+            if !seen[v] {
+                all_lengths(adj, v, j, len + w, seen, out);
+            }
+        }
+        seen[u] = false;
+    }
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(17, 9, 18).map(|_, &i| i, |_, &w| w.div_ceil(2));
+
+    let mut adj = vec![Vec::new(); 9];
+    for e in graph.raw_edges() {
+        adj[e.source().index()].push((e.target().index(), e.weight));
+        adj[e.target().index()].push((e.source().index(), e.weight));
+    }
+
+    let m = floyd_warshall(&graph);
+    for &(i, j) in &[(0, 8), (3, 5), (1, 7)] {
+        let mut expected = Vec::new();
+        all_lengths(&adj, i, j, 0, &mut vec![false; 9], &mut expected);
+        expected.sort_unstable();
+        expected.truncate(10);
+
+        let paths = k_shortest_paths(i, j, 10, &graph, &m);
+        let lengths: Vec<usize> = paths.iter().map(|&(len, _)| len).collect();
+        assert_eq!(lengths, expected);
+
+        for (len, p) in &paths {
+            assert_eq!((p[0], p[p.len() - 1]), (i, j));
+            let mut distinct = p.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(distinct.len(), p.len());
+            let sum: usize = p
+                .windows(2)
+                .map(|e| adj[e[0]].iter().find(|&&(v, _)| v == e[1]).unwrap().1)
+                .sum();
+            assert_eq!(sum, *len);
+        }
+    }
+
+    assert_eq!(k_shortest_paths(2, 2, 3, &graph, &m), vec![(0, vec![2])]);
+    assert!(k_shortest_paths(0, 8, 0, &graph, &m).is_empty());
+    // The edges of a directed graph are only followed in their direction.
+    let mut cycle = Graph::new();
+    let nodes: Vec<_> = (0..3).map(|i| cycle.add_node(i)).collect();
+    cycle.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 1), (nodes[2], nodes[0], 1)]);
+    let m = floyd_warshall(&cycle);
+    assert_eq!(k_shortest_paths(0, 2, 3, &cycle, &m), vec![(2, vec![0, 1, 2])]);
+
+    cycle.add_edge(nodes[0], nodes[2], 3);
+    let m = floyd_warshall(&cycle);
+    assert_eq!(k_shortest_paths(0, 2, 3, &cycle, &m), vec![(2, vec![0, 1, 2]), (3, vec![0, 2])]);
+    assert_eq!(k_shortest_paths(2, 1, 3, &cycle, &m), vec![(2, vec![2, 0, 1])]);
+}
+
+#[test]
+fn test_edge_usage() {
+    use petgraph::Graph;
+
+    // A path 0 - 1 - 2 - 3 with a shortcut between 0 and 3, which is too long to be used.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    let e01 = graph.add_edge(nodes[0], nodes[1], 1usize);
+    let e12 = graph.add_edge(nodes[1], nodes[2], 1);
+    let e23 = graph.add_edge(nodes[2], nodes[3], 1);
+    let e03 = graph.add_edge(nodes[0], nodes[3], 5);
+
+    let m = floyd_warshall(&graph);
+    let usage = m.edge_usage(&graph);
+    assert_eq!(usage.len(), 4);
+    assert_eq!(usage[&e01], 3);
+    assert_eq!(usage[&e12], 4);
+    assert_eq!(usage[&e23], 3);
+    assert_eq!(usage[&e03], 0);
+
+    #[cfg(feature = "export")]
+    {
+        let mut csv = Vec::new();
+        m.write_edge_usage_csv(&graph, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "source,target,count\n0,1,3\n1,2,4\n2,3,3\n0,3,0\n"
+        );
+    }
+}
+
+#[test]
+fn test_node_usage() {
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[0], nodes[3], 5),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.node_usage(), vec![0, 2, 2, 0, 0]);
+
+    #[cfg(feature = "export")]
+    {
+        let mut csv = Vec::new();
+        m.write_node_usage_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "node,count\n0,0\n1,2\n2,2\n3,0\n4,0\n");
+    }
+
+    // The counts agree with the reconstructed paths.
+    // A self-loop isn't used by any path.
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(5, 40, 100);
+    graph.add_edge(NodeIndex::new(3), NodeIndex::new(3), 1);
+
+    let m = floyd_warshall(&graph);
+    let mut expected = vec![0; 40];
+    for i in 0..40 {
+        for j in i + 1..40 {
+            if m.does_path_exist(i, j) {
+                for &k in m.get_path(i, j).iter() {
+                    expected[k] += 1;
+                }
+            }
+        }
+    }
+    assert_eq!(m.node_usage(), expected);
+}
+
+#[test]
+fn test_shortest_path_dag() {
+    use petgraph::Graph;
+
+    // A square with two equal-cost paths from 0 to 2, a longer diagonal and an unreachable node.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[3], nodes[0], 1),
+        (nodes[0], nodes[2], 3),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    let dag = m.shortest_path_dag(&graph, 0);
+    assert_eq!(dag.source(), 0);
+    assert_eq!(dag.node_count(), 5);
+    assert_eq!(dag.distance(2), Some(2));
+    assert_eq!(dag.distance(4), None);
+    assert_eq!(dag.successors(0), &[1, 3]);
+    assert_eq!(dag.predecessors(2), &[1, 3]);
+    assert!(dag.predecessors(0).is_empty());
+    assert_eq!(dag.edge_count(), 4);
+    assert_eq!(dag.edges().collect::<Vec<_>>(), vec![(0, 1), (0, 3), (1, 2), (3, 2)]);
+    assert_eq!(dag.order(), vec![0, 1, 3, 2]);
+
+    let dag = m.shortest_path_dag(&graph, 4);
+    assert_eq!(dag.edge_count(), 0);
+    assert_eq!(dag.order(), vec![4]);
+}
+
+#[test]
+fn test_metric_closure() {
+    use petgraph::algo::min_spanning_tree;
+    use petgraph::data::Element;
+    use petgraph::Graph;
+
+    // A star around node 0, whose leaves 1, 2 and 3 are terminals. Node 4 isn't connected.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i * 10)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[0], nodes[2], 2),
+        (nodes[0], nodes[3], 3),
+    ]);
+
+    let m = floyd_warshall(&graph);
+    let closure = m.metric_closure(&[1, 2, 3, 4]);
+    assert_eq!(closure.raw_nodes().iter().map(|n| n.weight).collect::<Vec<_>>(), vec![10, 20, 30, 40]);
+    assert_eq!(closure.edge_count(), 3);
+
+    let tree: usize = min_spanning_tree(&closure)
+        .filter_map(|e| match e {
+            Element::Edge { weight, .. } => Some(weight),
+            Element::Node { .. } => None,
+        })
+        .sum();
+    assert_eq!(tree, 3 + 4);
+
+    assert_eq!(m.metric_closure(&[]).node_count(), 0);
+}
+
+#[test]
+fn test_state() {
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use FloydWarshallState;
+
+    // A self-loop must not overwrite the distance 0 on the diagonal.
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(23, 30, 80);
+    graph.add_edge(NodeIndex::new(9), NodeIndex::new(9), 3);
+
+    let mut state = FloydWarshallState::new(&graph);
+    assert_eq!((state.node_count(), state.iterations(), state.completed()), (30, 30, 0));
+    assert!(!state.step(12));
+    assert_eq!(state.completed(), 12);
+
+    // A state in the middle of the run can be persisted and resumed.
+    #[cfg(feature = "serde")]
+    let state: FloydWarshallState<usize> = {
+        let json = ::serde_json::to_string(&state).unwrap();
+        ::serde_json::from_str(&json).unwrap()
+    };
+    assert_eq!(state.completed(), 12);
+
+    // A damaged state is rejected, instead of panicking in finish.
+    #[cfg(feature = "serde")]
+    {
+        let json = ::serde_json::to_value(&state).unwrap();
+        let mut damaged = json.clone();
+        damaged["next"] = 31.into();
+        assert!(::serde_json::from_value::<FloydWarshallState<usize>>(damaged).is_err());
+        let mut damaged = json.clone();
+        damaged["weights"].as_array_mut().unwrap().pop();
+        assert!(::serde_json::from_value::<FloydWarshallState<usize>>(damaged).is_err());
+        let mut damaged = json.clone();
+        damaged["kernel"]["dist"].as_array_mut().unwrap().pop();
+        assert!(::serde_json::from_value::<FloydWarshallState<usize>>(damaged).is_err());
+        assert!(::serde_json::from_value::<FloydWarshallState<usize>>(json).is_ok());
+    }
+
+    let m = state.finish();
+    let expected = floyd_warshall(&graph);
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+        }
+    }
+    assert_eq!(m.get_path(0, 29).get_slice(), expected.get_path(0, 29).get_slice());
+
+    let mut state = FloydWarshallState::new(&graph);
+    assert!(state.step(100));
+    assert!(state.is_finished());
+}
+
+#[test]
+fn test_random_ties() {
+    use floyd_warshall_checkpointed;
+    use floyd_warshall_with_options;
+    use petgraph::Graph;
+    use resume_from_checkpoint;
+    use Checkpoint;
+    use Options;
+
+    // A square, where 0 reaches 2 over 1 or over 3 with the same cost.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[3], nodes[0], 1),
+    ]);
+
+    let with_seed = |seed| Options {
+        random_ties: Some(seed),
+        ..Options::default()
+    };
+
+    let mut over_1 = 0;
+    for seed in 0..200 {
+        let m = floyd_warshall_with_options(&graph, &with_seed(seed));
+        assert_eq!(m.get_path_len(0, 2), 2);
+        if m.get_path(0, 2).get_slice() == [1] {
+            over_1 += 1;
+        }
+    }
+    assert!(over_1 > 60 && over_1 < 140, "{} of 200 paths run over 1", over_1);
+
+    // On a grid with unit weights, every path is a valid shortest path, and the same seed gives the same paths.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..64).map(|i| graph.add_node(i)).collect();
+    for i in 0..64 {
+        if i % 8 < 7 {
+            graph.add_edge(nodes[i], nodes[i + 1], 1usize);
+        }
+        if i < 56 {
+            graph.add_edge(nodes[i], nodes[i + 8], 1);
+        }
+    }
+    let expected = floyd_warshall(&graph);
+    let m = floyd_warshall_with_options(&graph, &with_seed(7));
+    let again = floyd_warshall_with_options(&graph, &with_seed(7));
+    for i in 0..64 {
+        for j in 0..64 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            if i != j {
+                let mut path = vec![i];
+                path.extend(m.path_from(i, j).cloned());
+                path.push(j);
+                let len: usize = path.windows(2).map(|e| m.get_path_len(e[0], e[1])).sum();
+                assert!(path.windows(2).all(|e| m.via(e[0], e[1]).is_none()));
+                assert_eq!(len, m.get_path_len(i, j));
+                assert_eq!(m.get_path(i, j).get_slice(), again.get_path(i, j).get_slice());
+            }
+        }
+    }
+
+    // A run resumed from a checkpoint keeps the path counts, so it breaks the remaining ties like the uninterrupted run.
+    let path = ::std::env::temp_dir().join(format!("floyd-warshall-ties-{}.ckpt", ::std::process::id()));
+    let checkpoint = Checkpoint {
+        path: path.clone(),
+        every: 20,
+    };
+    floyd_warshall_checkpointed(&graph, &with_seed(7), &checkpoint).unwrap();
+    let resumed = resume_from_checkpoint(&graph, &with_seed(7), &checkpoint).unwrap();
+    for i in 0..64 {
+        for j in 0..64 {
+            assert_eq!(resumed.get_path(i, j).get_slice(), m.get_path(i, j).get_slice());
+        }
+    }
+    ::std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_builder() {
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use FloydWarshall;
+
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(29, 40, 90);
+    graph.add_edge(NodeIndex::new(9), NodeIndex::new(9), 3);
+
+    let expected = floyd_warshall(&graph);
+    let m = FloydWarshall::new(&graph).tile_size(7).random_ties(3).run();
+    let bounded = FloydWarshall::new(&graph).max_distance(8).run();
+    for i in 0..40 {
+        for j in 0..40 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            assert_eq!(bounded.distance(i, j), expected.distance(i, j).filter(|&d| d <= 8));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        let m = FloydWarshall::new(&graph).parallel(true).max_distance(8).run();
+        for i in 0..40 {
+            for j in 0..40 {
+                assert_eq!(m.distance(i, j), bounded.distance(i, j));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_progress() {
+    use floyd_warshall_checkpointed;
+    use floyd_warshall_with_progress;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use resume_from_checkpoint;
+    use std::fs;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use Checkpoint;
+    use FloydWarshall;
+    use Options;
+    use Progress;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(31, 30, 70);
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let progress = {
+        let calls = calls.clone();
+        Progress::new(move |done, total| calls.lock().unwrap().push((done, total)))
+    };
+
+    let expected = floyd_warshall(&graph);
+    let m = FloydWarshall::new(&graph).progress(progress.clone()).run();
+    assert_eq!(*calls.lock().unwrap(), (0..31).map(|done| (done, 30)).collect::<Vec<_>>());
+
+    // The progress is reported without changing the result, also together with an observed run.
+    let m2 = FloydWarshall::new(&graph)
+        .random_ties(5)
+        .progress(Progress::new(|_, _| {}))
+        .run();
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            assert_eq!(m2.distance(i, j), expected.distance(i, j));
+        }
+    }
+
+    calls.lock().unwrap().clear();
+    let m3 = {
+        let calls = calls.clone();
+        floyd_warshall_with_progress(&graph, move |done, total| calls.lock().unwrap().push((done, total)))
+    };
+    assert_eq!(*calls.lock().unwrap(), (0..31).map(|done| (done, 30)).collect::<Vec<_>>());
+    assert_eq!(m3.distance(3, 17), expected.distance(3, 17));
+
+    // A resumed run counts on from the iterations, which were finished before the checkpoint.
+    let path = ::std::env::temp_dir()
+        .join(format!("floyd-warshall-progress-{}.ckpt", ::std::process::id()));
+    let checkpoint = Checkpoint {
+        path: path.clone(),
+        every: 7,
+    };
+    floyd_warshall_checkpointed(&graph, &Options::default(), &checkpoint).unwrap();
+    calls.lock().unwrap().clear();
+    let options = Options {
+        progress: Some(progress),
+        ..Options::default()
+    };
+    let m4 = resume_from_checkpoint(&graph, &options, &checkpoint).unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![(28, 30), (29, 30), (30, 30)]);
+    assert_eq!(m4.distance(3, 17), expected.distance(3, 17));
+    fs::remove_file(&path).unwrap();
+
+    #[cfg(feature = "indicatif")]
+    {
+        use indicatif::ProgressBar;
+
+        let bar = ProgressBar::hidden();
+        let m = FloydWarshall::new(&graph).progress(Progress::bar(bar.clone())).run();
+        assert_eq!(bar.position(), 30);
+        assert_eq!(bar.length(), Some(30));
+        assert!(bar.is_finished());
+        assert_eq!(m.distance(0, 1), expected.distance(0, 1));
+    }
+}
+
+#[test]
+fn test_dist_oracle() {
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use std::thread;
+    use DistOracle;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(37, 30, 60);
+    let graph = graph.map(|_, &i| format!("n{}", i), |_, &w| w);
+
+    let expected = floyd_warshall(&graph);
+    let oracle = DistOracle::with_labels(floyd_warshall(&graph)).cache_paths(16);
+
+    let workers: Vec<_> = (0..4)
+        .map(|t| {
+            let oracle = oracle.clone();
+            thread::spawn(move || {
+                let mut answers = Vec::new();
+                for i in 0..30 {
+                    for j in 0..30 {
+                        let path = oracle.path((i + t) % 30, j).map(|p| p.to_vec());
+                        answers.push((oracle.distance((i + t) % 30, j), path));
+                    }
+                }
+                answers
+            })
+        })
+        .collect();
+
+    let mut cache = expected.path_cache(1);
+    for (t, worker) in workers.into_iter().enumerate() {
+        let answers = worker.join().unwrap();
+        for i in 0..30 {
+            for j in 0..30 {
+                let (distance, ref path) = answers[i * 30 + j];
+                let i = (i + t) % 30;
+                assert_eq!(distance, expected.distance(i, j));
+                assert_eq!(*path, cache.path(i, j).map(|p| p.to_vec()));
+            }
+        }
+    }
+
+    for i in 0..30 {
+        assert_eq!(oracle.within(i, 7), expected.reachable_within(i, 7).collect::<Vec<_>>());
+    }
+    assert_eq!(oracle.distance_by_label("n3", "n17"), expected.distance(3, 17));
+    assert_eq!(oracle.path_by_label("n3", "n17"), oracle.path(3, 17));
+    assert_eq!(oracle.within_by_label("n5", 9), oracle.within(5, 9));
+    assert_eq!(oracle.index_of("n30"), None);
+    assert_eq!(DistOracle::new(floyd_warshall(&graph)).index_of(&"n3".to_string()), None);
+}
+
+#[test]
+fn test_explain_path() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node("A");
+    let b = graph.add_node("B");
+    let c = graph.add_node("C");
+    let d = graph.add_node("D");
+    let e = graph.add_node("E");
+    let ab = graph.add_edge(a, b, 3usize);
+    let bc = graph.add_edge(b, c, 4usize);
+    graph.add_edge(a, c, 9usize);
+    let cd = graph.add_edge(c, d, 1usize);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(
+        m.explain_path(0, 3, &graph, |h| h.to_string()),
+        Some("1. A -> B: 3 (total 3)\n2. B -> C: 4 (total 7)\n3. C -> D: 1 (total 8)".to_string())
+    );
+
+    let mut edges = Vec::new();
+    let text = m.explain_path(3, 0, &graph, |h| {
+        edges.push(h.edge);
+        format!("{}->{}={}", h.from, h.to, h.distance)
+    });
+    assert_eq!(text, Some("3->2=1\n2->1=5\n1->0=8".to_string()));
+    assert_eq!(edges, vec![cd, bc, ab]);
+
+    assert_eq!(m.explain_path(1, 1, &graph, |h| h.to_string()), Some(String::new()));
+    assert_eq!(m.explain_path(0, e.index(), &graph, |h| h.to_string()), None);
+}
+
+#[test]
+fn test_repair() {
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use rng::SplitMix64;
+    use graph_diff;
+    use GraphDiff;
+
+    let mut small = Graph::new_undirected();
+    let a = small.add_node(0);
+    let b = small.add_node(1);
+    let c = small.add_node(2);
+    small.add_edge(a, b, 2usize);
+    small.add_edge(b, c, 3usize);
+    let mut changed = small.clone();
+    changed.update_edge(b, c, 5);
+    changed.update_edge(a, c, 1);
+    let d = changed.add_node(3);
+    changed.add_edge(c, d, 1);
+    assert_eq!(
+        graph_diff(&small, &changed),
+        GraphDiff {
+            added_nodes: vec![3],
+            removed_nodes: vec![],
+            added_edges: vec![(0, 2, 1), (2, 3, 1)],
+            removed_edges: vec![],
+            changed_edges: vec![(1, 2, 3, 5)],
+        }
+    );
+    assert!(graph_diff(&small, &small).is_empty());
+
+    let mut rng = SplitMix64::new(41);
+    for round in 0..20 {
+        let n = 40;
+        let old: Graph<usize, usize, Undirected> = random_graph(41 + round, n, 80).map(|_, &i| i, |_, &w| w - 1);
+        let nodes: Vec<_> = old.node_indices().collect();
+
+        let mut new = old.clone();
+        for _ in 0..3 {
+            let e = petgraph::graph::EdgeIndex::new(rng.below(new.edge_count()));
+            new[e] = rng.below(10);
+        }
+        for _ in 0..2 {
+            let e = petgraph::graph::EdgeIndex::new(rng.below(new.edge_count()));
+            new.remove_edge(e);
+        }
+        for _ in 0..2 {
+            let (u, v) = (rng.below(n), rng.below(n));
+            if u != v {
+                new.update_edge(nodes[u], nodes[v], rng.below(10));
+            }
+        }
+        if round % 3 == 1 {
+            let x = new.add_node(n);
+            new.add_edge(x, nodes[rng.below(n)], rng.below(10));
+        } else if round % 3 == 2 {
+            new.remove_node(nodes[n - 1]);
+        }
+
+        let mut m = floyd_warshall(&old);
+        let before: Vec<Option<usize>> = (0..n * n).map(|p| m.distance(p / n, p % n)).collect();
+        let changed = m.repair(&old, &new);
+        let expected = floyd_warshall(&new);
+
+        assert_eq!(m.node_count(), new.node_count());
+        for i in 0..new.node_count() {
+            assert_eq!(m.node_weight(i), expected.node_weight(i));
+            for j in 0..new.node_count() {
+                assert_eq!(m.distance(i, j), expected.distance(i, j));
+                assert!(m.get_path(i, j).validate(&m, i, j, &new));
+
+                let old_distance = if i < n && j < n { before[i * n + j] } else { None };
+                if i < j && m.distance(i, j) != old_distance {
+                    assert!(changed.binary_search(&(i, j)).is_ok());
+                }
+            }
+        }
+    }
+
+    // Over an edge of weight 0, the old and the new paths must not lead the reconstruction in circles.
+    let mut old = Graph::new_undirected();
+    let nodes: Vec<_> = (0..6).map(|i| old.add_node(i)).collect();
+    old.extend_with_edges([
+        (nodes[2], nodes[4], 1usize),
+        (nodes[0], nodes[4], 0),
+        (nodes[1], nodes[3], 2),
+        (nodes[1], nodes[2], 1),
+    ]);
+    let mut new = old.clone();
+    new.extend_with_edges([(nodes[3], nodes[5], 1), (nodes[0], nodes[3], 4)]);
+
+    let mut m = floyd_warshall(&old);
+    m.repair(&old, &new);
+    let expected = floyd_warshall(&new);
+    for i in 0..6 {
+        for j in 0..6 {
+            let mut x = j;
+            for _ in 0..6 {
+                match m.predecessor(i, x) {
+                    Some(p) if p != i => x = p,
+                    _ => break,
+                }
+            }
+            assert!(m.predecessor(i, x).is_none_or(|p| p == i));
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            assert!(m.get_path(i, j).validate(&m, i, j, &new));
+        }
+    }
+}
+
+#[test]
+fn test_directed() {
+    use floyd_warshall_with_options;
+    use petgraph::algo::dijkstra;
+    use petgraph::Graph;
+    use FloydWarshallState;
+    use Options;
+    use PathMatrix;
+    use Workspace;
+
+    // A one-way cycle, where going back means going all the way round.
+    let mut cycle = Graph::new();
+    let nodes: Vec<_> = (0..4).map(|i| cycle.add_node(i)).collect();
+    for i in 0..4 {
+        cycle.add_edge(nodes[i], nodes[(i + 1) % 4], 1usize);
+    }
+    let m = floyd_warshall(&cycle);
+    assert!(m.is_directed());
+    assert_eq!(m.distance(0, 3), Some(3));
+    assert_eq!(m.distance(3, 0), Some(1));
+    assert_eq!(m.get_path(0, 3).get_slice(), [1, 2]);
+    assert_eq!(m.get_path(3, 1).get_slice(), [0]);
+    assert_eq!(m.shortest_path(2, 1), Some((3, vec![&2, &3, &0, &1])));
+
+    // Edges of weight 0 and a self-loop, which must not change the diagonal.
+    let mut graph: Graph<usize, usize> = random_graph(43, 30, 90).map(|_, &i| i, |_, &w| w - 1);
+    let nodes: Vec<_> = graph.node_indices().collect();
+    graph.add_edge(nodes[6], nodes[6], 2);
+
+    let check = |m: &PathMatrix<usize>| {
+        for i in 0..30 {
+            let expected = dijkstra(&graph, nodes[i], None, |e| *e.weight());
+            for j in 0..30 {
+                assert_eq!(m.distance(i, j), expected.get(&nodes[j]).cloned());
+                if let Some((len, path)) = m.shortest_path(i, j) {
+                    let total: usize = path
+                        .windows(2)
+                        .map(|w| graph[graph.find_edge(nodes[*w[0]], nodes[*w[1]]).unwrap()])
+                        .sum();
+                    assert_eq!(total, len);
+                }
+            }
+        }
+    };
+
+    let m = floyd_warshall(&graph);
+    check(&m);
+    assert!((0..30).any(|i| (0..30).any(|j| m.distance(i, j) != m.distance(j, i))));
+
+    check(&floyd_warshall_with_options(
+        &graph,
+        &Options {
+            tile_size: Some(4),
+            ..Options::default()
+        },
+    ));
+    check(&floyd_warshall_with_options(
+        &graph,
+        &Options {
+            random_ties: Some(7),
+            ..Options::default()
+        },
+    ));
+
+    let mut state = FloydWarshallState::new(&graph);
+    state.step(11);
+    check(&state.finish());
+
+    // A workspace switches between both storage modes.
+    let mut workspace = Workspace::new();
+    check(workspace.run(&graph));
+    let mut undirected = Graph::new_undirected();
+    let a = undirected.add_node(0);
+    let b = undirected.add_node(1);
+    undirected.add_edge(a, b, 2usize);
+    assert_eq!(workspace.run(&undirected).distance(1, 0), Some(2));
+    check(workspace.run(&graph));
+
+    #[cfg(feature = "rayon")]
+    check(&::floyd_warshall_par(&graph));
+}
+
+#[test]
+fn test_measure() {
+    use floyd_warshall_measure;
+    use petgraph::algo::dijkstra;
+    use petgraph::visit::EdgeRef;
+    use petgraph::Graph;
+
+    // Travel times, which would lose their order when rounded to integers.
+    let mut graph = Graph::new_undirected();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+    let c = graph.add_node("c");
+    graph.add_edge(a, b, 0.4);
+    graph.add_edge(b, c, 0.4);
+    graph.add_edge(a, c, 0.9);
+    let m = floyd_warshall_measure(&graph);
+    assert_eq!(m.distance(0, 2), Some(0.4 + 0.4));
+    assert_eq!(m.distance(2, 0), m.distance(0, 2));
+    assert_eq!(m.get_path(2, 0).get_slice(), ["b"]);
+    assert_eq!(m.distance(1, 1), Some(0.0));
+
+    fn check<Ty: petgraph::EdgeType>(graph: &Graph<usize, f64, Ty>) {
+        let nodes: Vec<_> = graph.node_indices().collect();
+        let n = nodes.len();
+        let m = floyd_warshall_measure(graph);
+        assert_eq!(m.is_directed(), graph.is_directed());
+
+        // Turning the directed graph into an undirected one can make edges parallel.
+        let lightest = |u: usize, v: usize| {
+            graph
+                .edge_references()
+                .filter(|e| {
+                    let (s, t) = (e.source().index(), e.target().index());
+                    (s, t) == (u, v) || (!graph.is_directed() && (s, t) == (v, u))
+                })
+                .map(|e| *e.weight())
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        for i in 0..n {
+            let expected = dijkstra(graph, nodes[i], None, |e| *e.weight());
+            for (j, node) in nodes.iter().enumerate() {
+                match expected.get(node) {
+                    None => assert_eq!(m.distance(i, j), None),
+                    Some(&d) => {
+                        assert!((m.distance(i, j).unwrap() - d).abs() < 1e-9);
+
+                        // The path runs along edges of the graph and has the stored length.
+                        let mut path = vec![i];
+                        path.extend(m.get_path(i, j).iter().cloned());
+                        if i > j && !graph.is_directed() {
+                            path[1..].reverse();
+                        }
+                        path.push(j);
+                        let total: f64 = path
+                            .windows(2)
+                            .filter(|w| w[0] != w[1])
+                            .map(|w| lightest(w[0], w[1]))
+                            .sum();
+                        assert!((total - d).abs() < 1e-9);
+                    }
+                }
+            }
+        }
+    }
+
+    let graph: Graph<usize, usize> = random_graph(47, 30, 80);
+    let graph = graph.map(|_, &i| i, |e, &w| w as f64 / (1 + e.index() % 7) as f64);
+    check(&graph);
+    check(&graph.into_edge_type::<petgraph::Undirected>());
+
+    // A negative edge in a directed graph without a negative cycle.
+    let mut graph = Graph::new();
+    let a = graph.add_node(0);
+    let b = graph.add_node(1);
+    let c = graph.add_node(2);
+    graph.add_edge(a, b, 4i64);
+    graph.add_edge(b, c, -3);
+    graph.add_edge(a, c, 2);
+    let m = floyd_warshall_measure(&graph);
+    assert_eq!(m.distance(0, 2), Some(1));
+    assert_eq!(m.distance(2, 0), None);
+}
+
+#[test]
+fn test_apsp_unweighted() {
+    use apsp_unweighted;
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    // Every edge has the weight 1, a self-loop must not count as a step.
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(53, 40, 70).map(|_, &i| i, |_, _| 1);
+    graph.add_edge(NodeIndex::new(2), NodeIndex::new(2), 1);
+    let mut directed: Graph<usize, usize> = random_graph(53, 40, 70).map(|_, &i| i, |_, _| 1);
+    directed.add_edge(NodeIndex::new(2), NodeIndex::new(2), 1);
+
+    for (m, expected) in &[
+        (apsp_unweighted(&graph), floyd_warshall(&graph)),
+        (apsp_unweighted(&directed), floyd_warshall(&directed)),
+    ] {
+        assert_eq!(m.is_directed(), expected.is_directed());
+        for i in 0..40 {
+            for j in 0..40 {
+                assert_eq!(m.distance(i, j), expected.distance(i, j));
+                if let Some((len, path)) = m.shortest_path(i, j) {
+                    assert_eq!(path.len(), len + 1);
+                    for w in path.windows(2) {
+                        assert_eq!(m.distance(*w[0], *w[1]), Some(1));
+                    }
+                }
+            }
+        }
+    }
+
+    // The weights are ignored.
+    let mut weighted = Graph::new_undirected();
+    let a = weighted.add_node(0);
+    let b = weighted.add_node(1);
+    let c = weighted.add_node(2);
+    weighted.add_edge(a, b, 1usize);
+    weighted.add_edge(b, c, 1usize);
+    weighted.add_edge(a, c, 5usize);
+    assert_eq!(apsp_unweighted(&weighted).distance(0, 2), Some(1));
+}
+
+#[test]
+fn test_apsp_dijkstra() {
+    use apsp_dijkstra;
+    use PathMatrix;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    // Edges of length 0 must not make the reconstruction of the paths loop forever.
+    let graph: Graph<usize, usize, Undirected> = random_graph(57, 40, 90).map(|_, &i| i, |_, &w| (w - 1) / 2);
+    let directed: Graph<usize, usize> = random_graph(57, 40, 90).map(|_, &i| i, |_, &w| (w - 1) / 2);
+
+    let check = |m: &PathMatrix<usize>, expected: &PathMatrix<usize>| {
+        assert_eq!(m.is_directed(), expected.is_directed());
+        for i in 0..40 {
+            for j in 0..40 {
+                assert_eq!(m.distance(i, j), expected.distance(i, j));
+                if let Some((len, path)) = m.shortest_path(i, j) {
+                    assert_eq!(*path[0], i);
+                    assert_eq!(**path.last().unwrap(), j);
+                    let sum: usize = path.windows(2).map(|w| m.distance(*w[0], *w[1]).unwrap()).sum();
+                    assert_eq!(sum, len);
+                }
+            }
+        }
+    };
+
+    check(&apsp_dijkstra(&graph), &floyd_warshall(&graph));
+    check(&apsp_dijkstra(&directed), &floyd_warshall(&directed));
+    #[cfg(feature = "rayon")]
+    {
+        use apsp_dijkstra_par;
+        check(&apsp_dijkstra_par(&graph), &floyd_warshall(&graph));
+        check(&apsp_dijkstra_par(&directed), &floyd_warshall(&directed));
+    }
+}
+
+#[test]
+fn test_apsp() {
+    use apsp;
+    use choose_strategy;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use Strategy;
+
+    let graph = |seed: u64, n: usize, e: usize| -> Graph<usize, usize, Undirected> { random_graph(seed, n, e) };
+    let cases = vec![
+        (graph(59, 30, 60), Strategy::FloydWarshall),
+        (graph(60, 300, 400), Strategy::Dijkstra),
+        (graph(61, 100, 3000), Strategy::FloydWarshall),
+        (graph(62, 100, 150).map(|_, &i| i, |_, _| 3), Strategy::Bfs),
+    ];
+
+    for (graph, strategy) in &cases {
+        assert_eq!(choose_strategy(graph), *strategy);
+        let result = apsp(graph);
+        assert_eq!(result.strategy, *strategy);
+
+        let expected = floyd_warshall(graph);
+        let n = graph.node_count();
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(result.matrix.distance(i, j), expected.distance(i, j));
+            }
+        }
+    }
+
+    assert_eq!(Strategy::FloydWarshall.to_string(), "Floyd-Warshall");
+}
+
+#[test]
+fn test_compact_tiles() {
+    use floyd_warshall_compact;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use CompactDistances;
+    use FloydWarshall;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(67, 100, 250).map(|_, &i| i, |_, &w| 11 * (w - 1));
+
+    let expected = floyd_warshall(&graph);
+    let check = |compact: CompactDistances| {
+        for i in 0..100 {
+            for j in 0..100 {
+                assert_eq!(compact.get(i, j).map(usize::from), expected.distance(i, j));
+            }
+        }
+    };
+
+    check(floyd_warshall_compact(&graph).unwrap());
+    // Tile sizes, which don't divide the number of nodes, leave smaller tiles at the end.
+    for &tile in &[1, 7, 32, 100, 1000] {
+        check(FloydWarshall::new(&graph).tile_size(tile).run_compact().unwrap());
+    }
+    // Auto-tuning may pick any tile size and, with the feature simd, either kernel, but not change the distances.
+    check(FloydWarshall::new(&graph).auto_tune(true).run_compact().unwrap());
+}
+
+#[test]
+fn test_min_plus_row() {
+    use compact::INFINITY;
+    use compact::OVERFLOW;
+    use minplus::relax_row;
+    use rng::SplitMix64;
+
+    // Distances around the limits, so that sums overflow and missing paths are mixed with present ones.
+    let mut rng = SplitMix64::new(71);
+    let values = [0, 1, 7, 30000, 40000, OVERFLOW - 1, OVERFLOW, INFINITY];
+    let mut pick = || values[rng.below(values.len())];
+
+    for len in [0, 1, 15, 16, 17, 40, 100] {
+        let row: Vec<u16> = (0..len).map(|_| pick()).collect();
+        let from_k: Vec<u16> = (0..len).map(|_| pick()).collect();
+        for &to_k in &[0, 5, 30000, OVERFLOW] {
+            let mut expected = row.clone();
+            for (d, &f) in expected.iter_mut().zip(&from_k) {
+                if f != INFINITY {
+                    *d = (*d).min((to_k as u32 + f as u32).min(OVERFLOW as u32) as u16);
+                }
+            }
+
+            let mut actual = row.clone();
+            relax_row(&mut actual, &from_k, to_k);
+            assert_eq!(actual, expected);
+        }
+    }
+}
+
+#[cfg(feature = "gpu")]
+#[test]
+fn test_gpu() {
+    use floyd_warshall_gpu;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use GpuError;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(73, 50, 120);
+    let directed: Graph<usize, usize> = random_graph(73, 50, 120);
+
+    let gpu = match floyd_warshall_gpu(&graph) {
+        Ok(gpu) => gpu,
+        // Without a GPU, there is nothing to compare.
+        Err(GpuError::NoAdapter) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+    let expected = floyd_warshall(&graph);
+    for i in 0..50 {
+        for j in 0..50 {
+            assert_eq!(gpu.get(i, j), expected.distance(i, j));
+        }
+    }
+
+    let gpu = floyd_warshall_gpu(&directed).unwrap();
+    let expected = floyd_warshall(&directed);
+    for i in 0..50 {
+        for j in 0..50 {
+            assert_eq!(gpu.get(i, j), expected.distance(i, j));
+        }
+    }
+
+    let mut long = Graph::new_undirected();
+    let a = long.add_node(0);
+    let b = long.add_node(1);
+    let c = long.add_node(2);
+    long.extend_with_edges([(a, b, 3_000_000_000usize), (b, c, 3_000_000_000)]);
+    assert_eq!(
+        floyd_warshall_gpu(&long).unwrap_err(),
+        GpuError::Overflow(::DistanceOverflow::Path(0, 2))
+    );
+}
+
+#[test]
+fn test_out_of_core() {
+    use floyd_warshall_out_of_core;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use OutOfCore;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(79, 45, 100);
+    let directed: Graph<usize, usize> = random_graph(79, 45, 100);
+
+    // A budget of 3 KiB leaves room for tiles of 11 nodes, which don't divide the number of nodes.
+    // The largest budget holds the whole matrix in a single tile.
+    for &budget in &[0, 3 * 1024, 20 * 1024, 1 << 20] {
+        let config = OutOfCore::new(budget);
+        for (m, expected) in &[
+            (floyd_warshall_out_of_core(&graph, &config).unwrap(), floyd_warshall(&graph)),
+            (floyd_warshall_out_of_core(&directed, &config).unwrap(), floyd_warshall(&directed)),
+        ] {
+            assert_eq!(m.node_count(), 45);
+            let all = m.to_distance_matrix().unwrap();
+            for i in 0..45 {
+                for j in 0..45 {
+                    assert_eq!(m.get(i, j).unwrap(), expected.distance(i, j));
+                    assert_eq!(all.get(i, j), expected.distance(i, j));
+                }
+            }
+        }
+    }
+
+    // The file of the tiles is removed with the result.
+    let dir = ::std::env::temp_dir().join(format!("floyd-warshall-test-{}", ::std::process::id()));
+    ::std::fs::create_dir_all(&dir).unwrap();
+    let m = floyd_warshall_out_of_core(&graph, &OutOfCore { memory_budget: 4096, dir: dir.clone() }).unwrap();
+    assert_eq!(::std::fs::read_dir(&dir).unwrap().count(), 1);
+    drop(m);
+    assert_eq!(::std::fs::read_dir(&dir).unwrap().count(), 0);
+    ::std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn test_cancellable() {
+    use floyd_warshall_cancellable;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use Options;
+    use Progress;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(89, 30, 60);
+    let nodes: Vec<_> = graph.node_indices().collect();
+    let expected = floyd_warshall(&graph);
+
+    let cancel = AtomicBool::new(false);
+    let m = floyd_warshall_cancellable(&graph, &Options::default(), &cancel).unwrap();
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+        }
+    }
+
+    // The flag is set by the progress callback after 10 iterations, so the run stops before the 11th one.
+    let cancel = Arc::new(AtomicBool::new(false));
+    let options = Options {
+        progress: Some({
+            let cancel = cancel.clone();
+            Progress::new(move |done, _| {
+                if done == 10 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+        }),
+        ..Options::default()
+    };
+    let interrupted = floyd_warshall_cancellable(&graph, &options, &cancel).unwrap_err();
+    assert_eq!(interrupted.completed, 10);
+
+    // Every partial path is a real path with its stored length, but it may be longer than the shortest one.
+    let partial = interrupted.partial;
+    for i in 0..30 {
+        for j in 0..30 {
+            if let Some((len, path)) = partial.shortest_path(i, j) {
+                let sum: usize = path.windows(2).map(|w| expected.distance(*w[0], *w[1]).unwrap()).sum();
+                assert!(sum <= len);
+                assert!(expected.distance(i, j).unwrap() <= len);
+            }
+        }
+    }
+
+    // A run, which is cancelled before it starts, only knows the edges.
+    let interrupted = floyd_warshall_cancellable(&graph, &Options::default(), &cancel).unwrap_err();
+    assert_eq!(interrupted.completed, 0);
+    for i in 0..30 {
+        for j in 0..30 {
+            let edge = graph.find_edge(nodes[i], nodes[j]).map(|e| graph[e]);
+            assert_eq!(interrupted.partial.distance(i, j), if i == j { Some(0) } else { edge });
+        }
+    }
+}
+
+#[test]
+fn test_run_with_deadline() {
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use run_with_deadline;
+    use std::time::Duration;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(97, 30, 60);
+
+    let expected = floyd_warshall(&graph);
+    let m = run_with_deadline(&graph, Duration::from_secs(3600)).unwrap();
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+        }
+    }
+
+    // An exhausted budget stops the run before the first iteration.
+    let interrupted = run_with_deadline(&graph, Duration::from_secs(0)).unwrap_err();
+    assert_eq!(interrupted.completed, 0);
+    assert_eq!(interrupted.partial.node_count(), 30);
+}
+
+#[test]
+fn test_estimate_memory() {
+    use estimate_memory;
+    use petgraph::Graph;
+    use std::mem::size_of;
+    use MemoryMode;
+    use Path;
+    use PathMatrix;
+
+    let mut graph = Graph::<u64, usize, _>::new_undirected();
+    let mut directed = Graph::<u64, usize>::new();
+    for i in 0..100 {
+        graph.add_node(i);
+        directed.add_node(i);
+    }
+
+    // One entry per unordered pair and one shared by the diagonal, and the node weights.
+    let matrix = PathMatrix::<u64>::estimated_bytes(100);
+    assert_eq!(
+        matrix,
+        size_of::<PathMatrix<u64>>() + (1 + 100 * 99 / 2) * size_of::<Path<u64>>() + 100 * size_of::<u64>()
+    );
+    assert!(PathMatrix::<u64>::estimated_bytes(200) > 3 * matrix);
+
+    let paths = estimate_memory(&graph, MemoryMode::Paths);
+    assert_eq!(paths, matrix + 2 * 100 * 100 * size_of::<usize>() + 2 * 100 * size_of::<usize>());
+    assert!(estimate_memory(&directed, MemoryMode::Paths) > paths);
+    assert_eq!(estimate_memory(&graph, MemoryMode::Distances), 100 * 100 * size_of::<usize>());
+    assert_eq!(estimate_memory(&graph, MemoryMode::Compact), 100 * 100 * 2);
+    assert_eq!(estimate_memory(Graph::<u64, usize>::new(), MemoryMode::Compact), 0);
+}
+
+#[test]
+fn test_floyd_warshall_distances() {
+    use floyd_warshall_distances;
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use Distances;
+
+    // A node weight, which can't be cloned, isn't needed.
+    struct Opaque;
+
+    // Self-loops must not overwrite the distance 0 on the diagonal.
+    let mut graph: Graph<usize, usize, Undirected> = random_graph(101, 150, 400);
+    graph.add_edge(NodeIndex::new(8), NodeIndex::new(8), 4);
+    let mut directed: Graph<usize, usize> = random_graph(101, 150, 400);
+    directed.add_edge(NodeIndex::new(8), NodeIndex::new(8), 4);
+    let opaque = directed.map(|_, _| Opaque, |_, &w| w);
+
+    let d = floyd_warshall_distances(&graph);
+    let expected = floyd_warshall(&graph);
+    assert_eq!(d.node_count(), 150);
+    for i in 0..150 {
+        for j in 0..150 {
+            assert_eq!(d.get(i, j), expected.distance(i, j));
+        }
+    }
+
+    let d = floyd_warshall_distances(&directed);
+    let expected = floyd_warshall(&directed);
+    for i in 0..150 {
+        for j in 0..150 {
+            assert_eq!(Distances::distance(&d, i, j), expected.distance(i, j));
+        }
+    }
+    assert_eq!(floyd_warshall_distances(&opaque), d);
+}
+
+#[test]
+fn test_predecessor_matrix() {
+    use petgraph::graph::NodeIndex;
+    use petgraph::EdgeType;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    fn check<Ty: EdgeType>(g: &Graph<usize, usize, Ty>) {
+        let n = g.node_count();
+        let m = floyd_warshall(g);
+        for i in 0..n {
+            assert_eq!(m.predecessor(i, i), None);
+            for j in (0..n).filter(|&j| j != i) {
+                if !m.does_path_exist(i, j) {
+                    assert_eq!(m.predecessor(i, j), None);
+                    continue;
+                }
+
+                // Walking the predecessors back from j follows the edges of a shortest path and ends at i.
+                let mut walk = vec![];
+                let mut len = 0;
+                let mut x = j;
+                while x != i {
+                    let p = m.predecessor(i, x).unwrap();
+                    len += g[g.find_edge(NodeIndex::new(p), NodeIndex::new(x)).unwrap()];
+                    x = p;
+                    if x != i {
+                        walk.push(x);
+                    }
+                    assert!(walk.len() < n);
+                }
+                walk.reverse();
+                assert_eq!(len, m.get_path_len(i, j));
+                assert_eq!(m.path_from(i, j).cloned().collect::<Vec<_>>(), walk);
+            }
+        }
+    }
+
+    // Edges of weight 0 make many shortest paths tie.
+    let graph: Graph<usize, usize, Undirected> = random_graph(103, 40, 120).map(|_, &i| i, |_, &w| (w - 1) / 3);
+    let directed: Graph<usize, usize> = random_graph(103, 40, 120).map(|_, &i| i, |_, &w| (w - 1) / 3);
+
+    check(&graph);
+    check(&directed);
+}
+
+#[test]
+fn test_next_hop_matrix() {
+    use floyd_warshall_next_hops;
+    use petgraph::graph::NodeIndex;
+    use petgraph::EdgeType;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use Distances;
+    use Route;
+
+    fn check<Ty: EdgeType>(g: &Graph<usize, usize, Ty>) {
+        let n = g.node_count();
+        let hops = floyd_warshall_next_hops(g);
+        let m = floyd_warshall(g);
+        assert_eq!(hops.node_count(), n);
+
+        for i in 0..n {
+            assert_eq!(hops.next_hop(i, i), None);
+            for j in 0..n {
+                assert_eq!(hops.distance(i, j), m.distance(i, j));
+                assert_eq!(Distances::distance(&hops, i, j), m.distance(i, j));
+
+                // Following the next hops runs along the edges of a shortest path.
+                let mut len = 0;
+                let mut x = i;
+                let mut steps = 0;
+                while let Some(h) = hops.next_hop(x, j) {
+                    len += g[g.find_edge(NodeIndex::new(x), h).unwrap()];
+                    x = h.index();
+                    steps += 1;
+                    assert!(steps < n);
+                }
+                if m.does_path_exist(i, j) {
+                    assert_eq!((x, len), (j, m.get_path_len(i, j)));
+                } else {
+                    assert_eq!(x, i);
+                }
+            }
+        }
+
+        // The tables have the same destinations and distances, but the next hops may differ between equal-cost paths.
+        let tables = hops.routing_tables();
+        let expected = m.routing_tables();
+        for i in 0..n {
+            let entries = |t: &[Route]| t.iter().map(|r| (r.destination, r.distance)).collect::<Vec<_>>();
+            assert_eq!(entries(tables.table(i)), entries(expected.table(i)));
+            for r in tables.table(i) {
+                assert_eq!(hops.next_hop(i, r.destination), Some(NodeIndex::new(r.next_hop)));
+            }
+        }
+    }
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(107, 40, 100).map(|_, &i| i, |_, &w| w - 1);
+    let directed: Graph<usize, usize> = random_graph(107, 40, 100).map(|_, &i| i, |_, &w| w - 1);
+
+    check(&graph);
+    check(&directed);
+}
+
+#[test]
+fn test_path_iter() {
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(109, 40, 60);
+
+    let m = floyd_warshall(&graph);
+    for i in 0..40 {
+        assert_eq!(m.path_iter(i, i).collect::<Vec<_>>(), vec![i]);
+        for j in (0..40).filter(|&j| j != i) {
+            let nodes: Vec<_> = m.path_iter(i, j).collect();
+            if !m.does_path_exist(i, j) {
+                assert!(nodes.is_empty());
+                continue;
+            }
+
+            assert_eq!((nodes[0], nodes[nodes.len() - 1]), (i, j));
+            let len: usize = nodes
+                .windows(2)
+                .map(|e| graph[graph.find_edge(NodeIndex::new(e[0]), NodeIndex::new(e[1])).unwrap()])
+                .sum();
+            assert_eq!(len, m.get_path_len(i, j));
+        }
+    }
+
+    // Nothing was reconstructed on the way.
+    for i in 0..40 {
+        for j in (0..40).filter(|&j| m.via(i, j).is_some()) {
+            assert!(!m.entry(i, j).is_materialized());
+        }
+    }
+
+    // The nodes are the same as those of the reconstructed paths.
+    for i in 0..40 {
+        for j in (0..40).filter(|&j| j != i && m.does_path_exist(i, j)) {
+            let mut nodes: Vec<_> = m.path_iter(i, j).skip(1).collect();
+            nodes.pop();
+            assert_eq!(nodes, m.path_from(i, j).cloned().collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn test_floyd_warshall_indices() {
+    use floyd_warshall_indices;
+    use petgraph::graph::NodeIndex;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+
+    // The node weights are neither cloned nor cloneable.
+    #[derive(Debug, PartialEq)]
+    struct Heavy(usize);
+
+    let graph: Graph<usize, usize, Undirected> = random_graph(113, 30, 60).map(|_, &i| i, |_, &w| w - 1);
+    let heavy = graph.map(|_, &i| Heavy(i), |_, &w| w);
+
+    let m = floyd_warshall_indices(&heavy);
+    let expected = floyd_warshall(&graph);
+    assert_eq!(m.node_weight(7), Some(&NodeIndex::new(7)));
+    for i in 0..30 {
+        for j in 0..30 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            if i != j && m.does_path_exist(i, j) {
+                let ids: Vec<_> = m.get_path_iter(i, j).map(|a| a.index()).collect();
+                assert_eq!(ids, expected.get_path(i, j).get_slice());
+
+                let weights: Vec<_> = expected.path_from(i, j).map(|&k| Heavy(k)).collect();
+                assert_eq!(m.resolve(&heavy, i, j), weights.iter().collect::<Vec<_>>());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_full_path() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 1), (nodes[2], nodes[3], 1)]);
+
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.full_path(0, 3), Some(vec![&0, &1, &2, &3]));
+    assert_eq!(m.full_path(3, 0), Some(vec![&3, &2, &1, &0]));
+
+    // Adjacent nodes have no intermediate nodes, but their full path isn't empty.
+    assert!(m.get_path(1, 2).get_slice().is_empty());
+    assert_eq!(m.full_path(1, 2), Some(vec![&1, &2]));
+    assert_eq!(m.full_path(2, 2), Some(vec![&2]));
+    assert_eq!(m.full_path(0, 4), None);
+}
+
+#[test]
+fn test_hops() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..5).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[1], nodes[2], 1),
+        (nodes[2], nodes[3], 1),
+        (nodes[0], nodes[3], 5),
+    ]);
+
+    // The path over three edges is shorter than the single edge.
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.hops(0, 3), Some(3));
+    assert_eq!(m.hops(3, 0), Some(3));
+    assert_eq!(m.hops(1, 2), Some(1));
+    assert_eq!(m.hops(2, 2), Some(0));
+    assert_eq!(m.hops(0, 4), None);
+    assert!(!m.entry(0, 3).is_materialized());
+
+    let directed: Graph<usize, usize> = random_graph(127, 40, 120).map(|_, &i| i, |_, &w| (w - 1) / 2);
+
+    let m = floyd_warshall(&directed);
+    for i in 0..40 {
+        for j in (0..40).filter(|&j| j != i) {
+            let expected = if m.does_path_exist(i, j) {
+                Some(m.get_path(i, j).get_slice().len() + 1)
+            } else {
+                None
+            };
+            assert_eq!(m.hops(i, j), expected);
+        }
+    }
+}
+
+#[test]
+fn test_all_shortest_paths() {
+    use petgraph::Graph;
+    use FloydWarshall;
+
+    // A grid of 3 x 3 nodes, in which every monotone walk from a corner to the opposite one is a shortest path.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..10).map(|i| graph.add_node(i)).collect();
+    for r in 0..3 {
+        for c in 0..3 {
+            if c < 2 {
+                graph.add_edge(nodes[3 * r + c], nodes[3 * r + c + 1], 1usize);
+            }
+            if r < 2 {
+                graph.add_edge(nodes[3 * r + c], nodes[3 * r + c + 3], 1);
+            }
+        }
+    }
+
+    let m = floyd_warshall(&graph);
+    let paths = m.all_shortest_paths(&graph, 0, 8);
+    assert_eq!(paths.len(), 6);
+    assert_eq!(paths[0], vec![0, 1, 2, 5, 8]);
+    assert_eq!(paths[5], vec![0, 3, 6, 7, 8]);
+    for p in &paths {
+        assert_eq!(p.len() - 1, m.get_path_len(0, 8));
+    }
+    assert!(paths.contains(&m.full_path(0, 8).unwrap().into_iter().cloned().collect()));
+
+    assert_eq!(m.all_shortest_paths(&graph, 1, 2), vec![vec![1, 2]]);
+    assert_eq!(m.all_shortest_paths(&graph, 4, 4), vec![vec![4]]);
+    assert!(m.all_shortest_paths(&graph, 0, 9).is_empty());
+
+    // An edge of weight 0 ties in both directions, but only simple paths are enumerated.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([
+        (nodes[0], nodes[1], 1usize),
+        (nodes[0], nodes[2], 1),
+        (nodes[1], nodes[2], 0),
+        (nodes[2], nodes[3], 1),
+    ]);
+    let m = floyd_warshall(&graph);
+    assert_eq!(m.all_shortest_paths(&graph, 0, 3), vec![vec![0, 1, 2, 3], vec![0, 2, 3]]);
+
+    // A direct edge, which ties with a path over another node, is found, even if the stored path takes the detour.
+    let mut graph = Graph::new_undirected();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 1), (nodes[0], nodes[2], 2)]);
+    let mut detours = 0;
+    for seed in 0..16 {
+        let m = FloydWarshall::new(&graph).random_ties(seed).run();
+        if m.via(0, 2).is_some() {
+            detours += 1;
+        }
+        assert_eq!(m.all_shortest_paths(&graph, 0, 2), vec![vec![0, 1, 2], vec![0, 2]]);
+        assert_eq!(m.shortest_path_dag(&graph, 2).successors(2), [0, 1]);
+    }
+    assert!(detours > 0);
+}
+
+#[test]
+fn test_directed_queries() {
+    use floyd_warshall_with_options;
+    use hub_labels;
+    use petgraph::graph::NodeIndex;
+    use petgraph::visit::EdgeRef;
+    use petgraph::Graph;
+    use std::fs;
+    use std::fs::File;
+    use EventLog;
+    use Options;
+    use PathMatrixRef;
+    use Snapshots;
+
+    // A one-way triangle, where the way back always takes two edges.
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    let ab = graph.add_edge(nodes[0], nodes[1], 1usize);
+    let bc = graph.add_edge(nodes[1], nodes[2], 1);
+    let ca = graph.add_edge(nodes[2], nodes[0], 1);
+
+    let snapshots = Snapshots::new();
+    let path = ::std::env::temp_dir()
+        .join(format!("floyd-warshall-directed-{}.jsonl", ::std::process::id()));
+    let log = EventLog::new(File::create(&path).unwrap());
+    let options = Options {
+        snapshots: Some(snapshots.clone()),
+        event_log: Some(log.clone()),
+        ..Options::default()
+    };
+    let m = floyd_warshall_with_options(&graph, &options);
+    log.finish().unwrap();
+
+    assert_eq!(m.get_path_edges(0, 2, &graph), Some(vec![ab, bc]));
+    assert_eq!(m.get_path_edges(2, 1, &graph), Some(vec![ca, ab]));
+    let r = PathMatrixRef::new(&graph);
+    let edges: Vec<_> = r.path_edges(1, 0).unwrap().iter().map(|e| e.id()).collect();
+    assert_eq!(edges, vec![bc, ca]);
+
+    assert_eq!(m.connected_pairs().count(), 6);
+    assert_eq!(m.distance_stats().unwrap().pairs, 6);
+    assert_eq!(m.node_usage(), vec![1, 1, 1]);
+
+    let h = hub_labels(&m);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_eq!(h.get(i, j), m.distance(i, j));
+        }
+    }
+    assert_eq!(h.get(1, 0), Some(2));
+
+    // Edges of weight 0 and a self-loop.
+    let mut random: Graph<usize, usize> = random_graph(7, 20, 50).map(|_, &i| i, |_, &w| w - 1);
+    random.update_edge(NodeIndex::new(5), NodeIndex::new(5), 3);
+    let r = floyd_warshall(&random);
+    let h = hub_labels(&r);
+    for i in 0..20 {
+        for j in 0..20 {
+            assert_eq!(h.get(i, j), r.distance(i, j));
+        }
+    }
+
+    let d = snapshots.distances(snapshots.len());
+    let expected: Vec<_> = [0, 1, 2, 2, 0, 1, 1, 2, 0].iter().map(|&x| Some(x)).collect();
+    assert_eq!(d, expected);
+
+    let lines = fs::read_to_string(&path).unwrap();
+    assert_eq!(lines.lines().filter(|l| l.contains(r#""event":"edge""#)).count(), 3);
+    assert!(lines.contains(r#"{"event":"edge","i":2,"j":0,"len":1}"#));
+    fs::remove_file(&path).unwrap();
+
+    // A slower edge against the direction of the cycle doesn't hide the changed weight of its counterpart.
+    graph.add_edge(nodes[2], nodes[1], 5);
+    assert!(m.revalidate(&graph).is_empty());
+    graph[bc] = 3;
+    assert_eq!(m.revalidate(&graph), vec![(0, 2), (1, 0), (1, 2)]);
+}
+
+#[test]
+#[should_panic(expected = "directed")]
+fn test_directed_vitality() {
+    use node_vitality;
+    use petgraph::Graph;
+
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 1)]);
+    node_vitality(&graph, &floyd_warshall(&graph));
+}
+
+#[test]
+#[should_panic(expected = "directed")]
+fn test_directed_repair() {
+    use petgraph::Graph;
+
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..3).map(|i| graph.add_node(i)).collect();
+    graph.extend_with_edges([(nodes[0], nodes[1], 1usize), (nodes[1], nodes[2], 1)]);
+    let mut m = floyd_warshall(&graph);
+    let changed = graph.clone();
+    m.repair(&graph, &changed);
+}
+
+#[test]
+fn test_edge_list() {
+    use floyd_warshall_edges;
+    use petgraph::Graph;
+    use petgraph::Undirected;
+    use rng::SplitMix64;
+
+    // The same graph once as a petgraph graph and once as an edge list, with self-loops, in both modes.
+    let mut rng = SplitMix64::new(17);
+    let edges: Vec<(usize, usize, usize)> = (0..60).map(|_| (rng.below(20), rng.below(20), 1 + rng.below(50))).collect();
+
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..20).map(|i| graph.add_node(i)).collect();
+    for &(u, v, w) in &edges {
+        graph.add_edge(nodes[u], nodes[v], w);
+    }
+
+    let expected = floyd_warshall(&graph);
+    let m = floyd_warshall_edges(20, true, &edges);
+    assert!(m.is_directed());
+    for i in 0..20 {
+        for j in 0..20 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            assert_eq!(m.get_path(i, j).get_slice(), expected.get_path(i, j).get_slice());
+        }
+    }
+
+    let expected = floyd_warshall(&graph.into_edge_type::<Undirected>());
+    let m = floyd_warshall_edges(20, false, &edges);
+    assert!(!m.is_directed());
+    for i in 0..20 {
+        for j in 0..20 {
+            assert_eq!(m.distance(i, j), expected.distance(i, j));
+            assert_eq!(m.get_path(i, j).get_slice(), expected.get_path(i, j).get_slice());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Statistics on how often the stored shortest paths use the elements of the graph, for example for capacity planning.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+#[cfg(feature = "export")]
+use std::io;
+#[cfg(feature = "export")]
+use std::io::Write;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::EdgeRef;
+use petgraph::visit::GraphBase;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::NodeCount;
+
+use analysis::edge_key;
+use analysis::intermediate_nodes;
+use analysis::path_edges;
+use analysis::Adjacency;
+use PathMatrix;
+
+impl<T> PathMatrix<T> {
+    /// Returns the edges of the graph as ```(source, target, count)``` triples in the order of ```edge_references()```,
+    /// where ```count``` is the number of stored shortest paths, which traverse the edge.
+    fn edge_counts<G>(&self, g: G) -> Vec<(usize, usize, usize)>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        assert_eq!(
+            self.node_count(),
+            g.node_count(),
+            "the graph doesn't belong to the matrix"
+        );
+
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut edges = Vec::new();
+        for (i, j) in self.connected_pairs() {
+            edges.clear();
+            path_edges(self, i, j, &mut edges);
+            for &e in &edges {
+                *counts.entry(e).or_insert(0) += 1;
+            }
+        }
+
+        let adj = Adjacency::new(g);
+        let stored = adj.stored_edges(self);
+        adj.edges()
+            .iter()
+            .enumerate()
+            .map(|(pos, &(u, v, _))| {
+                let key = edge_key(self, u, v);
+
+                // If there are parallel edges, only the one, which is stored in the matrix, carries any paths.
+                let count = if stored.get(&key) == Some(&pos) {
+                    counts.get(&key).cloned().unwrap_or(0)
+                } else {
+                    0
+                };
+                (u, v, count)
+            })
+            .collect()
+    }
+
+    /// Counts for every edge of the graph, how many of the stored shortest paths traverse it.
+    /// Every pair of distinct connected nodes contributes its path once, and unused edges are mapped to 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the graph has a different number of nodes than the matrix.
+    pub fn edge_usage<G>(&self, g: G) -> HashMap<G::EdgeId, usize>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+        G::EdgeId: Eq + Hash,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        g.edge_references()
+            .zip(self.edge_counts(g))
+            .map(|(e, (_, _, count))| (e.id(), count))
+            .collect()
+    }
+
+    /// Writes the usage of every edge, as counted by ```edge_usage```, as CSV with the header ```source,target,count```.
+    /// The edges are written in the order of ```edge_references()```.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the graph has a different number of nodes than the matrix.
+    #[cfg(feature = "export")]
+    pub fn write_edge_usage_csv<G, W>(&self, g: G, mut w: W) -> io::Result<()>
+    where
+        G: Data + GraphBase<NodeId = NodeIndex> + NodeCount + IntoEdgeReferences,
+        G::EdgeWeight: Clone + Into<usize>,
+        W: Write,
+    {
+        writeln!(w, "source,target,count")?;
+        for (u, v, count) in self.edge_counts(g) {
+            writeln!(w, "{},{},{}", u, v, count)?;
+        }
+        Ok(())
+    }
+
+    /// Counts for every node, how many of the stored shortest paths pass through it, not counting the paths, which start or end at it.
+    /// Every pair of distinct connected nodes contributes its path once. The paths are walked along the recorded intermediate nodes,
+    /// so none of them is reconstructed.
+    pub fn node_usage(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.node_count()];
+        let mut nodes = Vec::new();
+        for (i, j) in self.connected_pairs() {
+            nodes.clear();
+            intermediate_nodes(self, i, j, &mut nodes);
+            for &k in &nodes {
+                counts[k] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Writes the usage of every node, as counted by ```node_usage```, as CSV with the header ```node,count```.
+    #[cfg(feature = "export")]
+    pub fn write_node_usage_csv<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "node,count")?;
+        for (i, count) in self.node_usage().into_iter().enumerate() {
+            writeln!(w, "{},{}", i, count)?;
+        }
+        Ok(())
+    }
+}
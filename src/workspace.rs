@@ -0,0 +1,72 @@
+//! A reusable workspace for repeated runs of the algorithm.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Data;
+use petgraph::visit::GraphBase;
+use petgraph::visit::GraphProp;
+use petgraph::visit::IntoEdgeReferences;
+use petgraph::visit::IntoNodeIdentifiers;
+use petgraph::visit::IntoNodeReferences;
+use petgraph::visit::NodeCount;
+
+use kernel::Kernel;
+use Options;
+use PathMatrix;
+
+/// A workspace, which owns the buffers of a ```PathMatrix``` and can be run on many graphs one after another.
+/// Running it on a graph of the same or a smaller size as before doesn't allocate new buffers,
+/// which matters, if the algorithm is run thousands of times on similarly sized graphs.
+#[derive(Debug)]
+pub struct Workspace<T> {
+    m: PathMatrix<T>,
+    kernel: Kernel,
+}
+
+impl<T: Clone> Workspace<T> {
+    /// Creates an empty workspace. The buffers are allocated by the first run.
+    pub fn new() -> Workspace<T> {
+        Workspace::with_capacity(0)
+    }
+
+    /// Creates a workspace, whose buffers are large enough for graphs with up to ```n``` nodes.
+    pub fn with_capacity(n: usize) -> Workspace<T> {
+        Workspace {
+            m: PathMatrix::new(n),
+            kernel: Kernel::default(),
+        }
+    }
+
+    /// Computes the shortest paths between every two nodes in the graph like ```floyd_warshall```, but reuses the buffers of this workspace.
+    /// The returned matrix stays valid until the next run.
+    pub fn run<G>(&mut self, g: G) -> &PathMatrix<T>
+    where
+        G: Data<NodeWeight = T>
+            + GraphBase<NodeId = NodeIndex>
+            + NodeCount
+            + IntoNodeIdentifiers<NodeId = NodeIndex>
+            + IntoNodeReferences
+            + IntoEdgeReferences
+            + GraphProp,
+        G::EdgeWeight: Clone + Into<usize>,
+    {
+        self.m.reset(g.node_count());
+        ::compute(g, &Options::default(), &mut self.m, &mut self.kernel);
+        &self.m
+    }
+
+    /// Returns the matrix computed by the last run.
+    pub fn matrix(&self) -> &PathMatrix<T> {
+        &self.m
+    }
+
+    /// Consumes the workspace and returns the matrix computed by the last run.
+    pub fn into_matrix(self) -> PathMatrix<T> {
+        self.m
+    }
+}
+
+impl<T: Clone> Default for Workspace<T> {
+    fn default() -> Workspace<T> {
+        Workspace::new()
+    }
+}